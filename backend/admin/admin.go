@@ -0,0 +1,81 @@
+package admin
+
+import (
+	"backend/audit"
+	"backend/tools"
+	"crypto/subtle"
+	"net/http"
+	"strconv"
+	"strings"
+)
+
+// Token The secret token required to use the admin API. The admin API is disabled
+// unless QUIZLER_ADMIN_TOKEN is set
+var Token = tools.EnvOrDefault("QUIZLER_ADMIN_TOKEN", "")
+
+// MaxAuditLimit The maximum number of audit entries returned by a single request
+const MaxAuditLimit = 500
+
+// Enabled returns whether an admin token has been configured
+func Enabled() bool {
+	return Token != ""
+}
+
+// Authorized checks whether the request has the admin token as its bearer token
+func Authorized(request *http.Request) bool {
+	header := request.Header.Get("Authorization")
+	if !strings.HasPrefix(header, "Bearer ") { // If the request doesn't have a bearer token
+		return false
+	}
+	token := strings.TrimPrefix(header, "Bearer ")
+	return subtle.ConstantTimeCompare([]byte(token), []byte(Token)) == 1
+}
+
+// Handler wraps the provided handler so that it's only invoked for requests that
+// are authorized. Every authorized call is recorded in the audit log
+func Handler(action string, handler http.HandlerFunc) http.HandlerFunc {
+	return func(writer http.ResponseWriter, request *http.Request) {
+		if !Authorized(request) {
+			tools.WriteError(writer, http.StatusUnauthorized, "Invalid admin token")
+			return
+		}
+		audit.Record("admin ("+tools.ClientAddress(request)+")", action, request.URL.Query().Get("game"), "")
+		handler(writer, request)
+	}
+}
+
+// HandleAudit HTTP handler for GET /api/admin/audit. Responds with the most recent
+// audit entries optionally filtered by the game query parameter and limited by
+// the limit query parameter
+func HandleAudit(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodGet { // Only accept GET requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	query := request.URL.Query()
+	limit, err := strconv.Atoi(query.Get("limit"))
+	if err != nil || limit < 1 || limit > MaxAuditLimit { // Use the maximum for missing or invalid limits
+		limit = MaxAuditLimit
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Entries []audit.Entry `json:"entries"` // The matching audit entries newest first
+	}{Entries: audit.Query(query.Get("game"), limit)})
+}
+
+// HandleErasePlayer HTTP handler for DELETE /api/admin/players/{name}. Removes
+// every audit entry mentioning the player name or client address so operators can
+// act on deletion requests. Responds with the number of entries removed
+func HandleErasePlayer(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodDelete { // Only accept DELETE requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	subject := strings.TrimSpace(strings.TrimPrefix(request.URL.Path, "/api/admin/players/"))
+	if subject == "" {
+		tools.WriteError(writer, http.StatusBadRequest, "Missing player name")
+		return
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Removed int `json:"removed"` // The number of audit entries removed
+	}{Removed: audit.Erase(subject)})
+}
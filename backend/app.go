@@ -1,14 +1,35 @@
 package main
 
 import (
+	"backend/admin"
+	"backend/audit"
+	"backend/cli"
+	"backend/discovery"
 	"backend/game"
+	"backend/generator"
+	"backend/media"
+	"backend/moderation"
 	. "backend/net"
+	"backend/quiz"
+	"backend/speech"
+	"backend/stats"
 	"backend/tools"
+	"backend/upload"
 	_ "embed"
+	"encoding/json"
+	"errors"
 	"fmt"
 	"github.com/jacobtread/gowsps"
 	"log"
 	"net/http"
+	"os"
+	"os/signal"
+	"strconv"
+	"strings"
+	"sync"
+	"syscall"
+	"time"
+	"unicode/utf8"
 )
 
 const (
@@ -32,12 +53,61 @@ const (
 var appIndex []byte
 
 func main() {
-	address := tools.EnvOrDefault("QUIZLER_ADDRESS", "0.0.0.0") // Retrieve the address environment variable
-	port := tools.EnvOrDefault("QUIZLER_PORT", "8080")          // Retrieve the port environment variable
-	host := fmt.Sprintf("%s:%s", address, port)                 // Create a host url from ADDRESS:PORT
+	if len(os.Args) > 1 { // If a command was provided run it instead of the server
+		if code, serve := cli.Run(os.Args[1:]); !serve {
+			os.Exit(code)
+		}
+	}
+
+	addresses := tools.SplitList(tools.EnvOrDefault("QUIZLER_ADDRESS", "0.0.0.0")) // Retrieve the addresses environment variable
+	port := tools.EnvOrDefault("QUIZLER_PORT", "8080")                             // Retrieve the port environment variable
 
 	fmt.Printf(Intro, Version, port) // Print the intro message
 
+	if tools.Offline { // If the server must run without network access
+		if err := CheckOffline(appIndex); err != nil {
+			log.Fatal("Offline self-check failed: ", err)
+		}
+		log.Printf("Offline mode enabled. No outbound network requests will be made")
+	}
+
+	portNumber, _ := strconv.Atoi(port)
+	discovery.Start(Version, portNumber) // Announce the server on the local network
+	PrintJoinUrls(discovery.Server.Urls) // Print the urls players can join on
+	if cli.Hosting != nil {              // If the host command prepared a quiz
+		hostUrl := "http://localhost:" + port + "/create?quiz=" + cli.Hosting.Quiz
+		fmt.Printf("  Host the quiz on:\n    %s\n\n", hostUrl)
+		if cli.Hosting.Open {
+			go cli.OpenBrowser(hostUrl)
+		}
+	}
+	http.HandleFunc("/api/discovery", tools.Cors(discovery.HandleDiscovery))
+	http.HandleFunc("/api/quiz/validate", tools.Cors(quiz.HandleValidate))
+	http.HandleFunc("/api/quiz/json", tools.Cors(quiz.HandleCreate))
+	http.HandleFunc("/api/quiz/", tools.Cors(quiz.HandleThumbnail))
+	http.HandleFunc("/api/game/", tools.Cors(game.HandleGame))
+	http.HandleFunc("/api/profiles", tools.Cors(game.HandleProfiles))
+	http.HandleFunc("/api/media", tools.Cors(media.HandleMedia))
+	http.HandleFunc("/api/media/", tools.Cors(media.HandleMedia))
+	http.HandleFunc("/api/upload", tools.Cors(upload.HandleUpload))
+	http.HandleFunc("/api/upload/", tools.Cors(upload.HandleUpload))
+	// Remove uploads that were never finished or used
+	tools.Schedule(tools.Job{Name: "uploads", Interval: time.Minute, Jitter: 10 * time.Second, Run: upload.Expire})
+	// Remove uploaded images that are no longer used
+	tools.Schedule(tools.Job{Name: "media", Interval: time.Minute, Jitter: 10 * time.Second, Run: func() error {
+		media.ExpireMedia()
+		return nil
+	}})
+	if game.ArchiveEnabled() { // If finished game timelines are archived
+		if err := os.MkdirAll(game.ArchiveDir, 0755); err != nil {
+			log.Fatal("Failed to create the archive directory: ", err)
+		}
+		// Move old finished game timelines out of memory into the archive
+		tools.Schedule(tools.Job{Name: "archive", Interval: time.Hour, Jitter: time.Minute, Run: game.CompactTimelines})
+	}
+	// Remove audit entries older than the retention
+	tools.Schedule(tools.Job{Name: "audit", Interval: time.Hour, Jitter: time.Minute, Run: audit.Expire})
+
 	// Create a handler for handling http requests
 	http.HandleFunc("/", func(writer http.ResponseWriter, request *http.Request) {
 		if request.URL.Path == "/ws" { // If the user accessed the websocket endpoint
@@ -48,47 +118,244 @@ func main() {
 		}
 	})
 
-	err := http.ListenAndServe(host, nil) // Listen on the provided address
-	if err != nil {                       // If we encountered an error
+	if generator.Enabled() { // If a question generation provider is configured
+		http.HandleFunc("/api/generate/questions", tools.Cors(generator.HandleQuestions))
+		http.HandleFunc("/api/generate/distractors", tools.Cors(generator.HandleDistractors))
+		log.Printf("Question generation enabled using provider '%s'", generator.Url)
+	}
+	if speech.Enabled() { // If a text-to-speech provider is configured
+		log.Printf("Question audio enabled using provider '%s'", speech.Url)
+	}
+
+	if game.ProfilesFile != "" { // If the server defines its own settings profiles
+		if err := game.LoadProfiles(game.ProfilesFile); err != nil {
+			log.Fatal("Failed to load settings profiles: ", err)
+		}
+	}
+
+	if err := game.SetupNameFilter(game.NameFilterKind, game.NameFilterConfig); err != nil {
+		log.Fatal("Failed to set up the name filter: ", err)
+	}
+	if !game.IsDuplicateMode(game.DuplicateAnswers) {
+		log.Fatalf("Unknown duplicate answer mode '%s' expected error or keep", game.DuplicateAnswers)
+	}
+	if stats.Enabled() { // If the server collects usage statistics
+		if err := stats.Load(); err != nil {
+			log.Fatal("Failed to load usage statistics: ", err)
+		}
+	}
+	if moderation.Enabled() { // If a moderation webhook is configured
+		if !moderation.IsVerdict(moderation.Fallback) {
+			log.Fatalf("Unknown moderation fallback '%s' expected allow, flag or hold", moderation.Fallback)
+		}
+		log.Printf("Moderation enabled using webhook '%s' with fallback '%s'", moderation.Url, moderation.Fallback)
+	}
+
+	quiz.Stored.Scan() // Load the stored quizzes
+	// Reload the stored quizzes whenever their files change
+	quiz.Stored.Watch(time.Duration(tools.EnvIntOrDefault("QUIZLER_QUIZ_POLL", 2)) * time.Second)
+
+	if admin.Enabled() { // If an admin token is configured
+		http.HandleFunc("/api/admin/audit", tools.Cors(admin.Handler("audit.query", admin.HandleAudit)))
+		http.HandleFunc("/api/admin/quiz/import", tools.Cors(admin.Handler("quiz.import", quiz.HandleImport)))
+		http.HandleFunc("/api/admin/players/", tools.Cors(admin.Handler("players.erase", admin.HandleErasePlayer)))
+		http.HandleFunc("/api/admin/stats", tools.Cors(admin.Handler("stats.query", stats.HandleStats)))
+		log.Printf("Admin API enabled")
+	}
+
+	go StopOnSignal() // Stop the background jobs when the server is asked to stop
+
+	err := Listen(addresses, port) // Listen on the provided addresses
+	if err != nil {                // If we encountered an error
 		log.Fatal("An error occurred", err) // Print out the error
 	}
 }
 
+// StopOnSignal waits for an interrupt or termination signal then waits for any
+// background jobs that are running to finish before exiting. The timelines of
+// finished games still in memory are archived if archiving is enabled
+func StopOnSignal() {
+	signals := make(chan os.Signal, 1)
+	signal.Notify(signals, os.Interrupt, syscall.SIGTERM)
+	<-signals
+	log.Printf("Stopping server")
+	if !tools.StopJobs(10 * time.Second) {
+		log.Printf("Background jobs didn't finish in time")
+	}
+	if game.ArchiveEnabled() { // Keep the timelines that haven't been archived yet
+		game.ArchiveFinished()
+	}
+	os.Exit(0)
+}
+
+// Limits the number of concurrent websocket connections from each client address
+var connections = tools.NewConnectionLimiter(tools.EnvIntOrDefault("QUIZLER_MAX_CONNECTIONS", 0))
+
 // SocketState A structure representing the state of a socket instance
 type SocketState struct {
-	Hosted *game.Game   // The hosted player
-	Game   *game.Game   // The active game
-	Player *game.Player // The active player
+	Hosted  *game.Game   // The hosted player
+	Game    *game.Game   // The active game
+	Player  *game.Player // The active player
+	Display *game.Game   // The game this connection is a display for
+	Device  game.Device  // The device this connection was made from
+	closed  bool         // Whether the connection has closed so delayed handlers are skipped
+	lock    sync.Mutex   // A lock held while handling a packet
 
 	*gowsps.Connection // The websocket connection
 }
 
 //SocketConnect Creates a socket connection and upgrades the HTTP request to WS
 func SocketConnect(w http.ResponseWriter, r *http.Request) {
+	address := tools.ClientAddress(r)
+	if !tools.CheckOrigin(r) { // If the connection came from a page on another site
+		log.Printf("Rejected connection from '%s' with disallowed origin '%s'", address, r.Header.Get("Origin"))
+		http.Error(w, "Origin not allowed", http.StatusForbidden)
+		return
+	}
+	if !connections.Acquire(address) { // If the address has too many open connections
+		log.Printf("Rejected connection from '%s' too many open connections", address)
+		http.Error(w, "Too many connections", http.StatusTooManyRequests)
+		return
+	}
+	defer connections.Release(address) // Release the connection once it's closed
+
 	s := gowsps.NewPacketSystem()
-	var state = SocketState{} // Create a new state with the connection
+	var state = SocketState{ // Create a new state with the connection
+		Device: game.Device{Address: address, Agent: r.UserAgent()},
+	}
 
 	// Add handlers for each of
-	gowsps.AddHandler(s, CCreateGame, state.onCreateGame)
-	gowsps.AddHandler(s, CCheckNameTaken, state.onCheckNameTaken)
-	gowsps.AddHandler(s, CRequestGameState, state.onRequestGameState)
-	gowsps.AddHandler(s, CRequestJoin, state.onRequestJoin)
-	gowsps.AddHandler(s, CStateChange, state.onStateChange)
-	gowsps.AddHandler(s, CAnswer, state.onAnswer)
-	gowsps.AddHandler(s, CKick, state.onKick)
+	gowsps.AddHandler(s, CCreateGame, moderated(&state, func(data *CreateGameData) moderation.Content {
+		return moderation.Content{Kind: moderation.KindName, Text: data.Host}
+	}, state.onCreateGame))
+	gowsps.AddHandler(s, CCheckNameTaken, serial(&state, state.onCheckNameTaken))
+	gowsps.AddHandler(s, CRequestGameState, serial(&state, state.onRequestGameState))
+	gowsps.AddHandler(s, CRequestJoin, moderated(&state, func(data *RequestJoinData) moderation.Content {
+		return moderation.Content{Kind: moderation.KindName, Game: data.Id, Text: data.Name}
+	}, state.onRequestJoin))
+	gowsps.AddHandler(s, CStateChange, serial(&state, state.onStateChange))
+	gowsps.AddHandler(s, CAnswer, serial(&state, state.onAnswer))
+	gowsps.AddHandler(s, CKick, serial(&state, state.onKick))
+	gowsps.AddHandler(s, CReconnectHost, serial(&state, state.onReconnectHost))
+	gowsps.AddHandler(s, CJoinDisplay, serial(&state, state.onJoinDisplay))
+	gowsps.AddHandler(s, CSetMaxPlayers, serial(&state, state.onSetMaxPlayers))
+	gowsps.AddHandler(s, CUpdateSettings, serial(&state, state.onUpdateSettings))
+	gowsps.AddHandler(s, CBuzz, serial(&state, state.onBuzz))
+	gowsps.AddHandler(s, CJudgeBuzz, serial(&state, state.onJudgeBuzz))
+	gowsps.AddHandler(s, CWarmUp, serial(&state, state.onWarmUp))
+	gowsps.AddHandler(s, CResync, serial(&state, state.onResync))
+	gowsps.AddHandler(s, CRequestSnapshot, serial(&state, state.onRequestSnapshot))
 
 	s.UpgradeAndListen(w, r, func(conn *gowsps.Connection, err error) {
 		state.Connection = conn
 	})
 
-	state.Cleanup() // Cleanup the state
+	state.lock.Lock() // Wait for any handler delayed by moderation
+	state.closed = true
+	state.Cleanup()     // Cleanup the state
+	state.lock.Unlock() // Release lock
+}
+
+// serial wraps a packet handler so it holds the state lock while it runs. Handlers
+// delayed by moderation run on another goroutine so the lock stops them running
+// at the same time as the others. Handlers delayed until after the connection
+// closed are skipped
+func serial[T any](state *SocketState, handler func(data *T)) func(data *T) {
+	return func(data *T) {
+		state.lock.Lock()         // Establish lock on the state
+		defer state.lock.Unlock() // Defer the releasing of the lock
+		if !state.closed {
+			handler(data)
+		}
+	}
+}
+
+// moderated wraps a packet handler so the user written text of the packet is
+// checked with the moderation webhook before the handler runs. The check runs
+// off the read goroutine so the packets after it aren't held up by the webhook.
+// Held text is answered with an error instead of reaching the handler and flagged
+// text is recorded in the audit log. Packets without any text skip the webhook
+func moderated[T any](state *SocketState, content func(data *T) moderation.Content, handler func(data *T)) func(data *T) {
+	handler = serial(state, handler)
+	if !moderation.Enabled() {
+		return handler
+	}
+	return func(data *T) {
+		checked := content(data)
+		if strings.TrimSpace(checked.Text) == "" {
+			handler(data)
+			return
+		}
+		go func() {
+			switch moderation.Check(checked) {
+			case moderation.Hold: // Tell the sender their text wasn't accepted
+				audit.Record(state.Device.Address, "moderation.hold", checked.Game, checked.Kind+": "+checked.Text)
+				serial(state, func(data *T) {
+					state.Send(ErrorDetailsPacket(Moderated, "That "+checked.Kind+" wasn't accepted by moderation", nil))
+				})(data)
+			case moderation.Flag: // Keep a record of the text for review
+				audit.Record(state.Device.Address, "moderation.flag", checked.Game, checked.Kind+": "+checked.Text)
+				handler(data)
+			default:
+				handler(data)
+			}
+		}()
+	}
+}
+
+// moderateAnswer checks the typed text of an answer that was already recorded
+// with the moderation webhook. The check runs after the answer is recorded so
+// the webhook never delays the answer time. Held answers still count but their
+// text is hidden from the word cloud and the raw answers
+func (state *SocketState) moderateAnswer(g *game.Game, player *game.Player, index tools.QuestionIndex, text string) {
+	if !moderation.Enabled() || strings.TrimSpace(text) == "" {
+		return
+	}
+	content := moderation.Content{Kind: moderation.KindAnswer, Game: g.Id, Text: text}
+	address := state.Device.Address
+	go func() {
+		switch moderation.Check(content) {
+		case moderation.Hold: // Hide the text and tell the player it wasn't accepted
+			audit.Record(address, "moderation.hold", content.Game, content.Kind+": "+content.Text)
+			g.HoldAnswer(player, index)
+			state.Send(ErrorDetailsPacket(Moderated, "That answer wasn't accepted by moderation", nil))
+		case moderation.Flag: // Keep a record of the text for review
+			audit.Record(address, "moderation.flag", content.Game, content.Kind+": "+content.Text)
+		}
+	}()
+}
+
+// GameId returns the code of the game the connection is playing or hosting or
+// an empty string when it isn't in one
+func (state *SocketState) GameId() string {
+	if state.Game != nil {
+		return state.Game.Id
+	}
+	if state.Hosted != nil {
+		return state.Hosted.Id
+	}
+	return ""
 }
 
 // Cleanup Stops any hosted games by the state and removes the player
-// from any games if the player isn't the host
+// from any games if the player isn't the host. Hosted games that are still
+// running are paused instead when they lose their connection so the host
+// can reconnect
 func (state *SocketState) Cleanup() {
+	state.cleanup(game.HostGrace > 0)
+}
+
+// cleanup Stops or pauses any hosted game by the state and removes the player
+// from any games if the player isn't the host
+func (state *SocketState) cleanup(pause bool) {
 	if state.Hosted != nil {
-		state.Hosted.Stop()
+		if pause && state.Hosted.State != game.Stopped { // Give the host a chance to reconnect
+			audit.Record(state.Device.Address, "pause", state.Hosted.Id, "")
+			state.Hosted.Pause()
+		} else {
+			audit.Record(state.Device.Address, "stop", state.Hosted.Id, "")
+			state.Hosted.Stop()
+		}
 		state.Hosted = nil
 	}
 	if state.Game != nil && state.Player != nil {
@@ -96,16 +363,91 @@ func (state *SocketState) Cleanup() {
 		state.Game = nil
 		state.Player = nil
 	}
+	if state.Display != nil {
+		state.Display.RemoveDisplay(state.Connection)
+		state.Display = nil
+	}
 }
 
 // onCreateGame Packet handler function for the net.CCreateGame packet. Handles
 // the creation of new games
 func (state *SocketState) onCreateGame(data *CreateGameData) {
-	g := game.New(state.Connection, data.Title, data.Questions) // Create a new game
-	state.Hosted = g                                            // Set the hosted game for this state
-	state.Send(JoinGamePacket(true, g.Id, g.Title))             // Tell the host they've joined the new game as owner
-	state.Send(GameStatePacket(game.Waiting))                   // Tell the player the game state is waiting
-	log.Printf("Created new game '%s' (%s)", g.Title, g.Id)
+	hostName := strings.TrimSpace(data.Host)
+	if hostName != "" && !game.IsValidName(hostName) { // If the host chose a name that is too long
+		state.Send(InvalidNamePacket(game.MinNameLength, game.MaxNameLength))
+		return
+	}
+	if hostName != "" && !game.IsNameAllowed(hostName) { // If the host chose a name the filter rejects
+		state.Send(ErrorDetailsPacket(InvalidName, "That name isn't allowed", nil))
+		return
+	}
+	settings := data.GameSettings
+	if data.Profile != "" { // If the host chose a settings profile
+		profile := game.FindProfile(data.Profile)
+		if profile == nil {
+			state.Send(ErrorPacket("That settings profile doesn't exist"))
+			return
+		}
+		settings = profile.GameSettings
+	} else if err := game.ValidateSettings(settings); err != nil { // If the host chose settings that don't exist
+		state.Send(ErrorPacket("Invalid game settings: " + err.Error()))
+		return
+	}
+	q := &quiz.Quiz{TextStyle: data.TextStyle, Title: data.Title, Questions: data.Questions}
+	if data.Upload != "" { // If the quiz was sent as a resumable upload
+		contents, err := upload.Take(data.Upload)
+		if err == nil {
+			err = json.Unmarshal(contents, q)
+		}
+		if err != nil {
+			state.Send(ErrorPacket("Failed to load the uploaded quiz: " + err.Error()))
+			return
+		}
+	}
+	if data.Quiz != "" { // If the host is starting a stored quiz
+		stored := quiz.Stored.Get(data.Quiz)
+		if stored == nil {
+			state.Send(ErrorPacket("That quiz doesn't exist"))
+			return
+		}
+		q = &quiz.Quiz{TextStyle: stored.TextStyle, Title: stored.Title, Questions: stored.CopyQuestions()}
+	} else if err := q.Validate(); err != nil { // If the uploaded quiz isn't valid
+		state.Send(ErrorDetailsPacket(InvalidQuiz, "Invalid quiz: "+err.Error(), err))
+		return
+	} else if err := q.Prepare(); err != nil { // Prepare the uploaded quiz
+		state.Send(ErrorPacket(err.Error()))
+		return
+	}
+	rounds := []game.Round{{Title: q.Title}}
+	if len(data.Playlist)+1 > game.MaxPlaylist { // If the host queued too many quizzes
+		state.Send(ErrorPacket(fmt.Sprintf("A game can play at most %d quizzes", game.MaxPlaylist)))
+		return
+	}
+	for _, id := range data.Playlist { // Add the questions of each queued quiz after the questions of the quiz
+		stored := quiz.Stored.Get(id)
+		if stored == nil {
+			state.Send(ErrorPacket("The quiz '" + id + "' in the playlist doesn't exist"))
+			return
+		}
+		rounds = append(rounds, game.Round{Title: stored.Title, First: len(q.Questions)})
+		q.Questions = append(q.Questions, stored.CopyQuestions()...)
+	}
+	g := game.New(state.Connection, q.Title, q.Questions, hostName, settings) // Create a new game
+	g.Media = q.Media                                                         // The game releases the uploaded images once it finishes
+	g.Style = q.TextStyle                                                     // Players are told how to show the text of the quiz
+	g.Rounds = rounds                                                         // The quizzes of the playlist
+	state.Hosted = g                                                          // Set the hosted game for this state
+	joined := g.JoinData(true)                                                // The game data for the owner
+	joined.Warnings = q.DuplicateWarnings()                                   // Warn the host about accidental duplicate questions
+	if joined.TooLong {                                                       // Warn about games that may not fit the time available
+		log.Printf("Game '%s' (%s) is expected to take longer than %d minutes", g.Title, g.Id, game.MaxDuration)
+	}
+	g.Fanout.Push(JoinGamePacket(joined), state.Connection)        // Tell the host they've joined the new game as owner
+	g.Fanout.Push(GameStatePacket(game.Waiting), state.Connection) // Tell the player the game state is waiting
+	audit.Record(state.Device.Address, "create", g.Id, g.Title)
+	if cli.Hosting != nil && data.Quiz == cli.Hosting.Quiz { // Print the code for the quiz from the host command
+		fmt.Printf("  Join code for '%s': %s\n\n", g.Title, g.Id)
+	}
 }
 
 // onCheckNameTaken Packet handler function for the net.CCheckNameTaken packet. Handles
@@ -115,8 +457,8 @@ func (state *SocketState) onCheckNameTaken(data *CheckNameTakenData) {
 	if g == nil {          // If the game doesn't exist
 		state.Send(ErrorPacket("That game code doesn't exist"))
 	} else {
-		taken := g.IsNameTaken(data.Name)        // Check if the name is taken
-		state.Send(NameTakenResultPacket(taken)) // Send the result
+		taken := g.IsNameTaken(data.Name) || game.IsNameReserved(data.Name) || !game.IsNameAllowed(data.Name) // Check if the name is taken
+		state.Send(NameTakenResultPacket(taken))                                                              // Send the result
 	}
 }
 
@@ -144,12 +486,33 @@ func (state *SocketState) onRequestJoin(data *RequestJoinData) {
 		if g.State != game.Waiting { // If the game isn't in waiting state
 			log.Printf("%d", g.State)
 			state.Send(ErrorPacket("That game is already started"))
+		} else if g.IsFull() { // If the game has as many players as it allows
+			g.RejectFull()
+			state.Send(ErrorDetailsPacket(GameFull, "That game is full", struct {
+				Max int `json:"max"` // The most players that can join the game
+			}{Max: g.GetMaxPlayers()}))
+		} else if !game.IsValidName(data.Name) { // If the name is too short or too long
+			state.Send(InvalidNamePacket(game.MinNameLength, game.MaxNameLength))
+		} else if game.IsNameReserved(data.Name) { // If the name could be mistaken for the host or server
+			state.Send(ErrorDetailsPacket(InvalidName, "That name is reserved", nil))
+		} else if !game.IsNameAllowed(data.Name) { // If the name filter rejects the name
+			state.Send(ErrorDetailsPacket(InvalidName, "That name isn't allowed", nil))
 		} else if g.IsNameTaken(data.Name) { // If the name is already taken
 			state.Send(ErrorPacket("That name is already in use"))
+		} else if other := g.FindDevice(state.Device); other != nil && g.Settings().Devices == game.DevicesBlock {
+			state.Send(ErrorPacket("Another player has already joined from this device"))
 		} else {
-			state.Player = g.Join(state.Connection, data.Name) // Join and set the active player
-			state.Game = g                                     // Set the active game
-			state.Send(JoinGamePacket(false, g.Id, g.Title))   // Tell the host they've joined the new game as a player
+			state.Player = g.Join(state.Connection, data.Name, state.Device) // Join and set the active player
+			state.Player.Audio = data.Audio                                  // Set whether the player wants question audio
+			state.Player.Compact = data.Compact                              // Set whether the player wants compact questions
+			state.Game = g                                                   // Set the active game
+			if other != nil {                                                // If another player is using the same device warn the host
+				g.Fanout.Push(DuplicateDevicePacket(state.Player.Id, other.Id), g.HostTargets()...)
+			}
+			if tools.IsLocale(data.Locale) { // Set the language the player wants the questions in
+				state.Player.Locale = data.Locale
+			}
+			g.SendWarmUp(state.Connection) // Let the player vote in the warm-up poll if one is being asked
 		}
 	}
 }
@@ -163,14 +526,18 @@ func (state *SocketState) onStateChange(data *StateChangeData) {
 	switch data.State {
 	case CDisconnect: // If the client asked to disconnect from the game
 		log.Printf("Client disconnected")
-		state.Cleanup() // Cleanup the state (stop games and remove player)
+		state.cleanup(false) // Cleanup the state (stop games and remove player)
 	case CStart: // If the client told the server to start the game
 		if hosted == nil { // If the player is not hosting a game
 			state.Send(ErrorPacket("Failed to update game state. You aren't hosting one?"))
 		} else if hosted.State != game.Waiting { // If the game is already started
 			state.Send(ErrorPacket("Game is already started/starting"))
 		} else {
+			audit.Record(state.Device.Address, "start", hosted.Id, "")
 			hosted.Start() // Start the game
+			if stats.Enabled() {
+				recordStats(hosted)
+			}
 		}
 	case CSkip: // If the client told the server to skip the current question (host only)
 		if hosted == nil { // If the hosted game doesn't exist
@@ -178,6 +545,7 @@ func (state *SocketState) onStateChange(data *StateChangeData) {
 		} else if hosted.State != game.Started { // If the game is not in the started state
 			state.Send(ErrorPacket("Game is not started"))
 		} else {
+			audit.Record(state.Device.Address, "skip", hosted.Id, "")
 			hosted.SkipQuestion() // Skip the question
 		}
 	default: // If the state change is an unknown state change
@@ -185,6 +553,17 @@ func (state *SocketState) onStateChange(data *StateChangeData) {
 	}
 }
 
+// recordStats adds the started game to the usage statistics
+func recordStats(g *game.Game) {
+	var devices []string
+	g.Players.ForEach(func(_ tools.Identifier, player *game.Player) {
+		devices = append(devices, player.Device.Address+" "+player.Device.Agent)
+	})
+	if err := stats.RecordGame(g.Questions, devices); err != nil {
+		log.Printf("Failed to save usage statistics: %s", err)
+	}
+}
+
 // onAnswer Packet handler function for the net.CAnswer packet. Handles
 // selection of an answer by a player in the game. Along with handling on
 // cases such as players already answering
@@ -193,10 +572,121 @@ func (state *SocketState) onAnswer(data *AnswerData) {
 	player := state.Player
 	if g == nil || player == nil { // If player is not in a  game
 		state.Send(ErrorPacket("Not in a game"))
-	} else if player.HasAnswered(g) { // If the player has already answered
-		state.Send(ErrorPacket("You have already answered the question."))
+	} else if g.State == game.Waiting { // Answers in the lobby are votes in the warm-up poll
+		if err := player.VoteWarmUp(g, data.Id); err != nil {
+			state.Send(ErrorPacket(err.Error()))
+		}
+	} else if data.Question == nil || !g.IsAsking(*data.Question) { // Answers meant for an earlier question are stale
+		state.Send(ErrorDetailsPacket(StaleQuestion, "That question is no longer being asked.", nil))
+	} else if data.Text != "" { // If the player wrote their answer
+		if err := player.AnswerWords(g, data.Text); err != nil {
+			state.sendTextError(err)
+		} else {
+			state.moderateAnswer(g, player, *data.Question, data.Text)
+		}
+	} else if data.Blanks != nil { // If the player filled in the blanks
+		if err := player.AnswerBlanks(g, data.Blanks); err != nil {
+			state.sendTextError(err)
+		} else { // The words written for every blank are checked together
+			state.moderateAnswer(g, player, *data.Question, strings.Join(data.Blanks, " "))
+		}
+	} else if data.Number != nil { // If the player chose a number
+		if err := player.AnswerNumber(g, *data.Number); errors.Is(err, game.ErrAlreadyAnswered) {
+			state.Send(ErrorDetailsPacket(AlreadyAnswered, err.Error(), nil))
+		} else if err != nil {
+			state.Send(ErrorPacket(err.Error()))
+		}
+	} else {
+		ids := data.Ids
+		if ids == nil { // If the player chose a single answer
+			ids = []tools.AnswerIndex{data.Id}
+		}
+		if err := player.Answer(g, ids); errors.Is(err, game.ErrAlreadyAnswered) { // Submit the player answer
+			state.Send(ErrorDetailsPacket(AlreadyAnswered, err.Error(), nil))
+		} else if err != nil {
+			state.Send(ErrorDetailsPacket(InvalidAnswer, err.Error(), err))
+		}
+	}
+}
+
+// sendTextError sends the player the error for a typed answer that wasn't accepted.
+// Text that isn't allowed is sent as an InvalidAnswer error with the reason
+func (state *SocketState) sendTextError(err error) {
+	var invalid *game.TextError
+	if errors.Is(err, game.ErrAlreadyAnswered) {
+		state.Send(ErrorDetailsPacket(AlreadyAnswered, err.Error(), nil))
+	} else if errors.As(err, &invalid) {
+		state.Send(ErrorDetailsPacket(InvalidAnswer, err.Error(), invalid))
+	} else {
+		state.Send(ErrorPacket(err.Error()))
+	}
+}
+
+// onBuzz Packet handler function for the net.CBuzz packet. Handles a player
+// buzzing to answer a buzzer question
+func (state *SocketState) onBuzz(_ *BuzzData) {
+	g := state.Game
+	player := state.Player
+	if g == nil || player == nil { // If player is not in a  game
+		state.Send(ErrorPacket("Not in a game"))
+	} else if err := player.Buzz(g); err != nil {
+		state.Send(ErrorPacket(err.Error()))
+	}
+}
+
+// onJudgeBuzz Packet handler function for the net.CJudgeBuzz packet. Handles the
+// host judging the answer of the player whose turn it is to answer (Host only)
+func (state *SocketState) onJudgeBuzz(data *JudgeBuzzData) {
+	hosted := state.Hosted // Retrieve the hosted game
+	if hosted == nil {     // If the hosted game doesn't exist
+		state.Send(ErrorPacket("Failed to judge answer. You aren't hosting one?"))
+	} else if err := hosted.JudgeBuzz(data.Correct); err != nil {
+		state.Send(ErrorPacket(err.Error()))
+	} else {
+		audit.Record(state.Device.Address, "judge", hosted.Id, strconv.FormatBool(data.Correct))
+	}
+}
+
+// onWarmUp Packet handler function for the net.CWarmUp packet. Handles the host
+// asking or ending an unscored poll while waiting for players (Host only)
+func (state *SocketState) onWarmUp(data *WarmUpData) {
+	hosted := state.Hosted // Retrieve the hosted game
+	if hosted == nil {     // If the hosted game doesn't exist
+		state.Send(ErrorPacket("Failed to update game. You aren't hosting one?"))
+	} else if data.Question == "" { // An empty question ends the poll
+		hosted.EndWarmUp()
+	} else if err := hosted.StartWarmUp(*data); err != nil {
+		state.Send(ErrorPacket(err.Error()))
+	} else {
+		audit.Record(state.Device.Address, "warm_up", hosted.Id, data.Question)
+	}
+}
+
+// onResync Packet handler function for the net.CResync packet. Handles a player,
+// host or display asking for the events it missed after the provided number
+func (state *SocketState) onResync(data *ResyncData) {
+	if state.Game != nil && state.Player != nil { // If this connection is a player
+		state.Game.Resync(state.Connection, state.Player, data.Seq)
+	} else if state.Hosted != nil { // If this connection is the host
+		state.Hosted.Resync(state.Connection, nil, data.Seq)
+	} else if state.Display != nil { // If this connection is a display
+		state.Display.Resync(state.Connection, nil, data.Seq)
+	} else {
+		state.Send(ErrorPacket("Not in a game"))
+	}
+}
+
+// onRequestSnapshot Packet handler function for the net.CRequestSnapshot packet.
+// Handles a player, host or display asking for a compact snapshot of the game
+func (state *SocketState) onRequestSnapshot(_ *RequestSnapshotData) {
+	if state.Game != nil && state.Player != nil { // If this connection is a player
+		state.Send(SnapshotPacket(state.Game.Snapshot(state.Player)))
+	} else if state.Hosted != nil { // If this connection is the host
+		state.Send(SnapshotPacket(state.Hosted.Snapshot(nil)))
+	} else if state.Display != nil { // If this connection is a display
+		state.Send(SnapshotPacket(state.Display.Snapshot(nil)))
 	} else {
-		player.Answer(g, data.Id) // Submit the player answer
+		state.Send(ErrorPacket("Not in a game"))
 	}
 }
 
@@ -205,10 +695,78 @@ func (state *SocketState) onAnswer(data *AnswerData) {
 func (state *SocketState) onKick(data *KickData) {
 	hosted := state.Hosted // Retrieve the hosted game
 	if hosted != nil {     // Ensure the hosted game exists
+		if utf8.RuneCountInString(data.Reason) > game.MaxKickReason { // If the reason is too long to show
+			state.Send(ErrorPacket(fmt.Sprintf("Kick reasons must be at most %d characters", game.MaxKickReason)))
+			return
+		}
 		p := hosted.Players.Get(data.Id) // Retrieve the player
 		if p != nil {                    // If the player exists
-			hosted.RemovePlayer(p)                           // Remove the player from the game
-			p.Net.Send(DisconnectPacket("Kicked from game")) // Send a disconnect packet to the player
+			audit.Record(state.Device.Address, "kick", hosted.Id, p.Name+" ("+p.Id+")")
+			hosted.KickPlayer(p, data.Reason) // Remove the player from the game and tell them why
+		}
+	}
+}
+
+// onSetMaxPlayers Packet handler function for the net.CSetMaxPlayers packet. Handles
+// the host changing the most players that can join their game from the lobby (Host only)
+func (state *SocketState) onSetMaxPlayers(data *SetMaxPlayersData) {
+	hosted := state.Hosted // Retrieve the hosted game
+	if hosted == nil {     // If the hosted game doesn't exist
+		state.Send(ErrorPacket("Failed to update game. You aren't hosting one?"))
+	} else if err := hosted.SetMaxPlayers(data.Value); err != nil {
+		state.Send(ErrorPacket(err.Error()))
+	} else {
+		audit.Record(state.Device.Address, "max_players", hosted.Id, strconv.Itoa(data.Value))
+	}
+}
+
+// onUpdateSettings Packet handler function for the net.CUpdateSettings packet. Handles
+// the host changing the settings of their game from the lobby (Host only)
+func (state *SocketState) onUpdateSettings(data *GameSettings) {
+	hosted := state.Hosted // Retrieve the hosted game
+	if hosted == nil {     // If the hosted game doesn't exist
+		state.Send(ErrorPacket("Failed to update game. You aren't hosting one?"))
+	} else if err := hosted.UpdateSettings(*data); err != nil {
+		state.Send(ErrorPacket(err.Error()))
+	} else {
+		audit.Record(state.Device.Address, "settings", hosted.Id, fmt.Sprintf("%+v", *data))
+	}
+}
+
+// onReconnectHost Packet handler function for the net.CReconnectHost packet. Handles
+// hosts reconnecting to their game after their connection was lost
+func (state *SocketState) onReconnectHost(data *ReconnectHostData) {
+	g := game.Get(data.Id) // Retrieve the game with that ID
+	if g == nil || !tools.SecretEquals(g.Key, data.Key) {
+		state.Send(ErrorPacket("Unknown game or invalid host key"))
+	} else if g.State != game.Paused { // If the game still has its host
+		state.Send(ErrorPacket("That game isn't waiting for its host"))
+	} else if state.Hosted != nil || state.Game != nil || state.Display != nil { // If this connection is already in a game
+		state.Send(ErrorPacket("You are already in a game"))
+	} else {
+		audit.Record(state.Device.Address, "reconnect", g.Id, "")
+		state.Hosted = g           // Set the hosted game for this state
+		g.Resume(state.Connection) // Continue the game with the new connection
+	}
+}
+
+// onJoinDisplay Packet handler function for the net.CJoinDisplay packet. Handles
+// connecting read-only displays (e.g. a projector) that show the host view of the game
+func (state *SocketState) onJoinDisplay(data *JoinDisplayData) {
+	g := game.Get(data.Id) // Retrieve the game with that ID
+	if g == nil || !tools.SecretEquals(g.Key, data.Key) {
+		state.Send(ErrorPacket("Unknown game or invalid host key"))
+	} else if state.Hosted != nil || state.Game != nil || state.Display != nil { // If this connection is already in a game
+		state.Send(ErrorPacket("You are already in a game"))
+	} else {
+		joined := g.JoinData(false)
+		joined.Display, joined.Key = true, g.Key
+		state.Send(JoinGamePacket(joined)) // Tell the display which game it is showing before the game state
+		if !g.AddDisplay(state.Connection) {
+			state.Send(DisconnectPacket("That game has too many displays"))
+			return
 		}
+		state.Display = g // Set the displayed game for this state
+		audit.Record(state.Device.Address, "display", g.Id, "")
 	}
 }
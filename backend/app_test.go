@@ -0,0 +1,124 @@
+package main
+
+import (
+	"backend/game"
+	. "backend/net"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"github.com/gorilla/websocket"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"sync/atomic"
+	"testing"
+	"time"
+)
+
+// fuzzQuiz A valid quiz for the games created while fuzzing
+const fuzzQuiz = `{"title":"Quiz","questions":[{"question":"Q","answers":["A","B"],"values":[0]}]}`
+
+// fuzzClient A websocket client connected to the socket endpoint for fuzzing
+type fuzzClient struct {
+	*websocket.Conn
+}
+
+// dialFuzz connects a new client to the socket endpoint of the server
+func dialFuzz(server *httptest.Server) (*fuzzClient, error) {
+	conn, _, err := websocket.DefaultDialer.Dial("ws"+strings.TrimPrefix(server.URL, "http"), nil)
+	if err != nil {
+		return nil, err
+	}
+	return &fuzzClient{conn}, nil
+}
+
+// send writes a packet with the provided id and raw JSON data
+func (client *fuzzClient) send(id int, data string) error {
+	return client.WriteMessage(websocket.TextMessage, []byte(fmt.Sprintf(`{"id":%d,"data":%s}`, id, data)))
+}
+
+// await reads packets until one with the provided id arrives returning its data.
+// Returns an error if the connection closes or nothing arrives in time
+func (client *fuzzClient) await(id int) (json.RawMessage, error) {
+	_ = client.SetReadDeadline(time.Now().Add(5 * time.Second))
+	for {
+		var packet struct {
+			Id   int             `json:"id"`
+			Data json.RawMessage `json:"data"`
+		}
+		if err := client.ReadJSON(&packet); err != nil {
+			return nil, err
+		}
+		if packet.Id == id {
+			return packet.Data, nil
+		}
+	}
+}
+
+// isTimeout checks whether the error is from nothing arriving in time
+func isTimeout(err error) bool {
+	var netErr net.Error
+	return errors.As(err, &netErr) && netErr.Timeout()
+}
+
+// FuzzSocketPackets checks that any packet sent to the socket endpoint by a
+// player or a host is decoded, validated and handled without panicking or
+// stopping the connection from handling the packets after it
+func FuzzSocketPackets(f *testing.F) {
+	game.HostGrace = 0 // Games stop as soon as their host disconnects
+	server := httptest.NewServer(http.HandlerFunc(SocketConnect))
+	defer server.Close()
+	owner, err := dialFuzz(server)
+	if err != nil {
+		f.Fatalf("failed to connect: %s", err)
+	}
+	defer owner.Close()
+	if err := owner.send(CCreateGame, fuzzQuiz); err != nil {
+		f.Fatalf("failed to create game: %s", err)
+	}
+	joined, err := owner.await(SJoinedGame)
+	if err != nil {
+		f.Fatalf("expected the game to be created: %s", err)
+	}
+	var created JoinGameData
+	_ = json.Unmarshal(joined, &created)
+	var players int32
+
+	f.Add(uint8(CCreateGame), true, []byte(`{"title":"Quiz","questions":[{"question":"Q","answers":["A","B"],"values":[0]}],"devices":1}`))
+	f.Add(uint8(CCreateGame), true, []byte(`{"title":"Quiz","questions":[{"kind":5,"numeric":{"min":1e308,"max":-1e308,"step":0}}]}`))
+	f.Add(uint8(CRequestJoin), false, []byte(`{"id":"ABCDE","name":"Name","audio":true}`))
+	f.Add(uint8(CStateChange), true, []byte(`{"state":999999999999999999999}`))
+	f.Add(uint8(CAnswer), false, []byte(`{"id":-9223372036854775808,"question":0}`))
+	f.Add(uint8(CAnswer), false, []byte(`{"question":0,"blanks":["a","b","c","d","e","f"],"number":1e400}`))
+	f.Add(uint8(CKick), true, []byte(`{"id":"\ud800"}`))
+	f.Add(uint8(CUpdateSettings), true, []byte(`{"reveal":"\u0000","scoring":-1}`))
+	f.Add(uint8(CSetMaxPlayers), true, []byte(`{"value":-1}`))
+	f.Add(uint8(CWarmUp), true, []byte(`{"question":"Q","answers":null}`))
+	f.Add(uint8(CResync), false, []byte(`{"seq":-1}`))
+	f.Add(uint8(CRequestSnapshot), false, []byte(`[[[[[[[[[[]]]]]]]]]]`))
+	f.Fuzz(func(t *testing.T, id uint8, host bool, data []byte) {
+		client, err := dialFuzz(server)
+		if err != nil {
+			t.Skip("failed to connect: ", err)
+		}
+		defer client.Close()
+		if host { // Host a new game so the host packets are handled
+			_ = client.send(CCreateGame, fuzzQuiz)
+			if _, err := client.await(SJoinedGame); err != nil {
+				t.Fatalf("expected the game to be created: %s", err)
+			}
+		} else { // Join the shared game so the player packets are handled
+			name := fmt.Sprintf("Player %d", atomic.AddInt32(&players, 1))
+			_ = client.send(CRequestJoin, fmt.Sprintf(`{"id":%q,"name":%q}`, created.Id, name))
+		}
+		message := append([]byte(fmt.Sprintf(`{"id":%d,"data":`, int(id)%(CRequestSnapshot+1))), data...)
+		_ = client.WriteMessage(websocket.TextMessage, append(message, '}'))
+		// The connection must still answer the packets sent after the fuzzed one
+		// unless the server closed it
+		_ = client.send(CRequestGameState, `{"id":"NONE"}`)
+		if _, err := client.await(SGameState); isTimeout(err) {
+			t.Fatalf("expected the connection to keep handling packets after %q", message)
+		}
+	})
+}
@@ -0,0 +1,93 @@
+package audit
+
+import (
+	"backend/tools"
+	"log"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Entry A structure representing a single recorded host or admin action
+type Entry struct {
+	Time   time.Time `json:"time"`             // The time the action happened
+	Actor  string    `json:"actor"`            // Who performed the action (client address or admin)
+	Action string    `json:"action"`           // The name of the action (e.g. kick, start, skip)
+	Game   string    `json:"game,omitempty"`   // The game code the action was performed on
+	Target string    `json:"target,omitempty"` // The target of the action (e.g. the kicked player)
+}
+
+// Capacity The maximum number of entries kept in memory. Once reached the oldest
+// entries are discarded to make space for new ones
+var Capacity = tools.EnvIntOrDefault("QUIZLER_AUDIT_SIZE", 1000)
+
+// Retention How long entries are kept before Expire removes them. Entries are
+// kept until Capacity is reached when the retention is zero
+var Retention = time.Duration(tools.EnvIntOrDefault("QUIZLER_AUDIT_RETENTION", 0)) * time.Hour
+
+var (
+	lock    = sync.RWMutex{} // A lock for modifying the entries
+	entries []Entry          // The recorded entries from oldest to newest
+)
+
+// Record adds a new entry to the audit log for the provided action
+func Record(actor string, action string, game string, target string) {
+	entry := Entry{Time: time.Now(), Actor: actor, Action: action, Game: game, Target: target}
+	lock.Lock() // Establish write lock on the entries
+	entries = append(entries, entry)
+	if overflow := len(entries) - Capacity; overflow > 0 { // If the capacity was exceeded
+		entries = append(entries[:0:0], entries[overflow:]...) // Copy so the old entries can be freed
+	}
+	lock.Unlock() // Release write lock
+	log.Printf("Audit: '%s' performed '%s' on game '%s' target '%s'", actor, action, game, target)
+}
+
+// Query retrieves up to limit entries newest first. If game is not empty only
+// entries for that game code are included
+func Query(game string, limit int) []Entry {
+	lock.RLock()         // Establish a read lock on the entries
+	defer lock.RUnlock() // Defer the releasing of the read lock
+	out := make([]Entry, 0)
+	for i := len(entries) - 1; i >= 0 && len(out) < limit; i-- { // Iterate from newest to oldest
+		if game == "" || entries[i].Game == game {
+			out = append(out, entries[i])
+		}
+	}
+	return out
+}
+
+// Expire removes the entries that are older than the Retention
+func Expire() error {
+	if Retention <= 0 {
+		return nil
+	}
+	cutoff := time.Now().Add(-Retention)
+	lock.Lock()                     // Establish write lock on the entries
+	defer lock.Unlock()             // Defer the releasing of the write lock
+	for i, entry := range entries { // Entries are oldest first so stop at the first kept entry
+		if entry.Time.After(cutoff) {
+			entries = append(entries[:0:0], entries[i:]...)
+			return nil
+		}
+	}
+	entries = nil
+	return nil
+}
+
+// Erase removes every entry with an actor or target that mentions the provided
+// subject ignoring case so the records of a player or address can be deleted on
+// request. Returns the number of entries removed
+func Erase(subject string) int {
+	subject = strings.ToLower(subject)
+	lock.Lock()         // Establish write lock on the entries
+	defer lock.Unlock() // Defer the releasing of the write lock
+	kept := entries[:0:0]
+	for _, entry := range entries {
+		if !strings.Contains(strings.ToLower(entry.Actor), subject) && !strings.Contains(strings.ToLower(entry.Target), subject) {
+			kept = append(kept, entry)
+		}
+	}
+	removed := len(entries) - len(kept)
+	entries = kept
+	return removed
+}
@@ -0,0 +1,183 @@
+package cli
+
+import (
+	"backend/quiz"
+	"encoding/json"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"strings"
+)
+
+// Usage The help text describing the available commands
+const Usage = `Usage: quizler [command]
+
+Running without a command starts the server.
+
+Commands:
+  validate <file>...                                   Check that quiz files are valid
+  convert --from gift --to json [--out <file>] <file>  Convert questions from another format
+  host [--open] <file>                                 Start the server with the quiz ready to host
+  help                                                 Show this message
+`
+
+// Host The quiz prepared by the host command for the server to start with
+type Host struct {
+	Quiz string // The id of the quiz in the stored quizzes
+	Open bool   // Whether the host page should be opened in the browser
+}
+
+// Hosting The quiz prepared by the host command or nil if the server was started
+// without it
+var Hosting *Host
+
+// Run executes the command described by the provided arguments and returns the
+// exit code for the process along with whether the server should be started
+// instead of exiting
+func Run(args []string) (int, bool) {
+	switch args[0] {
+	case "validate":
+		return validate(args[1:]), false
+	case "convert":
+		return convert(args[1:]), false
+	case "host":
+		code := host(args[1:])
+		return code, code == 0
+	case "help", "-h", "--help":
+		fmt.Print(Usage)
+		return 0, false
+	default:
+		fmt.Fprintf(os.Stderr, "Unknown command '%s'\n\n%s", args[0], Usage)
+		return 2, false
+	}
+}
+
+// host loads the provided quiz file into the stored quizzes so the server starts
+// with it ready to host
+func host(args []string) int {
+	flags := flag.NewFlagSet("host", flag.ContinueOnError)
+	open := flags.Bool("open", false, "Open the host page in the browser once the server starts")
+	if err := flags.Parse(args); err != nil {
+		return 2
+	}
+	if flags.NArg() != 1 {
+		fmt.Fprint(os.Stderr, "Expected a single quiz file to host\n\n", Usage)
+		return 2
+	}
+	path := flags.Arg(0)
+	id, err := quiz.Stored.Pin(path)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "%s: %s\n", path, err)
+		return 1
+	}
+	Hosting = &Host{Quiz: id, Open: *open}
+	return 0
+}
+
+// OpenBrowser opens the provided url in the default browser of the system
+func OpenBrowser(url string) {
+	var command *exec.Cmd
+	switch runtime.GOOS {
+	case "windows":
+		command = exec.Command("rundll32", "url.dll,FileProtocolHandler", url)
+	case "darwin":
+		command = exec.Command("open", url)
+	default:
+		command = exec.Command("xdg-open", url)
+	}
+	if err := command.Start(); err != nil {
+		fmt.Fprintf(os.Stderr, "Failed to open the browser: %s\n", err)
+	}
+}
+
+// validate loads and validates each of the provided quiz files printing the
+// result for each one. Fails if any of the files are invalid
+func validate(args []string) int {
+	if len(args) == 0 {
+		fmt.Fprint(os.Stderr, "Missing quiz file to validate\n\n", Usage)
+		return 2
+	}
+	code := 0
+	for _, path := range args { // Iterate over the quiz files
+		q, err := quiz.Load(path)
+		if err == nil {
+			err = q.Validate()
+		}
+		var invalid *quiz.ValidationError
+		if errors.As(err, &invalid) { // List every problem with the quiz
+			fmt.Printf("%s: invalid\n", path)
+			for _, problem := range invalid.Problems {
+				fmt.Printf("  %s\n", problem)
+			}
+			code = 1
+		} else if err != nil {
+			fmt.Printf("%s: %s\n", path, err)
+			code = 1
+		} else {
+			fmt.Printf("%s: valid (%d questions)\n", path, len(q.Questions))
+		}
+	}
+	return code
+}
+
+// convert converts a file in another quiz format into a quiz file
+func convert(args []string) int {
+	flags := flag.NewFlagSet("convert", flag.ContinueOnError)
+	from := flags.String("from", "", "The format of the input file (gift)")
+	to := flags.String("to", "json", "The format to convert to (json)")
+	out := flags.String("out", "", "The file to write the quiz to (defaults to stdout)")
+	title := flags.String("title", "", "The title of the quiz (defaults to the input file name)")
+	if err := flags.Parse(args); err != nil {
+		return 2
+	}
+	if *from != "gift" || *to != "json" {
+		fmt.Fprintln(os.Stderr, "Only converting --from gift --to json is supported")
+		return 2
+	}
+	if flags.NArg() != 1 {
+		fmt.Fprint(os.Stderr, "Expected a single file to convert\n\n", Usage)
+		return 2
+	}
+	path := flags.Arg(0)
+	if *title == "" { // Use the file name without its extension as the title
+		*title = strings.TrimSuffix(filepath.Base(path), filepath.Ext(path))
+		if len(*title) > quiz.MaxTitleLength {
+			*title = (*title)[:quiz.MaxTitleLength]
+		}
+	}
+	text, err := os.ReadFile(path)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		return 1
+	}
+	q, err := quiz.ParseGift(*title, string(text))
+	if err == nil {
+		err = q.Validate()
+	}
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "%s: %s\n", path, err)
+		return 1
+	}
+	var writer io.Writer = os.Stdout
+	if *out != "" {
+		file, err := os.Create(*out)
+		if err != nil {
+			fmt.Fprintln(os.Stderr, err)
+			return 1
+		}
+		defer file.Close()
+		writer = file
+	}
+	encoder := json.NewEncoder(writer)
+	encoder.SetIndent("", "  ")
+	if err = encoder.Encode(q); err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		return 1
+	}
+	return 0
+}
@@ -0,0 +1,173 @@
+package discovery
+
+import (
+	"backend/tools"
+	"bytes"
+	"encoding/binary"
+	"fmt"
+	"log"
+	"net"
+	"net/http"
+	"time"
+)
+
+// Configuration for announcing the server on the local network
+var (
+	Announce = tools.EnvOrDefault("QUIZLER_MDNS", "false") == "true" // Whether to announce the server using mDNS
+	Name     = tools.EnvOrDefault("QUIZLER_MDNS_NAME", "Quizler")    // The service instance name to announce
+)
+
+// Timing and values for mDNS announcements
+const (
+	AnnounceInterval = 60 * time.Second // The time between unsolicited announcements
+	RecordTTL        = 120              // The time in seconds that clients can cache the records for
+	HostName         = "quizler.local." // The host name the records point to
+	ServiceType      = "_http._tcp.local."
+)
+
+// The multicast group and port used by mDNS
+var group = &net.UDPAddr{IP: net.IPv4(224, 0, 0, 251), Port: 5353}
+
+// DNS record types and classes
+const (
+	typeA        uint16 = 1
+	typePTR      uint16 = 12
+	typeTXT      uint16 = 16
+	typeSRV      uint16 = 33
+	classIN      uint16 = 1
+	classFlushIN uint16 = 0x8001 // Class IN with the cache flush bit set for unique records
+)
+
+// Info A structure describing the server for clients discovering it
+type Info struct {
+	Name    string   `json:"name"`    // The name the server is announced as
+	Version string   `json:"version"` // The version of the server
+	Urls    []string `json:"urls"`    // The urls players can open to reach the server
+}
+
+// Server The information describing this server set by Start
+var Server Info
+
+// Start sets the information describing this server and starts announcing it on
+// the local network if mDNS is enabled
+func Start(version string, port int) {
+	Server = Info{Name: Name, Version: version, Urls: []string{}}
+	for _, ip := range tools.LocalAddresses() {
+		Server.Urls = append(Server.Urls, fmt.Sprintf("http://%s:%d/", ip, port))
+	}
+	if Announce {
+		go announce(port)
+	}
+}
+
+// HandleDiscovery HTTP handler for GET /api/discovery. Describes the server and
+// the urls it can be reached on
+func HandleDiscovery(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodGet { // Only accept GET requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	tools.WriteJson(writer, http.StatusOK, Server)
+}
+
+// announce joins the mDNS multicast group announcing the server periodically
+// and answering any queries that ask about http services or this host
+func announce(port int) {
+	conn, err := net.ListenMulticastUDP("udp4", nil, group)
+	if err != nil {
+		log.Printf("Failed to start mDNS announcements: %s", err)
+		return
+	}
+	defer conn.Close()
+	message := buildAnnouncement(port, tools.LocalAddresses())
+	log.Printf("Announcing '%s' on the local network as %s", Name, HostName)
+	send := func() {
+		if _, err := conn.WriteToUDP(message, group); err != nil {
+			log.Printf("Failed to send mDNS announcement: %s", err)
+		}
+	}
+	send()
+	go func() { // Periodically announce so clients that missed it still find the server
+		for range time.Tick(AnnounceInterval) {
+			send()
+		}
+	}()
+	buffer := make([]byte, 9000)
+	lastAnswer := time.Time{}
+	for {
+		n, _, err := conn.ReadFromUDP(buffer)
+		if err != nil {
+			log.Printf("Stopped mDNS announcements: %s", err)
+			return
+		}
+		query := buffer[:n]
+		// Only answer queries (the response bit is unset) that mention us at most once a second
+		if n > 12 && query[2]&0x80 == 0 && isRelevant(query) && time.Since(lastAnswer) >= time.Second {
+			lastAnswer = time.Now()
+			send()
+		}
+	}
+}
+
+// isRelevant checks whether the mDNS query asks about http services or this host
+func isRelevant(query []byte) bool {
+	return bytes.Contains(query, []byte("\x05_http\x04_tcp")) || bytes.Contains(query, []byte("\x07quizler\x05local"))
+}
+
+// buildAnnouncement creates the mDNS response message containing the records that
+// describe the service: the PTR record for the service type, the SRV and TXT
+// records for the instance and an A record for each address of the host
+func buildAnnouncement(port int, addresses []net.IP) []byte {
+	instance := Name + "." + ServiceType
+	var records bytes.Buffer
+	count := 0
+	record := func(name string, kind uint16, class uint16, data []byte) {
+		records.Write(encodeName(name))
+		_ = binary.Write(&records, binary.BigEndian, kind)
+		_ = binary.Write(&records, binary.BigEndian, class)
+		_ = binary.Write(&records, binary.BigEndian, uint32(RecordTTL))
+		_ = binary.Write(&records, binary.BigEndian, uint16(len(data)))
+		records.Write(data)
+		count++
+	}
+	record(ServiceType, typePTR, classIN, encodeName(instance))
+	srv := make([]byte, 6) // Priority and weight of zero followed by the port
+	binary.BigEndian.PutUint16(srv[4:], uint16(port))
+	record(instance, typeSRV, classFlushIN, append(srv, encodeName(HostName)...))
+	record(instance, typeTXT, classFlushIN, encodeText("path=/", "version="+Server.Version))
+	for _, ip := range addresses {
+		record(HostName, typeA, classFlushIN, ip.To4())
+	}
+	header := make([]byte, 12)
+	binary.BigEndian.PutUint16(header[2:], 0x8400) // Authoritative response
+	binary.BigEndian.PutUint16(header[6:], uint16(count))
+	return append(header, records.Bytes()...)
+}
+
+// encodeName encodes a dot separated domain name as a sequence of length
+// prefixed labels terminated by an empty label
+func encodeName(name string) []byte {
+	var out []byte
+	start := 0
+	for i := 0; i <= len(name); i++ {
+		if i == len(name) || name[i] == '.' {
+			if i > start {
+				out = append(out, byte(i-start))
+				out = append(out, name[start:i]...)
+			}
+			start = i + 1
+		}
+	}
+	return append(out, 0)
+}
+
+// encodeText encodes the provided values as the length prefixed strings of a
+// TXT record
+func encodeText(values ...string) []byte {
+	var out []byte
+	for _, value := range values {
+		out = append(out, byte(len(value)))
+		out = append(out, value...)
+	}
+	return out
+}
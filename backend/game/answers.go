@@ -0,0 +1,171 @@
+package game
+
+import (
+	. "backend/tools"
+	"encoding/csv"
+	"io"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// RawAnswer An answer exactly as a player submitted it. Answers are kept for the
+// whole game so the host can review them even after the questions are marked
+type RawAnswer struct {
+	Player   Identifier    // The id of the player that answered
+	Name     string        // The name of the player when they answered
+	Question QuestionIndex // The index of the question that was answered
+	Answers  []AnswerIndex // The chosen answers in the order they were chosen
+	Text     string        // The words typed for word cloud questions
+	Number   *float64      // The number chosen for number questions
+	Blanks   []string      // The words typed for each blank of fill in the blanks questions
+	Time     time.Duration // How long the player took to answer
+	Held     bool          // Whether moderation held the typed text so it is hidden
+}
+
+// answerLog A structure representing every answer submitted during the game
+type answerLog struct {
+	answers []RawAnswer // The answers oldest first
+	lock    sync.Mutex  // A lock for modifying the answers
+}
+
+// recordAnswer keeps the raw answer the player submitted to the provided question
+func (player *Player) recordAnswer(game *Game, q *ActiveQuestion, answer RawAnswer) {
+	answer.Player, answer.Name, answer.Question = player.Id, player.Name, q.Index
+	answer.Time = player.AnswerTime - q.StartTime
+	game.answerLog.lock.Lock() // Establish lock on the answers
+	game.answerLog.answers = append(game.answerLog.answers, answer)
+	game.answerLog.lock.Unlock() // Release lock
+}
+
+// HoldAnswer hides the typed text of the player's answer to the question after
+// moderation held it. The answer still counts but its words are removed from
+// the word cloud and the raw answers
+func (game *Game) HoldAnswer(player *Player, index QuestionIndex) {
+	var text string
+	game.answerLog.lock.Lock() // Establish lock on the answers
+	for i := range game.answerLog.answers {
+		answer := &game.answerLog.answers[i]
+		if answer.Player == player.Id && answer.Question == index && !answer.Held {
+			text = answer.Text
+			answer.Held, answer.Text, answer.Blanks = true, "", nil
+		}
+	}
+	game.answerLog.lock.Unlock() // Release lock
+	if q := game.ActiveQuestion; text != "" && q != nil && q.Index == index {
+		words := NormalizeWords(text)
+		q.wordsLock.Lock() // Establish lock on the words
+		if q.words[words] > 1 {
+			q.words[words]--
+		} else {
+			delete(q.words, words)
+		}
+		q.wordsLock.Unlock() // Release lock
+	}
+}
+
+// RawAnswers returns a copy of every answer submitted so far oldest first
+func (game *Game) RawAnswers() []RawAnswer {
+	game.answerLog.lock.Lock()         // Establish lock on the answers
+	defer game.answerLog.lock.Unlock() // Defer the releasing of the lock
+	return append([]RawAnswer{}, game.answerLog.answers...)
+}
+
+// csvLocale How the answers CSV is written for a language. Languages that write
+// numbers with a decimal comma separate the columns with semicolons as that is
+// what spreadsheets in those languages expect
+type csvLocale struct {
+	Headers []string // The column headers
+	Held    string   // The text written in place of answers held by moderation
+	Comma   bool     // Whether numbers are written with a decimal comma
+}
+
+// csvLocales The languages the answers CSV can be written in mapped to their
+// language code
+var csvLocales = map[string]csvLocale{
+	"en": {[]string{"Question", "Question Text", "Player", "Name", "Answer", "Time (ms)"}, "(held by moderation)", false},
+	"de": {[]string{"Frage", "Fragetext", "Spieler", "Name", "Antwort", "Zeit (ms)"}, "(von der Moderation zurückgehalten)", true},
+	"es": {[]string{"Pregunta", "Texto de la pregunta", "Jugador", "Nombre", "Respuesta", "Tiempo (ms)"}, "(retenida por la moderación)", true},
+	"fr": {[]string{"Question", "Texte de la question", "Joueur", "Nom", "Réponse", "Temps (ms)"}, "(retenue par la modération)", true},
+	"it": {[]string{"Domanda", "Testo della domanda", "Giocatore", "Nome", "Risposta", "Tempo (ms)"}, "(trattenuta dalla moderazione)", true},
+	"nl": {[]string{"Vraag", "Vraagtekst", "Speler", "Naam", "Antwoord", "Tijd (ms)"}, "(tegengehouden door moderatie)", true},
+	"pt": {[]string{"Pergunta", "Texto da pergunta", "Jogador", "Nome", "Resposta", "Tempo (ms)"}, "(retida pela moderação)", true},
+}
+
+// findCsvLocale finds the CSV language for the provided locale using just its
+// language (e.g. pt for pt-BR). Unknown languages are written in English
+func findCsvLocale(locale string) csvLocale {
+	language, _, _ := strings.Cut(locale, "-")
+	if value, exists := csvLocales[strings.ToLower(language)]; exists {
+		return value
+	}
+	return csvLocales["en"]
+}
+
+// WriteAnswers writes every answer submitted so far as CSV with a header row in
+// the provided locale. Chosen answers are written as their text and typed text
+// is written as it was submitted once trimmed. Questions translated into the
+// locale are written using their translation
+func (game *Game) WriteAnswers(writer io.Writer, locale string) error {
+	format := findCsvLocale(locale)
+	out := csv.NewWriter(writer)
+	if format.Comma {
+		out.Comma = ';'
+	}
+	_ = out.Write(format.Headers)
+	for _, answer := range game.RawAnswers() {
+		question := game.QuestionAt(answer.Question)
+		if question == nil {
+			continue
+		}
+		localized := question.Localized(question.Locale(locale))
+		_ = out.Write([]string{
+			strconv.Itoa(answer.Question + 1),
+			csvText(localized.Question),
+			answer.Player,
+			csvText(answer.Name),
+			answerText(&localized, answer, format),
+			strconv.FormatInt(answer.Time.Milliseconds(), 10),
+		})
+	}
+	out.Flush()
+	return out.Error()
+}
+
+// answerText describes the raw answer to the provided question as a single value
+// written in the provided CSV language
+func answerText(question *QuestionData, answer RawAnswer, format csvLocale) string {
+	if answer.Held {
+		return format.Held
+	}
+	if answer.Number != nil {
+		number := strconv.FormatFloat(*answer.Number, 'g', -1, 64)
+		if format.Comma {
+			number = strings.Replace(number, ".", ",", 1)
+		}
+		return number
+	}
+	if answer.Blanks != nil {
+		return csvText(strings.Join(answer.Blanks, "; "))
+	}
+	if answer.Text != "" {
+		return csvText(answer.Text)
+	}
+	chosen := make([]string, 0, len(answer.Answers))
+	for _, id := range answer.Answers {
+		if id >= 0 && id < len(question.Answers) {
+			chosen = append(chosen, question.Answers[id])
+		}
+	}
+	return csvText(strings.Join(chosen, "; "))
+}
+
+// csvText stops text written by users from being treated as a formula when the
+// CSV is opened in a spreadsheet
+func csvText(text string) string {
+	if text != "" && strings.ContainsRune("=+-@\t\r", rune(text[0])) {
+		return "'" + text
+	}
+	return text
+}
@@ -0,0 +1,154 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"compress/gzip"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"log"
+	"os"
+	"path/filepath"
+	"sort"
+	"time"
+)
+
+// Configuration for archiving the timelines of finished games. Timelines are only
+// kept in memory unless QUIZLER_ARCHIVE_DIR is set
+var (
+	ArchiveDir   = EnvOrDefault("QUIZLER_ARCHIVE_DIR", "")                                     // The directory compressed timelines are stored in
+	ArchiveAfter = time.Duration(EnvIntOrDefault("QUIZLER_ARCHIVE_AFTER", 1)) * 24 * time.Hour // How long finished timelines stay in memory before they are archived
+)
+
+// archivedTimeline The contents of a timeline archive file
+type archivedTimeline struct {
+	Id        Identifier          `json:"id"`        // The id the game had
+	KeyHash   string              `json:"keyHash"`   // The SHA-256 of the host key so the key itself isn't stored
+	Finished  time.Time           `json:"finished"`  // When the game finished
+	Events    []Event             `json:"events"`    // The events from oldest to newest
+	Questions []net.QuestionStats `json:"questions"` // The stats of each marked question
+}
+
+// ArchiveEnabled returns whether an archive directory has been configured
+func ArchiveEnabled() bool {
+	return ArchiveDir != ""
+}
+
+// hashKey hashes a host key for storing in an archive
+func hashKey(key string) string {
+	hash := sha256.Sum256([]byte(key))
+	return hex.EncodeToString(hash[:])
+}
+
+// archive writes the timeline of the finished game to a gzip compressed file in
+// the ArchiveDir. The file is written to a temporary file first so a partially
+// written archive is never read
+func archive(game finishedGame) error {
+	contents := archivedTimeline{
+		Id:        game.Id,
+		KeyHash:   hashKey(game.Key),
+		Finished:  game.Time,
+		Events:    game.Timeline.Copy(),
+		Questions: game.Timeline.Summary().Questions,
+	}
+	path := filepath.Join(ArchiveDir, fmt.Sprintf("%d-%s.json.gz", game.Time.Unix(), game.Id))
+	temp := path + ".tmp"
+	file, err := os.Create(temp)
+	if err != nil {
+		return err
+	}
+	writer := gzip.NewWriter(file)
+	err = json.NewEncoder(writer).Encode(contents)
+	if closeErr := writer.Close(); err == nil {
+		err = closeErr
+	}
+	if closeErr := file.Close(); err == nil {
+		err = closeErr
+	}
+	if err == nil {
+		err = os.Rename(temp, path)
+	}
+	if err != nil {
+		_ = os.Remove(temp)
+	}
+	return err
+}
+
+// archiveAll archives the provided finished games logging any that fail. Games
+// that fail to archive are lost the same as when archiving is disabled
+func archiveAll(games []finishedGame) {
+	for _, game := range games {
+		if err := archive(game); err != nil {
+			log.Printf("Failed to archive the timeline of game '%s': %s", game.Id, err)
+		}
+	}
+}
+
+// CompactTimelines archives the timelines of games that finished longer than
+// ArchiveAfter ago removing them from memory
+func CompactTimelines() error {
+	cutoff := time.Now().Add(-ArchiveAfter)
+	finishedLock.Lock() // Establish lock on the finished games
+	count := 0
+	for count < len(finished) && finished[count].Time.Before(cutoff) { // Finished games are oldest first
+		count++
+	}
+	old := append([]finishedGame{}, finished[:count]...)
+	finished = append(finished[:0:0], finished[count:]...)
+	finishedLock.Unlock() // Release lock
+	archiveAll(old)
+	return nil
+}
+
+// ArchiveFinished archives the timelines of every finished game still in memory
+// removing them from memory. Called when the server stops so they aren't lost
+func ArchiveFinished() {
+	finishedLock.Lock() // Establish lock on the finished games
+	games := finished
+	finished = nil
+	finishedLock.Unlock() // Release lock
+	archiveAll(games)
+}
+
+// findArchived loads the archived timeline of the game with the provided id and
+// host key. Returns nil if there is no match
+func findArchived(id Identifier, key string) *Timeline {
+	paths, err := filepath.Glob(filepath.Join(ArchiveDir, "*-"+id+".json.gz"))
+	if err != nil {
+		return nil
+	}
+	sort.Sort(sort.Reverse(sort.StringSlice(paths))) // Newest first as game ids can be reused
+	hash := hashKey(key)
+	for _, path := range paths {
+		contents, err := readArchive(path)
+		if err != nil {
+			log.Printf("Failed to read timeline archive '%s': %s", path, err)
+			continue
+		}
+		if contents.Id == id && SecretEquals(contents.KeyHash, hash) {
+			return &Timeline{Events: contents.Events, Questions: contents.Questions}
+		}
+	}
+	return nil
+}
+
+// readArchive decompresses and decodes the timeline archive at the provided path
+func readArchive(path string) (*archivedTimeline, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+	reader, err := gzip.NewReader(file)
+	if err != nil {
+		return nil, err
+	}
+	defer reader.Close()
+	var contents archivedTimeline
+	if err := json.NewDecoder(reader).Decode(&contents); err != nil {
+		return nil, err
+	}
+	return &contents, nil
+}
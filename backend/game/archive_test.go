@@ -0,0 +1,36 @@
+package game
+
+import (
+	"testing"
+	"time"
+)
+
+// TestArchiveTimeline checks that old finished timelines are moved into the
+// archive and can only be found again with the host key
+func TestArchiveTimeline(t *testing.T) {
+	defer func(dir string, games []finishedGame) { ArchiveDir, finished = dir, games }(ArchiveDir, finished)
+	ArchiveDir = t.TempDir()
+	timeline := &Timeline{}
+	timeline.RecordState(Started)
+	finished = []finishedGame{
+		{Id: "ARCH", Key: "key", Timeline: timeline, Time: time.Now().Add(-ArchiveAfter - time.Hour)},
+		{Id: "KEPT", Key: "key", Timeline: &Timeline{}, Time: time.Now()},
+	}
+	if err := CompactTimelines(); err != nil {
+		t.Fatal(err)
+	}
+	if len(finished) != 1 || finished[0].Id != "KEPT" {
+		t.Fatalf("expected only the recent game to stay in memory got %+v", finished)
+	}
+	archived := FindTimeline("ARCH", "key")
+	if archived == nil || len(archived.Events) != 1 || archived.Events[0].Type != EventState {
+		t.Fatalf("expected the archived timeline got %+v", archived)
+	}
+	if FindTimeline("ARCH", "wrong") != nil {
+		t.Error("expected the wrong host key to be rejected")
+	}
+	ArchiveFinished()
+	if len(finished) != 0 || FindTimeline("KEPT", "key") == nil {
+		t.Errorf("expected every finished timeline to be archived got %+v", finished)
+	}
+}
@@ -0,0 +1,137 @@
+package game
+
+import (
+	. "backend/tools"
+	"errors"
+	"fmt"
+	"regexp"
+)
+
+// Errors returned when answering a fill in the blanks question isn't possible
+var (
+	ErrBlanksQuestion = errors.New("fill in the blanks questions are answered with a word for each blank")
+	ErrNotBlanks      = errors.New("the question isn't a fill in the blanks question")
+)
+
+// AnswerBlanks sets the words the player wrote for each blank of the current
+// fill in the blanks question. Returns an error without answering if there isn't
+// a word for every blank or a TextError if any of them aren't allowed (see CleanText)
+func (player *Player) AnswerBlanks(game *Game, texts []string) error {
+	q := game.ActiveQuestion           // Retrieve the active question from the game
+	if q == nil || q.Question == nil { // If the game hasn't started or has finished
+		return ErrNoQuestion
+	}
+	if q.Question.Kind != KindBlanks {
+		return ErrNotBlanks
+	}
+	if player.HasAnswered(game) { // Keep the first answer when the player answers again
+		return player.repeatAnswer(game, q)
+	}
+	if len(texts) != len(q.Question.Blanks) {
+		return fmt.Errorf("write a word for each of the %d blanks", len(q.Question.Blanks))
+	}
+	cleaned := make([]string, len(texts))
+	words := make([]string, len(texts))
+	for i, text := range texts {
+		text, err := CleanText(text)
+		if err != nil {
+			blank := i
+			err.(*TextError).Blank = &blank
+			return err
+		}
+		cleaned[i], words[i] = text, NormalizeWords(text)
+	}
+	player.AnswerTime = Time() // Set the time of answer
+	if player.Blanks == nil {
+		player.Blanks = map[QuestionIndex][]string{}
+	}
+	player.Blanks[q.Index] = words
+	player.Answers[q.Index] = []AnswerIndex{}
+	if q.Question.FirstBonus > 0 && q.BlanksCredit(words) == 1 { // Only filling every blank can earn the bonus
+		q.ClaimFirstCorrect(player.Id)
+	}
+	game.Timeline.RecordQuestion(EventAnswer, q.Index, player.Id)
+	player.recordAnswer(game, q, RawAnswer{Blanks: cleaned})
+	player.acceptAnswer(game, q)
+	return nil
+}
+
+// BlanksCredit calculates the share of the points the provided words earn for a
+// fill in the blanks question. Each blank filled with one of its accepted answers
+// or matching one of its patterns earns a share unless the question is marked
+// exact where every blank must be
+func (question *ActiveQuestion) BlanksCredit(words []string) float64 {
+	blanks := question.Question.Blanks
+	if len(blanks) == 0 {
+		return 0
+	}
+	patterns := question.blankPatterns()
+	right := 0
+	for i, accepted := range blanks {
+		if i >= len(words) {
+			continue
+		}
+		if acceptsWord(accepted, words[i]) || (i < len(patterns) && matchesWord(patterns[i], words[i])) {
+			right++
+		}
+	}
+	if question.Question.Marking == MarkExact && right != len(blanks) {
+		return 0
+	}
+	return float64(right) / float64(len(blanks))
+}
+
+// acceptsWord checks whether the word matches any of the accepted answers ignoring
+// case and spacing the same way word cloud responses are grouped
+func acceptsWord(accepted []string, word string) bool {
+	word = NormalizeWords(word)
+	for _, answer := range accepted {
+		if NormalizeWords(answer) == word {
+			return true
+		}
+	}
+	return false
+}
+
+// blankPatterns compiles the accepted answer patterns of each blank the first
+// time they are needed. Patterns are checked when the quiz is validated so any
+// that fail to compile are skipped
+func (question *ActiveQuestion) blankPatterns() [][]*regexp.Regexp {
+	question.patternsOnce.Do(func() {
+		question.patterns = make([][]*regexp.Regexp, len(question.Question.Patterns))
+		for i, patterns := range question.Question.Patterns {
+			for _, pattern := range patterns {
+				if compiled, err := CompilePattern(pattern); err == nil {
+					question.patterns[i] = append(question.patterns[i], compiled)
+				}
+			}
+		}
+	})
+	return question.patterns
+}
+
+// matchesWord checks whether the word matches any of the compiled patterns
+func matchesWord(patterns []*regexp.Regexp, word string) bool {
+	word = NormalizeWords(word)
+	for _, pattern := range patterns {
+		if pattern.MatchString(word) {
+			return true
+		}
+	}
+	return false
+}
+
+// revealedBlanks returns the first accepted answer of each blank of the question
+// to show once it has been marked
+func revealedBlanks(question *QuestionData) []string {
+	if len(question.Blanks) == 0 {
+		return nil
+	}
+	revealed := make([]string, len(question.Blanks))
+	for i, accepted := range question.Blanks {
+		if len(accepted) > 0 {
+			revealed[i] = accepted[0]
+		}
+	}
+	return revealed
+}
@@ -0,0 +1,112 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"errors"
+)
+
+// Errors returned when buzzing or judging a buzzer question isn't possible
+var (
+	ErrBuzzerQuestion = errors.New("buzzer questions are answered by buzzing")
+	ErrNotBuzzer      = errors.New("the question isn't a buzzer question")
+	ErrAlreadyBuzzed  = errors.New("you have already buzzed")
+	ErrNoBuzzer       = errors.New("nobody is answering the question")
+)
+
+// pushBuzzer adds the player to the end of the buzzers. Returns whether it is
+// their turn to answer because nobody else was waiting
+func (question *ActiveQuestion) pushBuzzer(player Identifier) bool {
+	question.buzzLock.Lock()         // Establish lock on the buzzers
+	defer question.buzzLock.Unlock() // Defer the releasing of the lock
+	question.buzzers = append(question.buzzers, player)
+	return len(question.buzzers) == 1
+}
+
+// popBuzzer removes the player whose turn it is to answer from the buzzers.
+// Returns false if nobody is waiting to answer
+func (question *ActiveQuestion) popBuzzer() (Identifier, bool) {
+	question.buzzLock.Lock()         // Establish lock on the buzzers
+	defer question.buzzLock.Unlock() // Defer the releasing of the lock
+	if len(question.buzzers) == 0 {
+		return "", false
+	}
+	player := question.buzzers[0]
+	question.buzzers = question.buzzers[1:]
+	return player, true
+}
+
+// Buzzer returns the id of the player whose turn it is to answer or an empty
+// string if nobody is waiting to answer
+func (question *ActiveQuestion) Buzzer() Identifier {
+	question.buzzLock.Lock()         // Establish lock on the buzzers
+	defer question.buzzLock.Unlock() // Defer the releasing of the lock
+	if len(question.buzzers) == 0 {
+		return ""
+	}
+	return question.buzzers[0]
+}
+
+// Buzz adds the player to the players waiting to answer the current buzzer
+// question. Each player can only buzz once for each question. The player is
+// announced to everyone if it is their turn to answer straight away
+func (player *Player) Buzz(game *Game) error {
+	q := game.ActiveQuestion // Retrieve the active question from the game
+	if q == nil || q.Question == nil || q.Skipped || q.Marked {
+		return ErrNoQuestion
+	}
+	if q.Question.Kind != KindBuzzer {
+		return ErrNotBuzzer
+	}
+	if player.HasAnswered(game) { // Buzzing counts as answering so it can't be repeated
+		return ErrAlreadyBuzzed
+	}
+	player.AnswerTime = Time() // Set the time of the buzz
+	player.Answers[q.Index] = []AnswerIndex{}
+	game.Timeline.RecordQuestion(EventBuzz, q.Index, player.Id)
+	player.acceptAnswer(game, q)
+	if q.pushBuzzer(player.Id) { // Nobody else is answering so it's their turn
+		game.Broadcast(net.BuzzerPacket(player.Id, player.Name), true)
+	}
+	return nil
+}
+
+// JudgeBuzz marks the answer of the player whose turn it is to answer the
+// current buzzer question. A correct answer ends the question and earns the
+// player the points. A wrong answer passes the turn to the next player that
+// buzzed and ends the question once every player has answered wrong
+func (game *Game) JudgeBuzz(correct bool) error {
+	q := game.ActiveQuestion
+	if q == nil || q.Question == nil || q.Skipped || q.Marked {
+		return ErrNoQuestion
+	}
+	if q.Question.Kind != KindBuzzer {
+		return ErrNotBuzzer
+	}
+	id, ok := q.popBuzzer()
+	if !ok {
+		return ErrNoBuzzer
+	}
+	game.Timeline.RecordQuestion(EventJudge, q.Index, id)
+	if correct {
+		q.ClaimFirstCorrect(id)
+		game.SkipQuestion() // Move on to marking the question
+		return nil
+	}
+	for { // Pass the turn to the next player that is still in the game
+		next := q.Buzzer()
+		if next == "" {
+			break
+		}
+		if player := game.Players.Get(next); player != nil {
+			game.Broadcast(net.BuzzerPacket(player.Id, player.Name), true)
+			return nil
+		}
+		q.popBuzzer() // The player left while waiting to answer
+	}
+	game.Broadcast(net.BuzzerPacket("", ""), true)
+	if game.HaveAllAnswered() { // Every player buzzed and answered wrong
+		game.SkipQuestion()
+	}
+	return nil
+}
@@ -0,0 +1,66 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"sync/atomic"
+)
+
+// MaxPlayers The most players that can join a single game. 0 for no limit
+var MaxPlayers = EnvIntOrDefault("QUIZLER_MAX_PLAYERS", 0)
+
+// CapacityWarning The percentage of MaxPlayers at which the host is warned
+// that the game is nearly full
+var CapacityWarning = EnvIntOrDefault("QUIZLER_CAPACITY_WARNING", 90)
+
+// The capacity events that have been sent to the host
+const (
+	capacityNone     int32 = iota // There is room in the game
+	capacityWarned                // The host was warned the game is nearly full
+	capacityRejected              // The host was told players are being turned away
+)
+
+// GetMaxPlayers returns the most players that can join the game. Games that
+// didn't choose their own limit use MaxPlayers. 0 for no limit
+func (game *Game) GetMaxPlayers() int {
+	if limit := game.Settings().MaxPlayers; limit > 0 {
+		return limit
+	}
+	return MaxPlayers
+}
+
+// IsFull checks whether the game has as many players as it allows
+func (game *Game) IsFull() bool {
+	limit := game.GetMaxPlayers()
+	return limit > 0 && game.Players.Count() >= limit
+}
+
+// RejectFull tells the host the first time a player is turned away because the
+// game is full so they can start the game or make room
+func (game *Game) RejectFull() {
+	if atomic.SwapInt32(&game.capacity, capacityRejected) != capacityRejected {
+		game.Fanout.Push(net.GameFullPacket(game.Players.Count(), game.GetMaxPlayers()), game.HostTargets()...)
+	}
+}
+
+// checkCapacity warns the host once the game reaches CapacityWarning percent of
+// its players and clears the sent events once players leave so the host is told
+// again if the game fills back up. Called whenever a player joins or is removed
+func (game *Game) checkCapacity() {
+	limit := game.GetMaxPlayers()
+	if limit <= 0 { // Games without a limit are never full
+		atomic.StoreInt32(&game.capacity, capacityNone)
+		return
+	}
+	count := game.Players.Count()
+	if count*100 < limit*CapacityWarning { // Below the warning threshold
+		atomic.StoreInt32(&game.capacity, capacityNone)
+		return
+	}
+	if count < limit { // Players can join again
+		atomic.CompareAndSwapInt32(&game.capacity, capacityRejected, capacityWarned)
+	}
+	if atomic.CompareAndSwapInt32(&game.capacity, capacityNone, capacityWarned) {
+		game.Fanout.Push(net.CapacityWarningPacket(count, limit), game.HostTargets()...)
+	}
+}
@@ -0,0 +1,171 @@
+package game
+
+import (
+	"backend/net"
+	. "github.com/jacobtread/gowsps"
+	"log"
+	"sync"
+)
+
+// Buffer sizes of the fanout
+const (
+	FanoutBuffer     = 64 // The number of deliveries that can be queued before senders wait
+	ConnectionBuffer = 64 // The number of packets that can wait for a single connection before its numbered events are dropped
+)
+
+type (
+	// Delivery A structure representing a packet queued to be sent to each of
+	// the target connections
+	Delivery struct {
+		Packet  Packet        // The packet to send
+		Targets []*Connection // The connections to send the packet to
+	}
+
+	// Fanout A structure representing the worker that sends the packets for a
+	// game. Packets are delivered to each connection in the order they were
+	// pushed so that game events can be sent without holding the player store
+	// lock. Each connection has its own queue so a slow connection only delays
+	// its own packets and never the game loop
+	Fanout struct {
+		Lock     sync.Mutex              // A lock for ensuring nothing is pushed after closing
+		Queue    chan Delivery           // The queue of deliveries waiting to be handed to the connections
+		Closed   bool                    // Whether the fanout has been closed
+		outboxes map[*Connection]*Outbox // The packets waiting to be sent to each connection
+	}
+
+	// Outbox A structure representing the packets waiting to be sent to a single
+	// connection. Numbered events are dropped once ConnectionBuffer packets are
+	// waiting as clients can ask to resync them. Every other packet is always
+	// kept because it can't be recovered
+	Outbox struct {
+		packets []Packet   // The packets waiting to be sent oldest first
+		closed  bool       // Whether the outbox has been closed
+		lock    sync.Mutex // A lock for modifying the packets
+		ready   sync.Cond  // Signalled when packets are added or the outbox is closed
+	}
+)
+
+// NewFanout Creates a new fanout and starts the goroutine delivering its packets
+func NewFanout() *Fanout {
+	fanout := &Fanout{Queue: make(chan Delivery, FanoutBuffer)}
+	go fanout.Run()
+	return fanout
+}
+
+// Push queues the packet to be sent to each of the targets. Packets pushed
+// after the fanout is closed are discarded
+func (fanout *Fanout) Push(packet Packet, targets ...*Connection) {
+	fanout.Lock.Lock()                      // Establish a lock so the queue isn't closed while pushing
+	defer fanout.Lock.Unlock()              // Defer the releasing of the lock
+	if fanout.Closed || len(targets) == 0 { // If the game has already been stopped or there is no one to send to
+		return
+	}
+	fanout.Queue <- Delivery{Packet: packet, Targets: targets}
+}
+
+// Run hands each of the queued deliveries to the outboxes of their target
+// connections until the fanout is closed
+func (fanout *Fanout) Run() {
+	if fanout.outboxes == nil {
+		fanout.outboxes = map[*Connection]*Outbox{}
+	}
+	for delivery := range fanout.Queue { // Iterate over the deliveries as they are queued
+		for _, target := range delivery.Targets {
+			fanout.deliver(target, delivery.Packet)
+		}
+	}
+	for _, outbox := range fanout.outboxes { // Stop sending once the queued packets are sent
+		outbox.Close()
+	}
+}
+
+// deliver adds the packet to the outbox of the target connection starting the
+// goroutine that sends to the connection the first time it is a target
+func (fanout *Fanout) deliver(target *Connection, packet Packet) {
+	outbox, exists := fanout.outboxes[target]
+	if !exists {
+		outbox = NewOutbox()
+		fanout.outboxes[target] = outbox
+		go outbox.Drain(target)
+	}
+	if !outbox.Add(packet) {
+		log.Printf("Dropped event for a connection that isn't keeping up")
+	}
+}
+
+// NewOutbox Creates a new empty outbox
+func NewOutbox() *Outbox {
+	outbox := &Outbox{}
+	outbox.ready.L = &outbox.lock
+	return outbox
+}
+
+// Add queues the packet to be sent. Returns false when the packet is a numbered
+// event that was dropped because the connection isn't keeping up
+func (outbox *Outbox) Add(packet Packet) bool {
+	outbox.lock.Lock()         // Establish lock on the packets
+	defer outbox.lock.Unlock() // Defer the releasing of the lock
+	if packet.Id == net.SSequenced && len(outbox.packets) >= ConnectionBuffer {
+		return false
+	}
+	outbox.packets = append(outbox.packets, packet)
+	outbox.ready.Signal()
+	return true
+}
+
+// Len returns the number of packets waiting to be sent
+func (outbox *Outbox) Len() int {
+	outbox.lock.Lock()         // Establish lock on the packets
+	defer outbox.lock.Unlock() // Defer the releasing of the lock
+	return len(outbox.packets)
+}
+
+// Close stops the outbox once the packets already waiting have been sent
+func (outbox *Outbox) Close() {
+	outbox.lock.Lock() // Establish lock on the packets
+	outbox.closed = true
+	outbox.ready.Signal()
+	outbox.lock.Unlock() // Release lock
+}
+
+// Drain sends each of the packets in the outbox to the target connection in
+// order until the outbox is closed
+func (outbox *Outbox) Drain(target *Connection) {
+	for {
+		outbox.lock.Lock() // Establish lock on the packets
+		for len(outbox.packets) == 0 && !outbox.closed {
+			outbox.ready.Wait()
+		}
+		if len(outbox.packets) == 0 { // Closed with nothing left to send
+			outbox.lock.Unlock() // Release lock
+			return
+		}
+		packet := outbox.packets[0]
+		outbox.packets[0] = Packet{} // Release the packet once it is sent
+		outbox.packets = outbox.packets[1:]
+		outbox.lock.Unlock() // Release lock
+		send(target, packet)
+	}
+}
+
+// send sends the packet to the target. A panic while sending is logged so one
+// broken connection can't stop the rest of the game packets from being sent
+func send(target *Connection, packet Packet) {
+	defer func() {
+		if err := recover(); err != nil {
+			log.Printf("Failed to send packet %d: %v", packet.Id, err)
+		}
+	}()
+	target.Send(packet)
+}
+
+// Close stops the fanout once the already queued deliveries have been sent.
+// Closing more than once does nothing
+func (fanout *Fanout) Close() {
+	fanout.Lock.Lock()
+	defer fanout.Lock.Unlock()
+	if !fanout.Closed {
+		fanout.Closed = true
+		close(fanout.Queue)
+	}
+}
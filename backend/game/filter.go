@@ -0,0 +1,150 @@
+package game
+
+import (
+	. "backend/tools"
+	"bufio"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"sync"
+)
+
+// NameFilter Decides whether players and hosts can use a name. The filter is
+// chosen with QUIZLER_NAME_FILTER so deployments can use word lists for their own
+// languages or no filter at all
+type NameFilter interface {
+	// Allowed checks whether the provided name can be used
+	Allowed(name string) bool
+}
+
+// NameFilterFactory Creates a name filter from the value of QUIZLER_NAME_FILTER_CONFIG
+type NameFilterFactory func(config string) (NameFilter, error)
+
+// Configuration for choosing the name filter
+var (
+	NameFilterKind   = EnvOrDefault("QUIZLER_NAME_FILTER", "none")    // The name of the filter to use
+	NameFilterConfig = EnvOrDefault("QUIZLER_NAME_FILTER_CONFIG", "") // The configuration passed to the filter (e.g. the word list directory)
+)
+
+var (
+	filterLock            = sync.RWMutex{} // A lock for replacing the filter and the factories
+	nameFilter NameFilter = NoNameFilter{} // The filter names are checked with
+)
+
+// nameFilters The factories for the filters that can be chosen by name
+var nameFilters = map[string]NameFilterFactory{
+	"none":  func(string) (NameFilter, error) { return NoNameFilter{}, nil },
+	"words": LoadWordFilter,
+}
+
+// RegisterNameFilter makes a filter available to QUIZLER_NAME_FILTER under the
+// provided name. Builds that include other filters register them on init
+func RegisterNameFilter(name string, factory NameFilterFactory) {
+	filterLock.Lock()
+	nameFilters[name] = factory
+	filterLock.Unlock()
+}
+
+// SetupNameFilter replaces the name filter with the filter registered under the
+// provided kind created using the provided config
+func SetupNameFilter(kind string, config string) error {
+	filterLock.RLock()
+	factory, exists := nameFilters[kind]
+	filterLock.RUnlock()
+	if !exists {
+		return fmt.Errorf("unknown name filter '%s'", kind)
+	}
+	filter, err := factory(config)
+	if err != nil {
+		return err
+	}
+	filterLock.Lock() // Establish lock on the filter
+	nameFilter = filter
+	filterLock.Unlock() // Release lock
+	return nil
+}
+
+// IsNameAllowed checks the name with the configured NameFilter
+func IsNameAllowed(name string) bool {
+	filterLock.RLock()         // Establish a read lock on the filter
+	defer filterLock.RUnlock() // Defer the releasing of the read lock
+	return nameFilter.Allowed(name)
+}
+
+// NoNameFilter A filter which allows every name
+type NoNameFilter struct{}
+
+// Allowed always allows the name
+func (NoNameFilter) Allowed(string) bool {
+	return true
+}
+
+// WordFilter A filter which rejects names containing any of its words. Words are
+// compared normalized so they can't be avoided by spacing, case or characters
+// that look like the letters of the word
+type WordFilter struct {
+	Words map[string][]string // The normalized words for each language
+}
+
+// LoadWordFilter loads a word filter from a directory with a text file for each
+// language (e.g. en.txt, fr.txt) containing one word per line. Empty lines and
+// lines starting with # are ignored. Names are checked against every language
+// since every player in the game can see them
+func LoadWordFilter(dir string) (NameFilter, error) {
+	if dir == "" {
+		return nil, errors.New("the words name filter needs QUIZLER_NAME_FILTER_CONFIG set to a directory of word lists")
+	}
+	paths, err := filepath.Glob(filepath.Join(dir, "*.txt"))
+	if err != nil {
+		return nil, err
+	}
+	if len(paths) == 0 {
+		return nil, fmt.Errorf("no word lists were found in '%s'", dir)
+	}
+	filter := &WordFilter{Words: map[string][]string{}}
+	for _, path := range paths {
+		language := strings.TrimSuffix(filepath.Base(path), ".txt")
+		words, err := readWords(path)
+		if err != nil {
+			return nil, fmt.Errorf("failed to load the %s word list: %w", language, err)
+		}
+		filter.Words[language] = words
+	}
+	return filter, nil
+}
+
+// readWords reads the normalized words from a word list file
+func readWords(path string) ([]string, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+	var words []string
+	scanner := bufio.NewScanner(file)
+	for scanner.Scan() {
+		line := strings.TrimSpace(scanner.Text())
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		if word := NormalizeName(line); word != "" {
+			words = append(words, word)
+		}
+	}
+	return words, scanner.Err()
+}
+
+// Allowed checks that the name doesn't contain any of the words
+func (filter *WordFilter) Allowed(name string) bool {
+	normalized := NormalizeName(name)
+	for _, words := range filter.Words {
+		for _, word := range words {
+			if strings.Contains(normalized, word) {
+				return false
+			}
+		}
+	}
+	return true
+}
@@ -0,0 +1,46 @@
+package game
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+// TestWordFilter checks that names containing words from any of the word lists
+// are rejected in any form and that unknown filters can't be chosen
+func TestWordFilter(t *testing.T) {
+	defer func(previous NameFilter) { nameFilter = previous }(nameFilter)
+	dir := t.TempDir()
+	lists := map[string]string{
+		"en.txt": "# English words\nbadword\n\n",
+		"fr.txt": "motinterdit\n",
+	}
+	for name, contents := range lists {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(contents), 0600); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := SetupNameFilter("words", dir); err != nil {
+		t.Fatal(err)
+	}
+	tests := []struct {
+		name    string
+		allowed bool
+	}{
+		{"Jacob", true},
+		{"Badword", false},
+		{"xBadw0rdx", false},
+		{"Mot Interdit", false},
+	}
+	for _, test := range tests {
+		if allowed := IsNameAllowed(test.name); allowed != test.allowed {
+			t.Errorf("%q: got allowed %t want %t", test.name, allowed, test.allowed)
+		}
+	}
+	if err := SetupNameFilter("unknown", ""); err == nil {
+		t.Error("expected an unknown filter to be rejected")
+	}
+	if err := SetupNameFilter("words", t.TempDir()); err == nil {
+		t.Error("expected a directory without word lists to be rejected")
+	}
+}
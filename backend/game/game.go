@@ -6,7 +6,9 @@ import (
 	. "github.com/jacobtread/gowsps"
 	"log"
 	"math"
-	"strings"
+	"regexp"
+	"runtime/debug"
+	"sort"
 	"sync"
 	"time"
 )
@@ -18,18 +20,59 @@ const (
 	Started                   // The game is started an in progress
 	Stopped                   // The game has Stopped and is ready to shut down
 	DoesNotExist              // The game doesn't exist
+	Paused                    // The host disconnected and the game is waiting for them to reconnect
+)
+
+// Enum for how games handle multiple players joining from the same device
+const (
+	DevicesAllowed DeviceMode = iota // Any number of players can join from the same device
+	DevicesWarn                      // The host is warned when players join from the same device
+	DevicesBlock                     // Players can't join from a device that is already in use
+)
+
+// Modes for what players are shown after each question is marked
+const (
+	RevealLeaderboard RevealMode = iota // Players see whether they were right, the correct answers and every score
+	RevealAnswers                       // Players see whether they were right, the correct answers and only their own score
+	RevealResult                        // Players only see whether they were right until the game is over
+)
+
+// Modes for how the points for each question are decided
+const (
+	ScoreTimed ScoringMode = iota // Questions are timed and quicker answers earn bonus points
+	ScoreExam                     // Questions have no time limit and every correct answer earns the same points
 )
 
 // Game a structure representing the game itself
 type Game struct {
-	Host           *Connection     // The connection to the game host
-	Id             Identifier      // The unique identifier / game code for this game
-	Title          string          // The title / name of this game
-	Questions      []QuestionData  // An array of the questions for this game
-	Players        PlayerStore     // The player store instance
-	StartTime      time.Duration   // The system time in ms of when the game was created
-	State          State           // The current state of the game
-	ActiveQuestion *ActiveQuestion // The currently active question nil by default
+	Host           *Connection      // The connection to the game host
+	Id             Identifier       // The unique identifier / game code for this game
+	Title          string           // The title / name of this game
+	Questions      []QuestionData   // An array of the questions for this game
+	Players        PlayerStore      // The player store instance
+	StartTime      time.Duration    // The system time in ms of when the game was created
+	State          State            // The current state of the game
+	ActiveQuestion *ActiveQuestion  // The currently active question nil by default
+	Fanout         *Fanout          // The worker that sends the game packets
+	HostName       string           // The display name of the host or empty if they didn't choose one
+	Key            string           // The secret key the host can use to access the game outside the socket
+	Timeline       *Timeline        // The events that happened during the game
+	Finished       bool             // Whether the game timeline has been kept after the game finished
+	PausedState    State            // The state to return to when the paused game is resumed
+	PausedTime     time.Duration    // The system time of when the game was paused
+	Displays       []*Connection    // The read-only connections showing the host view of the game
+	DisplaysLock   GameLock         // A lock for modifying the displays
+	Media          []string         // The ids of the uploaded images the game uses which are released once it finishes
+	Style          TextStyle        // How the text of the quiz should be shown
+	Rounds         []Round          // The quizzes of the playlist in the order they are played (empty for a single quiz)
+	roundScores    ScoreMap         // The scores of the players when the current quiz of the playlist started
+	warmUp         *WarmUp          // The unscored poll being asked while waiting for players or nil
+	warmUpLock     sync.Mutex       // A lock for modifying the warm-up poll
+	sequence       sequence         // The numbered events sent to everyone kept for clients that miss them
+	answerLog      answerLog        // Every answer exactly as it was submitted kept for the host to review
+	capacity       int32            // The capacity events already sent to the host
+	settings       net.GameSettings // The settings the host can change while the game is waiting for players
+	settingsLock   sync.RWMutex     // A lock for modifying the settings
 }
 
 // ActiveQuestion a structure representing the currently served question
@@ -38,10 +81,40 @@ type ActiveQuestion struct {
 	Index     QuestionIndex // The index of this question in the array of questions
 	StartTime time.Duration // The time that this question started at
 	Marked    bool          // Whether the question has been marked
+	Skipped   bool          // Whether the question was ended early by the host or everyone answering
+
+	firstLock    sync.Mutex         // A lock for claiming the first correct answer
+	firstCorrect Identifier         // The id of the first player to answer correctly or empty if nobody has
+	buzzLock     sync.Mutex         // A lock for modifying the buzzers
+	buzzers      []Identifier       // The players waiting to answer a buzzer question in the order they buzzed
+	wordsLock    sync.Mutex         // A lock for modifying the words
+	words        map[string]int     // The number of players that wrote each response to a word cloud question
+	patternsOnce sync.Once          // Compiles the accepted answer patterns the first time they are needed
+	patterns     [][]*regexp.Regexp // The compiled accepted answer patterns for each blank
+}
+
+// ClaimFirstCorrect records the player as the first to answer the question
+// correctly unless another player already was. Returns whether they were first
+func (question *ActiveQuestion) ClaimFirstCorrect(player Identifier) bool {
+	question.firstLock.Lock()         // Establish lock on the first correct answer
+	defer question.firstLock.Unlock() // Defer the releasing of the lock
+	if question.firstCorrect != "" {
+		return false
+	}
+	question.firstCorrect = player
+	return true
+}
+
+// FirstCorrect returns the id of the first player to answer the question
+// correctly or an empty string if nobody has
+func (question *ActiveQuestion) FirstCorrect() Identifier {
+	question.firstLock.Lock()         // Establish lock on the first correct answer
+	defer question.firstLock.Unlock() // Defer the releasing of the lock
+	return question.firstCorrect
 }
 
 // GamesLock A lock for modifying the games map
-var GamesLock = sync.RWMutex{}
+var GamesLock = GameLock{Name: "games"}
 
 // Games A map of games to their identifiers
 var Games = map[Identifier]*Game{}
@@ -74,16 +147,27 @@ func Get(identifier Identifier) *Game {
 // New Creates a new game instance with the provided host, title, and questions.
 // also starts a new goroutine for the games loop, adds it to Games and returns
 // a reference to the game
-func New(host *Connection, title string, questions []QuestionData) *Game {
+func New(host *Connection, title string, questions []QuestionData, hostName string, settings net.GameSettings) *Game {
 	id := CreateGameId() // Create a new unique game ID
+	for i := range questions {
+		if questions[i].Kind == KindOrder { // Players must not be sent the answers already in order
+			questions[i] = questions[i].ShuffleOrder()
+		}
+	}
 	game := Game{
-		Host:      host,
-		Id:        id,
-		Title:     title,
-		Questions: questions,
-		Players:   NewPlayerStore(),
-		StartTime: Time(),
-		State:     Waiting,
+		Host:         host,
+		Id:           id,
+		Title:        title,
+		Questions:    questions,
+		Players:      NewPlayerStore(),
+		StartTime:    Time(),
+		State:        Waiting,
+		Fanout:       NewFanout(),
+		HostName:     hostName,
+		Key:          CreateSecret(16),
+		Timeline:     &Timeline{},
+		DisplaysLock: GameLock{Name: "displays"},
+		settings:     settings,
 	}
 	GamesLock.Lock() // Establish write lock on the games map
 	// Store the game in the games map
@@ -93,60 +177,177 @@ func New(host *Connection, title string, questions []QuestionData) *Game {
 	return &game
 }
 
-// Join adds a new player to the game with the provided connection and name
-// and returns a reference to the player
-func (game *Game) Join(conn *Connection, name string) *Player {
-	player := game.Players.Create(conn, name) // Create a new player
+// Join adds a new player to the game with the provided connection, name and
+// device and returns a reference to the player. The sequence lock is held until
+// the player is sent the joined game packet so the first numbered event they
+// receive is always the one after the number it includes
+func (game *Game) Join(conn *Connection, name string, device Device) *Player {
+	data := game.JoinData(false)
+	game.sequence.lock.Lock() // Establish lock on the sequence
+	data.Seq = game.sequence.last
+	player := game.Players.Create(conn, name, device) // Create a new player
 	// Send the initial state of the game
-	player.Net.Send(net.GameStatePacket(game.State))
+	game.Fanout.Push(net.GameStatePacket(game.State), player.Net)
 	// Send the player their self player data
-	player.Net.Send(net.PlayerDataPacket(player.Id, player.Name, net.SelfMode))
+	game.Fanout.Push(net.PlayerDataPacket(player.Id, player.Name, net.SelfMode), player.Net)
+	// Tell the player they've joined the game as a player
+	game.Fanout.Push(net.JoinGamePacket(data), player.Net)
+	// Send the player the data for each other player in the game
+	game.Players.ForEach(func(id Identifier, other *Player) {
+		if id != player.Id {
+			game.Fanout.Push(net.PlayerDataPacket(id, other.Name, net.AddMode), player.Net)
+		}
+	})
+	game.sequence.lock.Unlock() // Release lock
 	// Information all other connections that this new player was added
 	game.BroadcastExcluding(player.Id, net.PlayerDataPacket(player.Id, name, net.AddMode), true)
-	log.Printf("Player '%s' has joined '%s' (%s) given id '%s'", name, game.Title, game.Id, player.Id)
+	game.Broadcast(net.PlayerCountPacket(game.Players.Count()), true)
+	game.checkCapacity()
+	game.Timeline.Record(Event{Type: EventJoin, Player: player.Id, Name: name})
+	log.Printf("Player '%s' (%s) has joined '%s' (%s) given id '%s'", name, device.Address, game.Title, game.Id, player.Id)
 	return player
 }
 
-// IsNameTaken checks the game players to see if any other players already
-// have a matching name (case-insensitive)
-func (game *Game) IsNameTaken(name string) bool {
-	return game.Players.AnyMatch(func(player *Player) bool {
-		return strings.EqualFold(player.Name, name)
+// JoinData creates the data sent to a client joining the game. The host key
+// is only included for the owner
+func (game *Game) JoinData(owner bool) net.JoinGameData {
+	duration := EstimateDuration(game.Questions)
+	data := net.JoinGameData{
+		GameSettings: game.Settings(),
+		TextStyle:    game.Style.For(""),
+		Rounds:       game.RoundTitles(),
+		Owner:        owner,
+		Id:           game.Id,
+		Title:        game.Title,
+		Host:         game.HostName,
+		Questions:    len(game.Questions),
+		Duration:     duration.Milliseconds(),
+		Seq:          game.Sequence(),
+	}
+	if owner {
+		data.TooLong = IsTooLong(duration)
+		data.Key = game.Key
+	}
+	return data
+}
+
+// Info creates the public details of the game that anyone who knows the game
+// code can see before joining. Nothing that only the host should see is included
+func (game *Game) Info() GameInfo {
+	return GameInfo{
+		TextStyle:  game.Style.For(""),
+		Id:         game.Id,
+		Title:      game.Title,
+		Host:       game.HostName,
+		State:      game.State,
+		Players:    game.Players.Count(),
+		MaxPlayers: game.GetMaxPlayers(),
+		Questions:  len(game.Questions),
+		Joinable:   game.State == Waiting && !game.IsFull(),
+		Locales:    Locales(game.Questions),
+	}
+}
+
+// MaxDisplays The maximum number of display connections a game can have
+const MaxDisplays = 4
+
+// HostTargets Collects the connections that receive the host view of the game
+// which is the host when it is connected and every display
+func (game *Game) HostTargets() []*Connection {
+	var targets []*Connection
+	if game.Host != nil { // The host is missing while the game is paused
+		targets = append(targets, game.Host)
+	}
+	return append(targets, game.DisplayTargets()...)
+}
+
+// DisplayTargets Collects the display connections of the game
+func (game *Game) DisplayTargets() []*Connection {
+	game.DisplaysLock.RLock()         // Establish a read lock on the displays
+	defer game.DisplaysLock.RUnlock() // Defer the releasing of the read lock
+	return append([]*Connection{}, game.Displays...)
+}
+
+// AddDisplay adds a read-only display connection to the game and sends it the
+// current state of the game. Returns false if the game already has MaxDisplays
+func (game *Game) AddDisplay(conn *Connection) bool {
+	game.DisplaysLock.Lock() // Establish write lock on the displays
+	if len(game.Displays) >= MaxDisplays {
+		game.DisplaysLock.Unlock() // Release write lock
+		return false
+	}
+	game.Displays = append(game.Displays, conn)
+	game.DisplaysLock.Unlock() // Release write lock
+	game.Players.ForEach(func(id Identifier, player *Player) {
+		game.Fanout.Push(net.PlayerDataPacket(player.Id, player.Name, net.AddMode), conn)
 	})
+	game.Fanout.Push(net.StateSyncPacket(game.StateSync(nil)), conn)
+	log.Printf("Display connected to game '%s' (%s)", game.Title, game.Id)
+	return true
 }
 
-// Broadcast sends the provided packet to all the players in the game
-func (game *Game) Broadcast(packet Packet, host bool) {
-	// Iterate over all the players
+// RemoveDisplay removes the display connection from the game
+func (game *Game) RemoveDisplay(conn *Connection) {
+	game.DisplaysLock.Lock() // Establish write lock on the displays
+	for i, display := range game.Displays {
+		if display == conn {
+			game.Displays = append(game.Displays[:i:i], game.Displays[i+1:]...)
+			break
+		}
+	}
+	game.DisplaysLock.Unlock() // Release write lock
+}
+
+// FindDevice finds a player that joined from the provided device. Returns nil
+// if there is no player using the device or the game allows shared devices
+func (game *Game) FindDevice(device Device) *Player {
+	if game.Settings().Devices == DevicesAllowed { // Shared devices are allowed
+		return nil
+	}
+	var found *Player
 	game.Players.ForEach(func(id Identifier, player *Player) {
-		player.Net.Send(packet) // Send the packet to the player
+		if player.Device == device { // If the player joined from the same device
+			found = player
+		}
 	})
-	if host { // If this packet should also be sent to the host
-		// Send the host the packet as well
-		game.Host.Send(packet)
+	return found
+}
+
+// Broadcast sends the provided packet to all the players in the game. Packets
+// that are also sent to the host are numbered in the game event sequence
+func (game *Game) Broadcast(packet Packet, host bool) {
+	if host { // Events sent to everyone are numbered so clients can tell when they missed one
+		game.broadcastSequenced(packet)
+		return
 	}
+	game.BroadcastExcluding("", packet, host)
 }
 
 // BroadcastExcluding sends the provided packet to all the players in the game
 // excluding any players that match the excluded id. The host parameter determines
-// whether this packet will also be sent to the host of the game
+// whether this packet will also be sent to the host of the game. The packet is
+// queued on the game fanout so the players lock is only held while collecting
+// the connections
 func (game *Game) BroadcastExcluding(exclude Identifier, packet Packet, host bool) {
+	var targets []*Connection
 	// Iterate over all the players
 	game.Players.ForEach(func(id Identifier, player *Player) {
 		if id != exclude { // If the player id != the excluded id
-			player.Net.Send(packet)
+			targets = append(targets, player.Net)
 		}
 	})
 	if host { // If this packet should also be sent to the host
-		// Send the host the packet as well
-		game.Host.Send(packet)
+		// Send the host and its displays the packet as well
+		targets = append(targets, game.HostTargets()...)
 	}
+	game.Fanout.Push(packet, targets...)
 }
 
 // Start Marks the game as Starting and begins the startup countdown and
 // time sync on the client's
 func (game *Game) Start() {
 	log.Printf("Game '%s' (%s) moving into starting state", game.Title, game.Id)
+	game.EndWarmUp() // Votes can't change once the game is starting
 	game.SetState(Starting)
 	game.StartTime = Time()
 }
@@ -156,19 +357,57 @@ const (
 	StartDelay   = 5 * time.Second  // The time to wait before starting the game
 	QuestionTime = 10 * time.Second // The time to display each question for
 	SyncDelay    = 2 * time.Second  // The delay to wait between each time sync
-	MarkTime     = 3 * time.Second  // The time to display the marking screen for
 	BonusTime    = 5 * time.Second  // The time the player can earn a bonus score within
 )
 
+// MarkTime The time to display the marking screen for when questions don't
+// choose their own review time
+var MarkTime = time.Duration(EnvIntOrDefault("QUIZLER_REVIEW_TIME", 3)) * time.Second
+
+// ReviewTime The time to display the marking screen for after the provided
+// question is marked
+func ReviewTime(question *QuestionData) time.Duration {
+	if question != nil && question.Review > 0 {
+		return time.Duration(question.Review) * time.Second
+	}
+	return MarkTime
+}
+
+// HostGrace The number of seconds a game is paused for waiting for its host to
+// reconnect before it is stopped. 0 stops games as soon as the host disconnects
+var HostGrace = EnvIntOrDefault("QUIZLER_HOST_GRACE", 60)
+
+// MaxDuration The longest a game is expected to run for in minutes before hosts are
+// warned that it may not fit the time they have (e.g. a class period). 0 for no limit
+var MaxDuration = EnvIntOrDefault("QUIZLER_MAX_DURATION", 0)
+
+// EstimateDuration Estimates how long a game with the provided questions will
+// take from the start countdown to the end of reviewing the last question
+func EstimateDuration(questions []QuestionData) time.Duration {
+	duration := StartDelay
+	for i := range questions {
+		duration += QuestionTime + ReviewTime(&questions[i])
+	}
+	return duration
+}
+
+// IsTooLong Checks whether the provided estimated duration is longer than
+// MaxDuration allows
+func IsTooLong(duration time.Duration) bool {
+	return MaxDuration > 0 && duration > time.Duration(MaxDuration)*time.Minute
+}
+
 // The minimum and maximum points that can be
 // awarded for each question
 const (
-	Points      uint32  = 100 // The default number of points to award
-	BonusPoints float64 = 200 // The maximum amount of bonus points that can be awarded
+	Points      uint32  = 100           // The default number of points to award
+	BonusPoints float64 = 200           // The maximum amount of bonus points that can be awarded
+	MaxScore    uint32  = 1_000_000_000 // The maximum total score a player can have
 )
 
 // Loop Run the game loop for the provided game
 func (game *Game) Loop() {
+	defer game.recoverLoop() // Stop the game instead of crashing the server if the loop fails
 	// Set the last sync time to very long ago to make sure that
 	// we will always sync the time straight away on the first go
 	var lastTimeSync = time.Duration(0)
@@ -182,6 +421,12 @@ func (game *Game) Loop() {
 
 		t := Time()
 
+		if state == Paused && t-game.PausedTime >= time.Duration(HostGrace)*time.Second { // If the host didn't come back in time
+			log.Printf("Host of game '%s' (%s) didn't reconnect in time", game.Title, game.Id)
+			game.Stop()
+			break
+		}
+
 		// The total time passed since the last time sync
 		elapsedSinceSync := t - lastTimeSync
 
@@ -202,25 +447,38 @@ func (game *Game) Loop() {
 			}
 		}
 
+		if state == Started && !game.checkActiveQuestion() { // If the game ended because its question was invalid
+			break
+		}
+
 		if state == Started { // If the game is started
 			if game.ActiveQuestion == nil { // If we don't already have an active question
 				game.NextQuestion() // Proceed to the next question
 				lastTimeSync = -1   // Clear the last time sync so we sync straight away
 			} else {
 				elapsedSinceStart := t - game.ActiveQuestion.StartTime
-				if elapsedSinceStart >= QuestionTime { // If we have passed the total question time
-					if elapsedSinceStart >= QuestionTime+MarkTime { // If the marking time has also completed
+				if game.QuestionEnded(game.ActiveQuestion, t) { // If the time for answering the question is over
+					review := ReviewTime(game.ActiveQuestion.Question)
+					if elapsedSinceStart >= QuestionTime+review { // If the marking time has also completed
 						game.NextQuestion() // Move on to the next question
 						lastTimeSync = -1   // Clear the last time sync so we sync straight away
-					} else if !game.ActiveQuestion.Marked { // If the question hasn't been marked
-						game.MarkQuestion(game.ActiveQuestion) // Mark the question
+					} else {
+						marking := !game.ActiveQuestion.Marked
+						if marking { // If the question hasn't been marked
+							game.MarkQuestion(game.ActiveQuestion) // Mark the question
+						}
+						if marking || elapsedSinceSync >= SyncDelay { // Sync the time left to review the results
+							lastTimeSync = t
+							remaining := QuestionTime + review - elapsedSinceStart
+							game.Broadcast(net.TimeSyncPacket(review, remaining), true)
+						}
 					}
 				} else {
-					if game.HaveAllAnswered() { // If all players have answered the question
+					if game.ActiveQuestion.Question.Kind != KindBuzzer && game.HaveAllAnswered() { // If all players have answered the question
 						game.SkipQuestion() // Skip the remaining time to the end of the question
 					}
 
-					if elapsedSinceSync >= SyncDelay { // If the current time needs to be synced
+					if game.IsTimed(game.ActiveQuestion) && elapsedSinceSync >= SyncDelay { // If the current time needs to be synced
 						lastTimeSync = t                                                  // Update the last sync time
 						remaining := QuestionTime - elapsedSinceStart                     // Calculate the
 						game.Broadcast(net.TimeSyncPacket(QuestionTime, remaining), true) // Broadcast the time sync packet
@@ -235,6 +493,26 @@ func (game *Game) Loop() {
 	}
 }
 
+// recoverLoop recovers from a panic in the game loop. A game without its loop
+// would never move on to the next question so it is stopped cleanly which
+// disconnects the players and keeps the timeline. Panics while stopping the
+// game are also logged rather than crashing the server
+func (game *Game) recoverLoop() {
+	err := recover()
+	if err == nil { // The loop finished normally
+		return
+	}
+	log.Printf("Game loop for '%s' (%s) failed: %v\n%s", game.Title, game.Id, err, debug.Stack())
+	defer func() {
+		if err := recover(); err != nil {
+			log.Printf("Failed to stop game '%s' (%s) after its loop failed: %v", game.Title, game.Id, err)
+		}
+	}()
+	if game.State != Stopped {
+		game.Stop()
+	}
+}
+
 // IsCorrect checks the correct answers for a question and checks if they match
 // the provided answer index
 func (question *ActiveQuestion) IsCorrect(answer AnswerIndex) bool {
@@ -247,6 +525,59 @@ func (question *ActiveQuestion) IsCorrect(answer AnswerIndex) bool {
 	return false
 }
 
+// Credit calculates the share of the points that the provided answers earn
+// based on the marking mode of the question. Returns a value between zero and one
+func (question *ActiveQuestion) Credit(answers []AnswerIndex) float64 {
+	total := len(question.Question.Values)
+	if total == 0 { // Questions without correct answers can't earn any points
+		return 0
+	}
+	if question.Question.Kind == KindOrder {
+		return question.orderCredit(answers)
+	}
+	right, wrong := 0, 0
+	for _, answer := range answers { // Count the right and wrong answers
+		if question.IsCorrect(answer) {
+			right++
+		} else {
+			wrong++
+		}
+	}
+	switch question.Question.Marking {
+	case MarkExact: // Every correct answer and nothing else
+		if right == total && wrong == 0 {
+			return 1
+		}
+		return 0
+	case MarkPartial: // A share for each correct answer
+		return float64(right) / float64(total)
+	case MarkPenalty: // A share for each correct answer less each wrong answer
+		return math.Max(float64(right-wrong), 0) / float64(total)
+	default: // Any of the correct answers
+		if right > 0 {
+			return 1
+		}
+		return 0
+	}
+}
+
+// orderCredit calculates the share of the points that the provided order earns
+// which is a share for each answer in the correct position. Questions with the
+// exact marking mode only earn points when every answer is in the right place
+func (question *ActiveQuestion) orderCredit(answers []AnswerIndex) float64 {
+	values := question.Question.Values
+	right := 0
+	for i, answer := range answers {
+		if i < len(values) && values[i] == answer {
+			right++
+		}
+	}
+	if question.Question.Marking == MarkExact && right != len(values) {
+		return 0
+	}
+	return float64(right) / float64(len(values))
+}
+
 // GetScore calculates the score that the player should be given based on how
 // long it took them to answer and the bonus that entails
 func GetScore(player *Player, question *ActiveQuestion) uint32 {
@@ -255,8 +586,9 @@ func GetScore(player *Player, question *ActiveQuestion) uint32 {
 	if passed <= BonusTime { // If the play is within the bonus period
 		// Calculate how far through the bonus they are. This is
 		// inverted because more score is awarded the quicker they go
-		// this value is later cast to an uint32, so we can't let it go below zero
-		percent := math.Max(1-(float64(passed)/float64(BonusTime)), 0)
+		// this value is later cast to an uint32, so it's clamped between
+		// zero and one in case the answer time is before the question started
+		percent := math.Min(math.Max(1-(float64(passed)/float64(BonusTime)), 0), 1)
 		bonus := uint32(math.RoundToEven(percent * BonusPoints)) // Get an even number of points
 		return Points + bonus
 	} else {
@@ -264,6 +596,47 @@ func GetScore(player *Player, question *ActiveQuestion) uint32 {
 	}
 }
 
+// QuestionPoints the points the player earns for fully answering the question
+// correctly. Exam mode awards the same points no matter how long they took
+func (game *Game) QuestionPoints(player *Player, question *ActiveQuestion) uint32 {
+	if game.Settings().Scoring == ScoreExam {
+		return Points
+	}
+	return GetScore(player, question)
+}
+
+// AddScore adds the points to the score without going over MaxScore
+func AddScore(score uint32, points uint32) uint32 {
+	if score >= MaxScore || points > MaxScore-score { // If adding the points would go over the max
+		return MaxScore
+	}
+	return score + points
+}
+
+// MaxTotalScore the highest total score a player can reach by fully answering the
+// provided questions correctly and first using the scoring mode
+func MaxTotalScore(questions []QuestionData, scoring ScoringMode) uint32 {
+	var total uint32
+	for _, question := range questions {
+		if question.IsSurvey() { // Survey questions aren't worth any points
+			continue
+		}
+		points := Points + uint32(question.FirstBonus)
+		if scoring == ScoreTimed { // Timed questions can also earn the speed bonus
+			points += uint32(BonusPoints)
+		}
+		total = AddScore(total, points)
+	}
+	return total
+}
+
+// IsAsking checks whether the question at the provided index is the one currently
+// being asked. Answers sent for any other question are stale
+func (game *Game) IsAsking(index QuestionIndex) bool {
+	q := game.ActiveQuestion
+	return q != nil && q.Question != nil && q.Index == index
+}
+
 // HaveAllAnswered checks whether all players have answered the current question
 func (game *Game) HaveAllAnswered() bool {
 	return game.Players.AllMatch(func(player *Player) bool {
@@ -271,12 +644,52 @@ func (game *Game) HaveAllAnswered() bool {
 	})
 }
 
+// QuestionAt retrieves the question at the provided index or nil if the index
+// is outside the questions of the game
+func (game *Game) QuestionAt(index QuestionIndex) *QuestionData {
+	if index < 0 || index >= len(game.Questions) {
+		return nil
+	}
+	return &game.Questions[index]
+}
+
+// checkActiveQuestion ends the game if the active question isn't one of the game
+// questions which can only happen if the game state has become inconsistent.
+// Returns whether the game can continue
+func (game *Game) checkActiveQuestion() bool {
+	q := game.ActiveQuestion
+	if q == nil || (q.Question != nil && game.QuestionAt(q.Index) != nil) {
+		return true
+	}
+	log.Printf("Game '%s' (%s) has an invalid active question %d of %d, ending the game", game.Title, game.Id, q.Index, len(game.Questions))
+	game.GameOver()
+	return false
+}
+
+// IsTimed checks whether the provided question has a time limit. Questions in
+// exam mode and buzzer questions which wait for the host to judge don't
+func (game *Game) IsTimed(question *ActiveQuestion) bool {
+	return game.Settings().Scoring != ScoreExam && question.Question.Kind != KindBuzzer
+}
+
+// QuestionEnded checks whether the time for answering the provided question is
+// over. Questions without a time limit only end once they are skipped by the host,
+// everyone has answered or a buzzer answer is judged correct
+func (game *Game) QuestionEnded(question *ActiveQuestion, t time.Duration) bool {
+	if !game.IsTimed(question) && !question.Skipped {
+		return false
+	}
+	return t-question.StartTime >= QuestionTime
+}
+
 // SkipQuestion skips to the next question by changing the question start time
 // to the time at which marking should be happening
 func (game *Game) SkipQuestion() {
 	q := game.ActiveQuestion
 	if q != nil { // If we have an active question
+		game.Timeline.RecordQuestion(EventSkip, q.Index, "")
 		q.StartTime = Time() - QuestionTime // Set the time to a time when it would be complete
+		q.Skipped = true
 	} else { // If we don't already have a question
 		game.NextQuestion() // Set the next question straight away
 	}
@@ -285,27 +698,130 @@ func (game *Game) SkipQuestion() {
 // MarkQuestion Marks the question at the end of the
 func (game *Game) MarkQuestion(question *ActiveQuestion) {
 	log.Printf("Marking questions for game '%s' (%s)", game.Title, game.Id)
+	var correctTargets, incorrectTargets []*Connection
+	var individual []Delivery // Results that are different for each player
+	var values []AnswerIndex
+	reveal := game.Settings().Reveal
+	var number *float64
+	var blanks []string
+	if reveal != RevealResult { // The correct answers are revealed
+		values = question.Question.Values
+		if question.Question.Numeric != nil {
+			number = &question.Question.Numeric.Answer
+		}
+		blanks = revealedBlanks(question.Question)
+	}
+	stats := net.QuestionStats{Question: question.Index}
+	survey := question.Question.IsSurvey()
+	first := question.FirstCorrect()
+	var firstName string          // The name of the first player to answer correctly if they are still in the game
+	var latencies []time.Duration // The time each player took to answer
+	var fastest time.Duration     // The time the fastest player took to answer
 	game.Players.ForEach(func(id Identifier, player *Player) {
-		// Retrieve the player answer
-		answerIndex, answered := player.GetAnswer(question.Index)
-		// Check the player answer
-		correct := answered && question.IsCorrect(answerIndex)
-		// Send the player their marking result
-		player.Net.Send(net.AnswerResultPacket(correct))
+		// Retrieve the player answers
+		answers, answered := player.GetAnswer(question.Index)
+		credit := 0.0
+		stats.Players++
+		if answered {
+			// Check the player answers
+			if question.Question.Kind == KindNumber {
+				credit = question.NumberCredit(player.Numbers[question.Index])
+			} else if question.Question.Kind == KindBlanks {
+				credit = question.BlanksCredit(player.Blanks[question.Index])
+			} else if question.Question.Kind != KindBuzzer {
+				credit = question.Credit(answers)
+			} else if id == first { // Only the buzzer the host judged correct earns the points
+				credit = 1
+			}
+			stats.Answered++
+			latency := player.AnswerTime - question.StartTime
+			if len(latencies) == 0 || latency < fastest { // If this is the fastest answer so far
+				stats.Fastest = player.Name
+				fastest = latency
+			}
+			latencies = append(latencies, latency)
+		} else if _, previous := player.GetAnswer(question.Index - 1); previous {
+			stats.Stopped++ // The player answered the last question but not this one
+		}
+		correct := credit > 0
 		if correct {
-			// Set the player score
-			player.Score = GetScore(player, question)
-			if player.Score > 0 {
-				log.Printf("Player '%s' scored %d points", player.Name, player.Score)
+			// Add the share of the points earned for this question to the player score
+			points := uint32(math.Round(float64(game.QuestionPoints(player, question)) * credit))
+			if id == first { // The first player to answer correctly earns the bonus
+				points += uint32(question.Question.FirstBonus)
+				firstName = player.Name
 			}
+			player.AddPoints(question.Index, points)
+			log.Printf("Player '%s' scored %d points", player.Name, points)
+		}
+		if survey { // Survey responses are never right or wrong
+			return
+		}
+		if reveal == RevealAnswers { // Each player is only sent their own score
+			score := player.Score
+			packet := net.AnswerResultPacket(net.AnswerResultData{Result: correct, Values: values, Number: number, Blanks: blanks, Score: &score})
+			individual = append(individual, Delivery{Packet: packet, Targets: []*Connection{player.Net}})
+		} else if correct {
+			correctTargets = append(correctTargets, player.Net)
+		} else {
+			incorrectTargets = append(incorrectTargets, player.Net)
 		}
 	})
+	if survey { // Everyone is shown how the players responded
+		game.Broadcast(net.PollResultsPacket(game.PollResults(question)), true)
+	}
+	// Send the players their marking results
+	for _, delivery := range individual {
+		game.Fanout.Push(delivery.Packet, delivery.Targets...)
+	}
+	game.Fanout.Push(net.AnswerResultPacket(net.AnswerResultData{Result: true, Values: values, Number: number, Blanks: blanks}), correctTargets...)
+	game.Fanout.Push(net.AnswerResultPacket(net.AnswerResultData{Result: false, Values: values, Number: number, Blanks: blanks}), incorrectTargets...)
+	if firstName != "" && question.Question.FirstBonus > 0 { // Announce who earned the first correct bonus
+		packet := net.FirstCorrectPacket(first, firstName, question.Question.FirstBonus)
+		if reveal == RevealResult { // Players aren't told about other players until the game is over
+			game.Fanout.Push(packet, game.HostTargets()...)
+		} else {
+			game.Broadcast(packet, true)
+		}
+	}
 	// Create a new scores packet
 	scorePacket := net.ScoresPacket(game.Players.CollectScores())
-	// Broadcast the scores' packet to everyone
-	game.Broadcast(scorePacket, true)
+	if reveal == RevealLeaderboard { // Broadcast the scores' packet to everyone
+		game.Broadcast(scorePacket, true)
+	} else { // Only the host and displays see every score until the game is over
+		game.Fanout.Push(scorePacket, game.HostTargets()...)
+	}
 	// Set the question as marked
 	question.Marked = true
+	game.Timeline.RecordQuestion(EventMark, question.Index, "")
+	sort.Slice(latencies, func(i, j int) bool { return latencies[i] < latencies[j] })
+	stats.Quickest = fastest.Milliseconds()
+	stats.Median = Percentile(latencies, 50).Milliseconds()
+	stats.P90 = Percentile(latencies, 90).Milliseconds()
+	game.Timeline.RecordStats(stats)
+	if next := game.QuestionAt(question.Index + 1); next != nil && game.Host != nil { // Displays are shown to players so only the host is sent the preview
+		game.Fanout.Push(net.NextQuestionPacket(question.Index+1, *next), game.Host)
+	}
+}
+
+// Percentile finds the duration that the provided percent of the sorted durations
+// are less than or equal to using the nearest rank. Returns zero without any durations
+func Percentile(sorted []time.Duration, percent int) time.Duration {
+	if len(sorted) == 0 {
+		return 0
+	}
+	rank := (percent*len(sorted) + 99) / 100 // Round up to the nearest rank
+	if rank < 1 {
+		rank = 1
+	}
+	return sorted[rank-1]
+}
+
+// questionVariant The version of a question sent to a group of players which
+// depends on their language and whether they want the question audio
+type questionVariant struct {
+	Locale string // The language of the translation or empty for the original question
+	Audio  bool   // Whether the question audio is included
 }
 
 // NextQuestion moves on to the next question and informs all the clients
@@ -316,74 +832,232 @@ func (game *Game) NextQuestion() {
 	if game.ActiveQuestion == nil { // If there isn't already an active question
 		nextIndex = 0 // Set the next index to the first index
 	} else { // Else
-		game.ActiveQuestion.Question.FreeMemory() // Free memory for the last question
+		if game.ActiveQuestion.Question != nil {
+			game.ActiveQuestion.Question.FreeMemory() // Free memory for the last question
+		}
 
 		nextIndex = game.ActiveQuestion.Index + 1 // Increase the index by 1
 	}
-	if nextIndex >= len(game.Questions) { // If the next index is higher than the amount of questions
+	next := game.QuestionAt(nextIndex)
+	if len(game.Rounds) > 1 && nextIndex > 0 { // Playlists announce the scores of each quiz once it is over
+		if round := game.RoundAt(nextIndex - 1); next == nil || game.RoundAt(nextIndex) != round {
+			game.endRound(round)
+		}
+	}
+	if next == nil { // If the next index is higher than the amount of questions
+		if nextIndex > len(game.Questions) { // Only the index after the last question is expected
+			log.Printf("Game '%s' (%s) moved past question %d of %d", game.Title, game.Id, nextIndex, len(game.Questions))
+		}
 		game.GameOver() // Game over
 	} else {
-		q := game.Questions[nextIndex] // Retrieve the next question
+		q := *next // Copy the next question
 		game.ActiveQuestion = &ActiveQuestion{
 			Question:  &q,
 			Index:     nextIndex,
 			StartTime: t,
 			Marked:    false,
 		}
-		// Broadcast the question
-		game.Broadcast(net.QuestionPacket(q), false)
+		variants := map[questionVariant][]*Connection{} // The players receiving each language with or without audio
+		var presentedTargets []*Connection
+		presentation := game.Settings().Present
+		// Send the question to every player
+		game.Players.ForEach(func(id Identifier, player *Player) {
+			if presentation || player.Compact { // The question is only shown on the displays or the player wants to save data
+				presentedTargets = append(presentedTargets, player.Net)
+			} else {
+				variant := questionVariant{Locale: q.Locale(player.Locale), Audio: player.Audio}
+				variants[variant] = append(variants[variant], player.Net)
+			}
+		})
+		plain := questionVariant{}
+		variants[plain] = append(variants[plain], game.DisplayTargets()...) // Displays show the original question without the correct answers
+		for variant, targets := range variants {
+			game.Fanout.Push(net.QuestionPacket(nextIndex, q.Localized(variant.Locale), game.Style.For(variant.Locale), variant.Audio), targets...)
+		}
+		game.Fanout.Push(net.PresentedQuestionPacket(nextIndex, q), presentedTargets...)
+		game.Timeline.RecordQuestion(EventQuestion, nextIndex, "")
 	}
 }
 
 // GameOver called when the game has ended and there is no more questions
 // sets the game state to stopped and logs the game over
 func (game *Game) GameOver() {
+	if game.Settings().Reveal != RevealLeaderboard { // Players see every score once the game is over
+		game.Broadcast(net.ScoresPacket(game.Players.CollectScores()), false)
+	}
+	game.Fanout.Push(net.SummaryPacket(game.Timeline.Summary()), game.HostTargets()...)
+	game.Broadcast(net.ResultsPacket(game.Results()), true) // Everyone is sent the final rankings before the game over screen
 	game.SetState(Stopped)
+	game.Fanout.Close() // Stop the fanout once the final results are sent
 	log.Printf("Game over for game '%s' (%s)", game.Title, game.Id)
 
 	GamesLock.Lock()       // Establish write lock on the games map
 	delete(Games, game.Id) // Remove the game
 	GamesLock.Unlock()     // Release write lock
+	game.finish()          // Keep the timeline for after the game
 }
 
 // SetState sets the current game state and broadcasts the game state packet
 // to inform all the clients of the game state change
 func (game *Game) SetState(state State) {
 	game.State = state
+	game.Timeline.RecordState(state)
 	game.Broadcast(net.GameStatePacket(state), true)
 }
 
-// RemovePlayer Deletes the player from the players list. Made thread safe with PLock
+// RemovePlayer Deletes the player from the players list after they left the
+// game. Made thread safe with PLock
 func (game *Game) RemovePlayer(player *Player) {
-	if game.State != Stopped { // If the game is stopped we don't need to inform the other players
+	game.removePlayer(player, net.RemoveMode, "Left the game")
+}
+
+// MaxKickReason The most characters the reason for kicking a player can have
+const MaxKickReason = 100
 
-		// Create a remove player data packet
-		dataPacket := net.PlayerDataPacket(player.Id, player.Name, net.RemoveMode)
-		// Broadcast the packet to all the other players and the host
-		game.BroadcastExcluding(player.Id, dataPacket, true)
+// KickPlayer Deletes the player from the players list because the host kicked
+// them. Only the kicked player is sent the kicked packet so clients don't need
+// to compare ids to know whether they were the one kicked
+func (game *Game) KickPlayer(player *Player, reason string) {
+	if reason == "" {
+		reason = "Kicked by the host"
 	}
+	if game.removePlayer(player, net.KickMode, reason) {
+		game.Fanout.Push(net.KickedPacket(reason), player.Net)
+	}
+}
+
+// removePlayer Deletes the player from the players list and tells everyone else
+// why they were removed. Returns whether the player was in the game
+func (game *Game) removePlayer(player *Player, mode net.PlayerDataMode, reason string) bool {
 	// Remove the player from the player list
-	game.Players.Remove(player.Id)
+	if !game.Players.Remove(player.Id) { // A kicked player is removed again when their connection closes
+		return false
+	}
+	if game.State != Stopped { // If the game is stopped we don't need to inform the other players
+		// Broadcast the remove player data packet to all the other players and the host
+		game.Broadcast(net.PlayerRemovedPacket(player.Id, player.Name, mode, reason), true)
+		game.Broadcast(net.PlayerCountPacket(game.Players.Count()), true)
+		game.checkCapacity()
+	}
+	event := EventLeave
+	if mode == net.KickMode {
+		event = EventKick
+	}
+	game.Timeline.Record(Event{Type: event, Player: player.Id})
 	// Log a debug message saying who was disconnected
-	log.Printf("Player '%s' (%s) removed from game '%s' (%s)", player.Name, player.Id, game.Title, game.Id)
+	log.Printf("Player '%s' (%s) removed from game '%s' (%s): %s", player.Name, player.Id, game.Title, game.Id, reason)
+	return true
+}
+
+// Pause Pauses the game after the host disconnected. The timers stop until the
+// host reconnects using Resume or the game is stopped after HostGrace
+func (game *Game) Pause() {
+	log.Printf("Host of game '%s' (%s) disconnected pausing for %ds", game.Title, game.Id, HostGrace)
+	game.Host = nil
+	game.PausedState = game.State
+	game.PausedTime = Time()
+	game.SetState(Paused)
+}
+
+// Resume Gives the paused game its new host connection and continues the game
+// from where it was paused. The host is sent everything needed to rebuild their
+// view of the game
+func (game *Game) Resume(host *Connection) {
+	paused := Time() - game.PausedTime // Move the timers forward by the time spent paused
+	game.StartTime += paused
+	if game.ActiveQuestion != nil {
+		game.ActiveQuestion.StartTime += paused
+	}
+	game.Host = host
+	game.Fanout.Push(net.JoinGamePacket(game.JoinData(true)), host)
+	game.Players.ForEach(func(id Identifier, player *Player) {
+		game.Fanout.Push(net.PlayerDataPacket(player.Id, player.Name, net.AddMode), host)
+	})
+	log.Printf("Host of game '%s' (%s) reconnected", game.Title, game.Id)
+	game.SetState(game.PausedState)
+	game.Fanout.Push(net.StateSyncPacket(game.StateSync(nil)), host)
+}
+
+// StateSync collects the current state of the game for a connection that is
+// rejoining it. When a player is provided the question is in the form that
+// player receives and the results of the questions they answered are included
+func (game *Game) StateSync(player *Player) net.StateSyncData {
+	settings := game.Settings()
+	data := net.StateSyncData{State: game.State, Scores: game.Players.CollectScores(), Seq: game.Sequence()}
+	if player != nil && settings.Reveal != RevealLeaderboard && game.State != Stopped { // Players only see their own score
+		data.Scores = ScoreMap{player.Id: player.Score}
+	}
+	data.Total, data.Remaining = game.countdown()
+	q := game.ActiveQuestion
+	if game.State == Started && q != nil && q.Question != nil && !q.Marked { // The question is still being answered
+		locale := ""
+		if player != nil { // Players are sent the question in their language
+			locale = q.Question.Locale(player.Locale)
+		}
+		packet := net.QuestionPacket(q.Index, q.Question.Localized(locale), game.Style.For(locale), player != nil && player.Audio)
+		if player != nil && (settings.Present || player.Compact) {
+			packet = net.PresentedQuestionPacket(q.Index, *q.Question)
+		}
+		data.Question = packet.Data
+	}
+	if player != nil {
+		data.Results = map[QuestionIndex]bool{}
+		for index, answers := range player.Answers {
+			question := game.QuestionAt(index)
+			if question == nil || (q != nil && index == q.Index && !q.Marked) { // Unmarked answers aren't revealed
+				continue
+			}
+			if question.Kind != KindChoice { // Only the answers to choice questions can be checked again
+				continue
+			}
+			marked := &ActiveQuestion{Question: question, Index: index}
+			data.Results[index] = marked.Credit(answers) > 0
+		}
+	}
+	return data
+}
+
+// countdown returns the total and remaining time in ms of whatever the game is
+// currently counting down to. Both are zero when nothing is being counted down
+func (game *Game) countdown() (total int64, remaining int64) {
+	t := Time()
+	q := game.ActiveQuestion
+	if game.State == Starting { // Continue the countdown to the first question
+		total, remaining = StartDelay.Milliseconds(), (StartDelay - (t - game.StartTime)).Milliseconds()
+	} else if game.State == Started && q != nil && q.Question != nil && !q.Marked { // The question is still being answered
+		if game.IsTimed(q) { // Exam and buzzer questions have no timer
+			total, remaining = QuestionTime.Milliseconds(), (QuestionTime - (t - q.StartTime)).Milliseconds()
+		}
+	} else if game.State == Started && q != nil && q.Marked { // The results are being reviewed
+		review := ReviewTime(q.Question)
+		total, remaining = review.Milliseconds(), (QuestionTime + review - (t - q.StartTime)).Milliseconds()
+	}
+	if remaining < 0 {
+		remaining = 0
+	}
+	return total, remaining
 }
 
 // Stop Sets the game state to Stopped and calls RemovePlayer
 // on all the players. Made thread safe with PLock
 func (game *Game) Stop() {
 	game.State = Stopped // Set the game state to stopped
+	game.Timeline.RecordState(Stopped)
 	packet := net.DisconnectPacket("Removed from game")
 	// Write safe iteration over all the players
 	game.Players.ForEachSafe(func(player *Player) {
 		// Remove the player
 		game.RemovePlayer(player)
 		// Send a disconnect packet to the player
-		player.Net.Send(packet)
+		game.Fanout.Push(packet, player.Net)
 	})
+	game.Fanout.Push(packet, game.DisplayTargets()...)
+	game.Fanout.Close() // Stop the fanout once the disconnect packets are sent
 	// Log a debug messaging saying the game was stopped
 	log.Printf("Stopping game '%s' (%s)", game.Title, game.Id)
 
 	GamesLock.Lock()       // Establish write lock on the games map
 	delete(Games, game.Id) // Remove the game
 	GamesLock.Unlock()     // Release write lock
+	game.finish()          // Keep the timeline for after the game
 }
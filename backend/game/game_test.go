@@ -0,0 +1,1327 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"encoding/json"
+	"errors"
+	"fmt"
+	. "github.com/jacobtread/gowsps"
+	"math"
+	"net/http"
+	"net/http/httptest"
+	"reflect"
+	"strings"
+	"sync/atomic"
+	"testing"
+	"time"
+)
+
+// benchmarkMark benchmarks marking an answer for the provided question the
+// same way MarkQuestion does for each player
+func benchmarkMark(b *testing.B, question QuestionData) {
+	active := &ActiveQuestion{Question: &question, StartTime: 0}
+	player := &Player{AnswerTime: 2 * time.Second}
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		if active.IsCorrect(i % len(question.Answers)) {
+			player.Score = GetScore(player, active)
+		}
+	}
+}
+
+// BenchmarkMark benchmarks marking answers for questions with a single correct
+// answer and questions with many correct answers
+func BenchmarkMark(b *testing.B) {
+	b.Run("single", func(b *testing.B) {
+		benchmarkMark(b, QuestionData{
+			Answers: []string{"A", "B", "C", "D"},
+			Values:  []AnswerIndex{2},
+		})
+	})
+	b.Run("multiple", func(b *testing.B) {
+		benchmarkMark(b, QuestionData{
+			Answers: []string{"A", "B", "C", "D", "E", "F", "G", "H", "I"},
+			Values:  []AnswerIndex{0, 2, 4, 6, 8},
+		})
+	})
+}
+
+// BenchmarkScores benchmarks serializing the scores packet for games of
+// different sizes
+func BenchmarkScores(b *testing.B) {
+	for _, players := range []int{10, 100, 1000} {
+		scores := ScoreMap{}
+		for i := 0; i < players; i++ {
+			scores[fmt.Sprintf("%06d", i)] = uint32(i * 100)
+		}
+		b.Run(fmt.Sprintf("%d", players), func(b *testing.B) {
+			for i := 0; i < b.N; i++ {
+				if _, err := json.Marshal(net.ScoresPacket(scores).Data); err != nil {
+					b.Fatal(err)
+				}
+			}
+		})
+	}
+}
+
+// BenchmarkCreateGameId benchmarks creating a unique game id when 90% of the
+// possible ids are already in use
+func BenchmarkCreateGameId(b *testing.B) {
+	const total = 1 << 20 // 16^5 possible game ids
+	GamesLock.Lock()
+	previous := Games
+	Games = make(map[Identifier]*Game, total)
+	for len(Games) < total*9/10 {
+		Games[CreateRandomId(5)] = nil
+	}
+	GamesLock.Unlock()
+	defer func() {
+		GamesLock.Lock()
+		Games = previous
+		GamesLock.Unlock()
+	}()
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		CreateGameId()
+	}
+}
+
+// TestGetScore checks the score awarded at the edges of the bonus period
+func TestGetScore(t *testing.T) {
+	question := &ActiveQuestion{StartTime: 10 * time.Second}
+	tests := []struct {
+		name     string
+		answered time.Duration
+		want     uint32
+	}{
+		{"instant", 10 * time.Second, Points + uint32(BonusPoints)},
+		{"half bonus", 10*time.Second + BonusTime/2, Points + uint32(BonusPoints/2)},
+		{"end of bonus", 10*time.Second + BonusTime, Points},
+		{"after bonus", 10*time.Second + BonusTime + time.Second, Points},
+		{"after question", 10*time.Second + QuestionTime*100, Points},
+		{"before start", 0, Points + uint32(BonusPoints)},
+		{"far before start", -time.Duration(1 << 62), Points + uint32(BonusPoints)},
+	}
+	for _, test := range tests {
+		player := &Player{AnswerTime: test.answered}
+		if got := GetScore(player, question); got != test.want {
+			t.Errorf("%s: got %d want %d", test.name, got, test.want)
+		}
+	}
+}
+
+// TestExamScoring checks that exam questions earn flat points and only end once skipped
+func TestExamScoring(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.settings.Scoring = ScoreExam
+	question := &ActiveQuestion{StartTime: 10 * time.Second}
+	if got := game.QuestionPoints(&Player{AnswerTime: 10 * time.Second}, question); got != Points {
+		t.Errorf("expected no bonus for an instant answer got %d", got)
+	}
+	if game.QuestionEnded(question, 10*time.Second+QuestionTime*100) {
+		t.Error("expected the question to stay open without a time limit")
+	}
+	question.Skipped = true
+	if !game.QuestionEnded(question, 10*time.Second+QuestionTime) {
+		t.Error("expected the question to end once skipped")
+	}
+	game.settings.Scoring = ScoreTimed
+	question.Skipped = false
+	if !game.QuestionEnded(question, 10*time.Second+QuestionTime) {
+		t.Error("expected timed questions to end after the question time")
+	}
+}
+
+// TestAddScore checks that adding to a score never overflows or exceeds MaxScore
+func TestAddScore(t *testing.T) {
+	tests := []struct {
+		score, points, want uint32
+	}{
+		{0, 300, 300},
+		{MaxScore - 100, 100, MaxScore},
+		{MaxScore - 100, 101, MaxScore},
+		{MaxScore, 1, MaxScore},
+		{math.MaxUint32, math.MaxUint32, MaxScore},
+	}
+	for _, test := range tests {
+		if got := AddScore(test.score, test.points); got != test.want {
+			t.Errorf("AddScore(%d, %d): got %d want %d", test.score, test.points, got, test.want)
+		}
+	}
+}
+
+// TestCredit checks the share of the points earned in each marking mode
+func TestCredit(t *testing.T) {
+	tests := []struct {
+		marking MarkingMode
+		answers []AnswerIndex
+		want    float64
+	}{
+		{MarkSingle, []AnswerIndex{1}, 1},
+		{MarkSingle, []AnswerIndex{0}, 0},
+		{MarkExact, []AnswerIndex{1, 2, 3}, 1},
+		{MarkExact, []AnswerIndex{1, 2}, 0},
+		{MarkExact, []AnswerIndex{0, 1, 2, 3}, 0},
+		{MarkPartial, []AnswerIndex{1, 2}, 2.0 / 3},
+		{MarkPartial, []AnswerIndex{0, 1, 2}, 2.0 / 3},
+		{MarkPenalty, []AnswerIndex{0, 1, 2}, 1.0 / 3},
+		{MarkPenalty, []AnswerIndex{0, 1}, 0},
+		{MarkPenalty, []AnswerIndex{}, 0},
+	}
+	for _, test := range tests {
+		question := &ActiveQuestion{Question: &QuestionData{
+			Answers: []string{"A", "B", "C", "D"},
+			Values:  []AnswerIndex{1, 2, 3},
+			Marking: test.marking,
+		}}
+		if got := question.Credit(test.answers); math.Abs(got-test.want) > 1e-9 {
+			t.Errorf("mode %d answers %v: got %f want %f", test.marking, test.answers, got, test.want)
+		}
+	}
+}
+
+// TestOrderCredit checks that order questions earn a share for each answer in
+// the right place and that shuffling keeps the correct order of the answers
+func TestOrderCredit(t *testing.T) {
+	tests := []struct {
+		marking MarkingMode
+		answers []AnswerIndex
+		want    float64
+	}{
+		{MarkSingle, []AnswerIndex{2, 0, 1}, 1},
+		{MarkSingle, []AnswerIndex{2, 1, 0}, 1.0 / 3},
+		{MarkSingle, []AnswerIndex{0, 1, 2}, 0},
+		{MarkExact, []AnswerIndex{2, 0, 1}, 1},
+		{MarkExact, []AnswerIndex{2, 1, 0}, 0},
+	}
+	for _, test := range tests {
+		question := &ActiveQuestion{Question: &QuestionData{
+			Answers: []string{"B", "C", "A"},
+			Values:  []AnswerIndex{2, 0, 1},
+			Marking: test.marking,
+			Kind:    KindOrder,
+		}}
+		if got := question.Credit(test.answers); math.Abs(got-test.want) > 1e-9 {
+			t.Errorf("mode %d answers %v: got %f want %f", test.marking, test.answers, got, test.want)
+		}
+	}
+	original := QuestionData{
+		Answers:      []string{"A", "B", "C", "D"},
+		Values:       []AnswerIndex{0, 1, 2, 3},
+		Kind:         KindOrder,
+		Translations: map[string]Translation{"fr": {Question: "Q", Answers: []string{"a", "b", "c", "d"}}},
+	}
+	shuffled := original.ShuffleOrder()
+	for i, value := range shuffled.Values {
+		if shuffled.Answers[value] != original.Answers[i] || shuffled.Translations["fr"].Answers[value] != strings.ToLower(original.Answers[i]) {
+			t.Errorf("expected answer %d of the order to be %s got %v", i, original.Answers[i], shuffled)
+		}
+	}
+	if original.Answers[0] != "A" || original.Values[0] != 0 {
+		t.Error("expected shuffling to leave the original question unchanged")
+	}
+}
+
+// TestNumberCredit checks that numbers within the tolerance earn every point and
+// partial marking scales the points down over the margin
+func TestNumberCredit(t *testing.T) {
+	tests := []struct {
+		marking MarkingMode
+		number  float64
+		want    float64
+	}{
+		{MarkSingle, 1969, 1},
+		{MarkSingle, 1971, 1},
+		{MarkSingle, 1972, 0},
+		{MarkPartial, 1968, 1},
+		{MarkPartial, 1976, 0.5},
+		{MarkPartial, 1950, 0},
+	}
+	for _, test := range tests {
+		numeric := &NumericData{Slider: Slider{Min: 1900, Max: 2000}, Answer: 1969, Tolerance: 2}
+		if test.marking == MarkPartial {
+			numeric.Margin = 10
+		}
+		question := &ActiveQuestion{Question: &QuestionData{Question: "Moon landing?", Kind: KindNumber, Marking: test.marking, Numeric: numeric}}
+		if problems := question.Question.Problems(); len(problems) > 0 {
+			t.Fatal(problems)
+		}
+		if got := question.NumberCredit(test.number); math.Abs(got-test.want) > 1e-9 {
+			t.Errorf("mode %d number %g: got %f want %f", test.marking, test.number, got, test.want)
+		}
+	}
+	decimal := &ActiveQuestion{Question: &QuestionData{Numeric: &NumericData{Answer: 0.3, Tolerance: 0.1}}}
+	if got := decimal.NumberCredit(0.1 + 0.3); got != 1 {
+		t.Errorf("expected a number on the edge of the tolerance to earn every point got %f", got)
+	}
+	game := newTestGame([]QuestionData{{Question: "Moon landing?", Kind: KindNumber, Numeric: &NumericData{Slider: Slider{Min: 1900, Max: 2000}, Answer: 1969}}})
+	player := &Player{Id: "P", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.Players.Map[player.Id] = player
+	game.SetState(Started)
+	game.NextQuestion()
+	if err := player.Answer(game, []AnswerIndex{0}); err != ErrNumberQuestion {
+		t.Errorf("expected ErrNumberQuestion got %v", err)
+	}
+	if err := player.AnswerNumber(game, 2001); err == nil {
+		t.Error("expected a number off the slider to be rejected")
+	}
+	if err := player.AnswerNumber(game, 1969); err != nil {
+		t.Fatal(err)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	if player.Score == 0 {
+		t.Error("expected the correct number to earn points")
+	}
+}
+
+// TestBlanksCredit checks that each blank filled with an accepted answer earns a
+// share of the points unless every blank must be right
+func TestBlanksCredit(t *testing.T) {
+	tests := []struct {
+		marking MarkingMode
+		words   []string
+		want    float64
+	}{
+		{MarkSingle, []string{"Paris", "the  Seine"}, 1},
+		{MarkSingle, []string{"paris", "thames"}, 0.5},
+		{MarkSingle, []string{"london"}, 0},
+		{MarkExact, []string{"paris", "seine"}, 1},
+		{MarkExact, []string{"paris", "thames"}, 0},
+	}
+	for _, test := range tests {
+		question := &ActiveQuestion{Question: &QuestionData{
+			Question: "___ is on the river ___",
+			Kind:     KindBlanks,
+			Marking:  test.marking,
+			Blanks:   [][]string{{"Paris"}, {"Seine", "The Seine"}},
+		}}
+		if problems := question.Question.Problems(); len(problems) > 0 {
+			t.Fatal(problems)
+		}
+		if got := question.BlanksCredit(test.words); math.Abs(got-test.want) > 1e-9 {
+			t.Errorf("mode %d words %v: got %f want %f", test.marking, test.words, got, test.want)
+		}
+	}
+	patterned := &ActiveQuestion{Question: &QuestionData{
+		Question: "___ has ___ legs",
+		Kind:     KindBlanks,
+		Blanks:   [][]string{{"Spider"}, {"8"}},
+		Patterns: [][]string{{"spiders?"}, {"8|eight"}},
+	}}
+	if problems := patterned.Question.Problems(); len(problems) > 0 {
+		t.Fatal(problems)
+	}
+	if got := patterned.BlanksCredit([]string{"Spiders", "Eight"}); got != 1 {
+		t.Errorf("expected both patterns to match got %f", got)
+	}
+	if got := patterned.BlanksCredit([]string{"spiderman", "eighty"}); got != 0 {
+		t.Errorf("expected patterns to match the whole word got %f", got)
+	}
+	for _, patterns := range [][][]string{{{"a)|(b"}}, {{"("}}, {{strings.Repeat("a", MaxPattern+1)}}, {{"a"}, {"b"}, {"c"}}} {
+		invalid := QuestionData{Question: "___ and ___", Kind: KindBlanks, Blanks: [][]string{{"A"}, {"B"}}, Patterns: patterns}
+		if len(invalid.Problems()) == 0 {
+			t.Errorf("expected the patterns %q to be rejected", patterns)
+		}
+	}
+	missing := QuestionData{Question: "___ is on the river", Kind: KindBlanks, Blanks: [][]string{{"Paris"}, {"Seine"}}}
+	if len(missing.Problems()) == 0 {
+		t.Error("expected a question without a marker for each blank to be invalid")
+	}
+	game := newTestGame([]QuestionData{{Question: "___ is on the river ___", Kind: KindBlanks, Blanks: [][]string{{"Paris"}, {"Seine"}}}})
+	player := &Player{Id: "P", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.Players.Map[player.Id] = player
+	game.SetState(Started)
+	game.NextQuestion()
+	if err := player.AnswerBlanks(game, []string{"Paris"}); err == nil {
+		t.Error("expected an answer without a word for each blank to be rejected")
+	}
+	var invalid *TextError
+	if err := player.AnswerBlanks(game, []string{"Paris", " "}); !errors.As(err, &invalid) || invalid.Blank == nil || *invalid.Blank != 1 {
+		t.Errorf("expected the empty blank to be rejected got %v", err)
+	}
+	if err := player.AnswerBlanks(game, []string{"Paris", "Seine"}); err != nil {
+		t.Fatal(err)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	if player.Score == 0 {
+		t.Error("expected filling every blank to earn points")
+	}
+}
+
+// TestCleanText tests that typed answers are trimmed and that empty, overly long
+// and control character answers are rejected with the reason
+func TestCleanText(t *testing.T) {
+	tests := []struct {
+		text   string
+		want   string
+		reason string
+	}{
+		{"  Hello \t  World ", "Hello World", ""},
+		{"Ünïcödé", "Ünïcödé", ""},
+		{"   ", "", TextEmpty},
+		{strings.Repeat("a", MaxWordLength+1), "", TextTooLong},
+		{strings.Repeat(" ", 10_000) + "a", "", TextTooLong},
+		{"bell\a", "", TextControl},
+		{"\xff", "", TextControl},
+	}
+	for _, test := range tests {
+		got, err := CleanText(test.text)
+		var invalid *TextError
+		if errors.As(err, &invalid) != (test.reason != "") || (invalid != nil && invalid.Reason != test.reason) {
+			t.Errorf("%q: got error %v want reason %q", test.text, err, test.reason)
+		} else if got != test.want {
+			t.Errorf("%q: got %q want %q", test.text, got, test.want)
+		}
+	}
+}
+
+// TestEstimateDuration tests that the estimate covers the start countdown and
+// every question and that the maximum duration is only enforced when set
+func TestEstimateDuration(t *testing.T) {
+	if got := EstimateDuration(nil); got != StartDelay {
+		t.Errorf("empty quiz: got %s want %s", got, StartDelay)
+	}
+	if got, want := EstimateDuration(make([]QuestionData, 4)), StartDelay+4*(QuestionTime+MarkTime); got != want {
+		t.Errorf("4 questions: got %s want %s", got, want)
+	}
+	reviewed := []QuestionData{{Review: 10}, {}}
+	if got, want := EstimateDuration(reviewed), StartDelay+2*QuestionTime+10*time.Second+MarkTime; got != want {
+		t.Errorf("review time: got %s want %s", got, want)
+	}
+	defer func(previous int) { MaxDuration = previous }(MaxDuration)
+	MaxDuration = 0
+	if IsTooLong(24 * time.Hour) {
+		t.Error("expected no limit when MaxDuration is 0")
+	}
+	MaxDuration = 1
+	if IsTooLong(time.Minute) || !IsTooLong(time.Minute + time.Second) {
+		t.Error("expected only durations over a minute to be too long")
+	}
+}
+
+// TestFindTimeline tests that timelines can only be found with the host key and
+// are still found after the game has finished
+func TestFindTimeline(t *testing.T) {
+	game := &Game{Id: "TIMELINE", Key: CreateSecret(16), Timeline: &Timeline{}}
+	game.Timeline.RecordState(Started)
+	game.finish()
+	game.finish() // Stopping a game that is already over shouldn't keep it twice
+	if FindTimeline(game.Id, "") != nil || FindTimeline(game.Id, "wrong") != nil {
+		t.Fatal("expected timeline to require the host key")
+	}
+	timeline := FindTimeline(game.Id, game.Key)
+	if timeline == nil {
+		t.Fatal("expected finished game timeline to be kept")
+	}
+	if events := timeline.Copy(); len(events) != 1 || events[0].Type != EventState || *events[0].State != Started {
+		t.Errorf("unexpected events %+v", events)
+	}
+	count := 0
+	for _, entry := range finished {
+		if entry.Id == game.Id {
+			count++
+		}
+	}
+	if count != 1 {
+		t.Errorf("expected game to be kept once got %d", count)
+	}
+}
+
+// TestHandleGameInfo tests that the public game info is served without the host key
+func TestHandleGameInfo(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.Id, game.Key = "INFO", CreateSecret(16)
+	game.Players.Map["A"] = &Player{Id: "A", Name: "A"}
+	GamesLock.Lock()
+	Games[game.Id] = game
+	GamesLock.Unlock()
+	defer func() {
+		GamesLock.Lock()
+		delete(Games, game.Id)
+		GamesLock.Unlock()
+	}()
+	recorder := httptest.NewRecorder()
+	HandleGame(recorder, httptest.NewRequest(http.MethodGet, "/api/game/INFO/info", nil))
+	if recorder.Code != http.StatusOK || strings.Contains(recorder.Body.String(), game.Key) {
+		t.Fatalf("unexpected response %d %s", recorder.Code, recorder.Body.String())
+	}
+	var info GameInfo
+	if err := json.Unmarshal(recorder.Body.Bytes(), &info); err != nil {
+		t.Fatal(err)
+	}
+	if info.Players != 1 || info.Questions != len(game.Questions) || !info.Joinable {
+		t.Errorf("unexpected info %+v", info)
+	}
+	recorder = httptest.NewRecorder()
+	HandleGame(recorder, httptest.NewRequest(http.MethodGet, "/api/game/MISSING/info", nil))
+	if recorder.Code != http.StatusNotFound {
+		t.Errorf("expected unknown games to be not found got %d", recorder.Code)
+	}
+}
+
+// TestRemovePlayer tests that kicks and leaves are recorded separately and that
+// removing a player twice only records it once
+func TestRemovePlayer(t *testing.T) {
+	game := newTestGame(testQuestions())
+	kicked, left := &Player{Id: "A", Name: "A"}, &Player{Id: "B", Name: "B"}
+	game.Players.Map[kicked.Id], game.Players.Map[left.Id] = kicked, left
+	game.KickPlayer(kicked, "")
+	game.RemovePlayer(kicked) // The connection of a kicked player closing
+	game.RemovePlayer(left)
+	events := game.Timeline.Copy()
+	if len(events) != 2 || events[0].Type != EventKick || events[1].Type != EventLeave {
+		t.Errorf("unexpected events %+v", events)
+	}
+	if game.Players.Count() != 0 {
+		t.Errorf("expected every player to be removed got %d", game.Players.Count())
+	}
+}
+
+// TestCapacity tests that the host is warned once as the game fills up and
+// again after players leave and it fills back up
+func TestCapacity(t *testing.T) {
+	defer func(previous int) { CapacityWarning = previous }(CapacityWarning)
+	CapacityWarning = 50
+	game := newTestGame(testQuestions())
+	game.settings.MaxPlayers = 4
+	join := func(id string) {
+		game.Players.Map[id] = &Player{Id: id, Name: id}
+		game.checkCapacity()
+	}
+	join("A")
+	if game.capacity != capacityNone {
+		t.Error("expected no warning below the threshold")
+	}
+	join("B")
+	if game.capacity != capacityWarned {
+		t.Error("expected a warning at the threshold")
+	}
+	join("C")
+	join("D")
+	if !game.IsFull() {
+		t.Fatal("expected the game to be full")
+	}
+	game.RejectFull()
+	if game.capacity != capacityRejected {
+		t.Error("expected the rejection to be recorded")
+	}
+	game.RemovePlayer(game.Players.Map["D"])
+	if game.capacity != capacityWarned || game.IsFull() {
+		t.Error("expected room again after a player left")
+	}
+	game.RemovePlayer(game.Players.Map["C"])
+	game.RemovePlayer(game.Players.Map["B"])
+	if game.capacity != capacityNone {
+		t.Error("expected the warning to clear below the threshold")
+	}
+}
+
+// TestSetMaxPlayers tests that hosts can only change the max players within the
+// server limit and not below the players already in the game
+func TestSetMaxPlayers(t *testing.T) {
+	defer func(previous int) { MaxPlayers = previous }(MaxPlayers)
+	MaxPlayers = 10
+	game := newTestGame(testQuestions())
+	game.Players.Map["A"] = &Player{Id: "A", Name: "A"}
+	game.Players.Map["B"] = &Player{Id: "B", Name: "B"}
+	for _, value := range []int{-1, 1, 11} {
+		if game.SetMaxPlayers(value) == nil {
+			t.Errorf("expected %d to be rejected", value)
+		}
+	}
+	if err := game.SetMaxPlayers(2); err != nil || game.GetMaxPlayers() != 2 || !game.IsFull() {
+		t.Errorf("expected the game to be full at 2 players: %v", err)
+	}
+	MaxPlayers = 0
+	if err := game.SetMaxPlayers(0); err != nil || game.IsFull() {
+		t.Errorf("expected no limit when the server has none: %v", err)
+	}
+}
+
+// TestUpdateSettings tests that only known settings are accepted and that the
+// settings can't be changed once the game has started
+func TestUpdateSettings(t *testing.T) {
+	game := newTestGame(testQuestions())
+	invalid := []net.GameSettings{
+		{Devices: DevicesBlock + 1},
+		{Reveal: RevealResult + 1},
+		{Scoring: ScoreExam + 1},
+	}
+	for _, settings := range invalid {
+		if game.UpdateSettings(settings) == nil {
+			t.Errorf("expected %+v to be rejected", settings)
+		}
+	}
+	settings := net.GameSettings{Reveal: RevealResult, Scoring: ScoreExam, Present: true}
+	if err := game.UpdateSettings(settings); err != nil || game.Settings() != settings {
+		t.Errorf("expected the settings to be updated: %v", err)
+	}
+	game.SetState(Started)
+	if game.UpdateSettings(net.GameSettings{}) == nil || game.Settings() != settings {
+		t.Error("expected the settings to be locked once the game started")
+	}
+}
+
+// TestPauseResume tests that resuming a paused game returns it to the state
+// it was paused in with its timers moved forward by the time spent paused
+func TestPauseResume(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.SetState(Started)
+	game.NextQuestion()
+	started := game.ActiveQuestion.StartTime
+	game.Pause()
+	if game.State != Paused || game.Host != nil {
+		t.Fatalf("expected paused game without a host got state %d", game.State)
+	}
+	game.PausedTime -= 5 * time.Second // Pretend the game was paused for 5 seconds
+	game.Resume(nil)
+	if game.State != Started {
+		t.Errorf("expected game to resume as started got %d", game.State)
+	}
+	if moved := game.ActiveQuestion.StartTime - started; moved < 5*time.Second {
+		t.Errorf("expected question timer to move forward by at least 5s got %s", moved)
+	}
+}
+
+// TestDisplays tests that displays receive the host view and that a game can't
+// have more than MaxDisplays
+func TestDisplays(t *testing.T) {
+	game := newTestGame(testQuestions())
+	for i := 0; i < MaxDisplays; i++ {
+		if !game.AddDisplay(&Connection{}) {
+			t.Fatalf("expected display %d to be added", i)
+		}
+	}
+	if game.AddDisplay(&Connection{}) {
+		t.Error("expected displays past MaxDisplays to be rejected")
+	}
+	if targets := game.HostTargets(); len(targets) != MaxDisplays {
+		t.Errorf("expected %d host targets without a host got %d", MaxDisplays, len(targets))
+	}
+	game.RemoveDisplay(game.Displays[0])
+	if len(game.Displays) != MaxDisplays-1 {
+		t.Errorf("expected display to be removed got %d displays", len(game.Displays))
+	}
+}
+
+// TestFanoutSlowConnection tests that a connection which isn't keeping up only
+// has its numbered events dropped and never stops the fanout from handing out
+// the rest of the packets
+func TestFanoutSlowConnection(t *testing.T) {
+	slow := &Connection{}
+	outbox := NewOutbox() // Nothing sends the packets queued for this connection
+	fanout := &Fanout{Queue: make(chan Delivery, FanoutBuffer), outboxes: map[*Connection]*Outbox{slow: outbox}}
+	done := make(chan struct{})
+	go func() {
+		fanout.Run()
+		close(done)
+	}()
+	for i := 0; i < ConnectionBuffer*4; i++ {
+		fanout.Push(net.SequencedPacket(uint64(i+1), net.PlayerCountPacket(i)), slow)
+	}
+	for i := 0; i < ConnectionBuffer*2; i++ { // A join into a full lobby sends a packet for each player
+		fanout.Push(net.PlayerDataPacket(fmt.Sprint(i), "Name", net.AddMode), slow)
+	}
+	fanout.Push(net.JoinGamePacket(net.JoinGameData{}), slow)
+	fanout.Close()
+	select {
+	case <-done:
+	case <-time.After(time.Second):
+		t.Fatal("expected the fanout to finish without waiting on the slow connection")
+	}
+	if expected := ConnectionBuffer * 3; outbox.Len() != expected {
+		t.Errorf("expected %d packets queued for the slow connection got %d", expected, outbox.Len())
+	}
+	if last := outbox.packets[len(outbox.packets)-1]; last.Id != net.SJoinedGame {
+		t.Errorf("expected the joined game packet to be kept got %d", last.Id)
+	}
+}
+
+// TestLoopPanic tests that a game whose loop panics is stopped instead of
+// crashing the server or leaving the players waiting
+func TestLoopPanic(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.State = Started
+	game.NextQuestion()
+	game.Players = PlayerStore{} // A player store without its lock makes checking the answers panic
+	game.Loop()
+	if game.State != Stopped {
+		t.Errorf("expected game to be stopped after its loop panicked got %d", game.State)
+	}
+}
+
+// TestInvalidActiveQuestion tests that a game whose active question is past the
+// end of its questions is ended instead of panicking
+func TestInvalidActiveQuestion(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.State = Started
+	question := game.Questions[0]
+	game.ActiveQuestion = &ActiveQuestion{Question: &question, Index: len(game.Questions) + 2, StartTime: Time()}
+	game.Loop()
+	if game.State != Stopped {
+		t.Errorf("expected game with an invalid question to be over got %d", game.State)
+	}
+	if game.QuestionAt(-1) != nil || game.QuestionAt(len(game.Questions)) != nil {
+		t.Error("expected questions outside the game to be nil")
+	}
+}
+
+// TestAnswerWithoutQuestion tests that answering before the first question or
+// after the last question is rejected instead of panicking
+func TestAnswerWithoutQuestion(t *testing.T) {
+	game := newTestGame(testQuestions())
+	player := &Player{Id: "P", Answers: map[QuestionIndex][]AnswerIndex{}}
+	if player.HasAnswered(game) {
+		t.Error("expected player to not have answered without a question")
+	}
+	if err := player.Answer(game, []AnswerIndex{0}); err != ErrNoQuestion {
+		t.Errorf("expected ErrNoQuestion got %v", err)
+	}
+	game.SetState(Started)
+	for game.State != Stopped { // Move past the last question
+		game.NextQuestion()
+	}
+	game.NextQuestion() // A timer firing after the game is over
+	if game.State != Stopped {
+		t.Errorf("expected game to stay over got %d", game.State)
+	}
+}
+
+// TestIsAsking checks that only answers meant for the question being asked are
+// accepted once the next question starts
+func TestIsAsking(t *testing.T) {
+	game := newTestGame(testQuestions())
+	if game.IsAsking(0) {
+		t.Error("expected no question to be asked before the game starts")
+	}
+	game.SetState(Started)
+	game.NextQuestion()
+	if !game.IsAsking(0) {
+		t.Error("expected the first question to be asked")
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	game.NextQuestion()
+	if game.IsAsking(0) || !game.IsAsking(1) {
+		t.Error("expected answers for the first question to be stale once the second is asked")
+	}
+}
+
+// TestDuplicateAnswer checks that answering the same question again keeps the
+// first answer and its time whether repeated answers are rejected or ignored
+func TestDuplicateAnswer(t *testing.T) {
+	defer func(mode string) { DuplicateAnswers = mode }(DuplicateAnswers)
+	game := newTestGame([]QuestionData{
+		{Question: "Pick", Answers: []string{"A", "B", "C"}, Values: []AnswerIndex{0}},
+		{Question: "One word", Kind: KindWords},
+	})
+	player := &Player{Id: "P", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.Players.Map[player.Id] = player
+	game.SetState(Started)
+	game.NextQuestion()
+	if err := player.Answer(game, []AnswerIndex{0}); err != nil {
+		t.Fatal(err)
+	}
+	first := player.AnswerTime
+	DuplicateAnswers = DuplicateError
+	if err := player.Answer(game, []AnswerIndex{1}); err != ErrAlreadyAnswered {
+		t.Errorf("expected ErrAlreadyAnswered got %v", err)
+	}
+	DuplicateAnswers = DuplicateKeep
+	if err := player.Answer(game, []AnswerIndex{2}); err != nil {
+		t.Errorf("expected the retry to be ignored got %v", err)
+	}
+	if answer, _ := player.GetAnswer(game.ActiveQuestion.Index); !reflect.DeepEqual(answer, []AnswerIndex{0}) {
+		t.Errorf("expected the first answer [0] to be kept got %v", answer)
+	}
+	if player.AnswerTime != first {
+		t.Errorf("expected the first answer time %s to be kept got %s", first, player.AnswerTime)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	if player.Score == 0 {
+		t.Error("expected the first answer to be marked")
+	}
+	game.NextQuestion()
+	if err := player.AnswerWords(game, "Blue"); err != nil {
+		t.Fatal(err)
+	}
+	if err := player.AnswerWords(game, "Green"); err != nil {
+		t.Errorf("expected the retry to be ignored got %v", err)
+	}
+	DuplicateAnswers = DuplicateError
+	if err := player.AnswerWords(game, "Blue"); err != ErrAlreadyAnswered {
+		t.Errorf("expected ErrAlreadyAnswered got %v", err)
+	}
+	expected := map[string]int{"blue": 1}
+	if words := game.PollResults(game.ActiveQuestion).Words; !reflect.DeepEqual(words, expected) {
+		t.Errorf("expected words %v got %v", expected, words)
+	}
+}
+
+// TestGameLockReport tests that waiting too long for a game lock is reported
+func TestGameLockReport(t *testing.T) {
+	timeout := LockTimeout
+	LockTimeout = 10 * time.Millisecond
+	defer func() { LockTimeout = timeout }()
+	lock := &GameLock{Name: "test"}
+	lock.Lock()
+	go func() {
+		time.Sleep(50 * time.Millisecond)
+		lock.Unlock()
+	}()
+	before := atomic.LoadInt64(&LockReports)
+	lock.RLock()
+	lock.RUnlock()
+	if atomic.LoadInt64(&LockReports) == before {
+		t.Error("expected the slow lock to be reported")
+	}
+}
+
+// TestQuestionStats tests that marking a question records how many players
+// answered it, how many stopped answering since the previous question and how
+// long the players took to answer
+func TestQuestionStats(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.Players.Map["A"] = &Player{Id: "A", Name: "A", AnswerTime: 2 * time.Second, Answers: map[QuestionIndex][]AnswerIndex{0: {0}, 1: {1}}}
+	game.Players.Map["B"] = &Player{Id: "B", Name: "B", AnswerTime: 3 * time.Second, Answers: map[QuestionIndex][]AnswerIndex{0: {0}}}
+	game.MarkQuestion(&ActiveQuestion{Question: &game.Questions[0], Index: 0})
+	game.MarkQuestion(&ActiveQuestion{Question: &game.Questions[1], Index: 1})
+	expected := []net.QuestionStats{
+		{Question: 0, Players: 2, Answered: 2, Stopped: 0, Median: 2000, P90: 3000, Fastest: "A", Quickest: 2000},
+		{Question: 1, Players: 2, Answered: 1, Stopped: 1, Median: 2000, P90: 2000, Fastest: "A", Quickest: 2000},
+	}
+	if got := game.Timeline.Summary().Questions; fmt.Sprint(got) != fmt.Sprint(expected) {
+		t.Errorf("expected %+v got %+v", expected, got)
+	}
+}
+
+// TestFirstCorrect tests that only the first player to fully answer correctly
+// earns the first correct bonus
+func TestFirstCorrect(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.settings.Scoring = ScoreExam
+	game.Questions[0].FirstBonus = 50
+	players := []*Player{
+		{Id: "A", Name: "A", Answers: map[QuestionIndex][]AnswerIndex{}},
+		{Id: "B", Name: "B", Answers: map[QuestionIndex][]AnswerIndex{}},
+		{Id: "C", Name: "C", Answers: map[QuestionIndex][]AnswerIndex{}},
+	}
+	for _, player := range players {
+		game.Players.Map[player.Id] = player
+	}
+	game.SetState(Started)
+	game.NextQuestion()
+	for i, answer := range []AnswerIndex{1, 0, 0} { // A is wrong then B is right before C
+		if err := players[i].Answer(game, []AnswerIndex{answer}); err != nil {
+			t.Fatal(err)
+		}
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	for i, expected := range []uint32{0, Points + 50, Points} {
+		if players[i].Score != expected {
+			t.Errorf("expected player %s to have %d points got %d", players[i].Id, expected, players[i].Score)
+		}
+	}
+}
+
+// TestStateSync tests that the state sent to a rejoining player includes the
+// TestBuzzer checks that wrong buzzer answers pass the turn to the next player
+// that buzzed and only the player judged correct earns the points
+func TestBuzzer(t *testing.T) {
+	game := newTestGame([]QuestionData{{Question: "Name a planet", Kind: KindBuzzer}})
+	game.settings.Scoring = ScoreExam
+	players := []*Player{
+		{Id: "A", Name: "A", Answers: map[QuestionIndex][]AnswerIndex{}},
+		{Id: "B", Name: "B", Answers: map[QuestionIndex][]AnswerIndex{}},
+		{Id: "C", Name: "C", Answers: map[QuestionIndex][]AnswerIndex{}},
+	}
+	for _, player := range players {
+		game.Players.Map[player.Id] = player
+	}
+	game.SetState(Started)
+	game.NextQuestion()
+	if err := game.JudgeBuzz(true); err != ErrNoBuzzer {
+		t.Errorf("expected ErrNoBuzzer got %v", err)
+	}
+	for _, player := range players[:2] { // A buzzes before B
+		if err := player.Buzz(game); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := players[0].Buzz(game); err != ErrAlreadyBuzzed {
+		t.Errorf("expected ErrAlreadyBuzzed got %v", err)
+	}
+	if err := players[2].Answer(game, []AnswerIndex{0}); err != ErrBuzzerQuestion {
+		t.Errorf("expected ErrBuzzerQuestion got %v", err)
+	}
+	if err := game.JudgeBuzz(false); err != nil || game.ActiveQuestion.Buzzer() != "B" {
+		t.Fatalf("expected B to answer after A was wrong got %q (%v)", game.ActiveQuestion.Buzzer(), err)
+	}
+	if game.QuestionEnded(game.ActiveQuestion, Time()) {
+		t.Error("expected the question to wait for the host to judge")
+	}
+	if err := game.JudgeBuzz(true); err != nil || !game.ActiveQuestion.Skipped {
+		t.Fatalf("expected a correct answer to end the question (%v)", err)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	for i, expected := range []uint32{0, Points, 0} {
+		if players[i].Score != expected {
+			t.Errorf("expected player %s to have %d points got %d", players[i].Id, expected, players[i].Score)
+		}
+	}
+}
+
+// TestPollResults checks that poll and word cloud responses are counted and
+// never earn any points
+func TestPollResults(t *testing.T) {
+	game := newTestGame([]QuestionData{
+		{Question: "Favourite?", Answers: []string{"A", "B", "C"}, Kind: KindPoll},
+		{Question: "One word", Kind: KindWords},
+	})
+	players := []*Player{
+		{Id: "A", Name: "A", Answers: map[QuestionIndex][]AnswerIndex{}},
+		{Id: "B", Name: "B", Answers: map[QuestionIndex][]AnswerIndex{}},
+		{Id: "C", Name: "C", Answers: map[QuestionIndex][]AnswerIndex{}},
+	}
+	for _, player := range players {
+		game.Players.Map[player.Id] = player
+	}
+	game.SetState(Started)
+	game.NextQuestion()
+	for i, answer := range []AnswerIndex{1, 2, 1} {
+		if err := players[i].Answer(game, []AnswerIndex{answer}); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if counts := game.PollResults(game.ActiveQuestion).Counts; !reflect.DeepEqual(counts, []int{0, 2, 1}) {
+		t.Errorf("expected counts [0 2 1] got %v", counts)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	game.NextQuestion()
+	if err := players[0].Answer(game, []AnswerIndex{0}); err != ErrWordsQuestion {
+		t.Errorf("expected ErrWordsQuestion got %v", err)
+	}
+	if err := players[0].AnswerWords(game, strings.Repeat("a", MaxWordLength+1)); err == nil {
+		t.Error("expected words longer than MaxWordLength to be rejected")
+	}
+	for i, text := range []string{"Blue  Sky", " blue sky", "Green"} {
+		if err := players[i].AnswerWords(game, text); err != nil {
+			t.Fatal(err)
+		}
+	}
+	expected := map[string]int{"blue sky": 2, "green": 1}
+	if words := game.PollResults(game.ActiveQuestion).Words; !reflect.DeepEqual(words, expected) {
+		t.Errorf("expected words %v got %v", expected, words)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	for _, player := range players {
+		if player.Score != 0 {
+			t.Errorf("expected player %s to have no points got %d", player.Id, player.Score)
+		}
+	}
+	if total := MaxTotalScore(game.Questions, ScoreTimed); total != 0 {
+		t.Errorf("expected survey questions to be worth nothing got %d", total)
+	}
+}
+
+// TestLocalizedQuestion checks that players are sent the translation closest to
+// their locale and the original question otherwise
+func TestLocalizedQuestion(t *testing.T) {
+	question := QuestionData{
+		Question: "Which is red?",
+		Answers:  []string{"Apple", "Pear"},
+		Values:   []AnswerIndex{0},
+		Audio:    "audio",
+		Translations: map[string]Translation{
+			"fr":    {Question: "Lequel est rouge ?", Answers: []string{"Pomme", "Poire"}},
+			"pt-BR": {Question: "Qual é vermelho?"},
+		},
+	}
+	if problems := question.Problems(); len(problems) != 0 {
+		t.Fatalf("expected the translations to be valid got %v", problems)
+	}
+	tests := []struct {
+		locale   string
+		question string
+		answer   string
+	}{
+		{"", "Which is red?", "Apple"},
+		{"fr-CA", "Lequel est rouge ?", "Pomme"},
+		{"PT-br", "Qual é vermelho?", "Apple"},
+		{"pt", "Which is red?", "Apple"},
+		{"de", "Which is red?", "Apple"},
+	}
+	for _, test := range tests {
+		localized := question.Localized(question.Locale(test.locale))
+		if localized.Question != test.question || localized.Answers[0] != test.answer {
+			t.Errorf("%s: expected %q with %q got %q with %q", test.locale, test.question, test.answer, localized.Question, localized.Answers[0])
+		}
+		if (localized.Audio == "") != (test.question != question.Question) {
+			t.Errorf("%s: expected audio only for the original question", test.locale)
+		}
+	}
+	question.Translations["fr"] = Translation{Question: "Lequel ?", Answers: []string{"Pomme"}}
+	question.Translations["not a locale"] = Translation{Question: "?"}
+	if problems := question.Problems(); len(problems) != 2 {
+		t.Errorf("expected problems with the answer count and locale got %v", problems)
+	}
+}
+
+// question being answered and only the results of marked questions
+func TestStateSync(t *testing.T) {
+	game := newTestGame(testQuestions())
+	player := &Player{Id: "P", Name: "P", Answers: map[QuestionIndex][]AnswerIndex{0: {0}, 1: {1}}}
+	game.Players.Map[player.Id] = player
+	game.SetState(Started)
+	game.NextQuestion()
+	game.NextQuestion()
+	data := game.StateSync(player)
+	if data.State != Started || data.Question == nil || data.Remaining <= 0 {
+		t.Errorf("expected the active question with time remaining got %+v", data)
+	}
+	if len(data.Results) != 1 || !data.Results[0] {
+		t.Errorf("expected only the marked question result got %v", data.Results)
+	}
+	if game.StateSync(nil).Results != nil {
+		t.Error("expected no results without a player")
+	}
+}
+
+func TestStateSyncReveal(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.settings.Reveal = RevealResult
+	game.Players.Map["A"] = &Player{Id: "A", Name: "A", Score: 10}
+	game.Players.Map["B"] = &Player{Id: "B", Name: "B", Score: 20}
+	game.SetState(Started)
+	scores := game.StateSync(game.Players.Map["A"]).Scores
+	if len(scores) != 1 || scores["A"] != 10 {
+		t.Errorf("expected only the players own score got %v", scores)
+	}
+	if len(game.StateSync(nil).Scores) != 2 {
+		t.Error("expected every score for the host")
+	}
+	game.SetState(Stopped)
+	if len(game.StateSync(game.Players.Map["A"]).Scores) != 2 {
+		t.Error("expected every score once the game is over")
+	}
+}
+
+// TestPlaylistRounds tests that the points earned in each quiz of a playlist are
+// recorded when the game moves on to the next quiz and when the game ends
+func TestPlaylistRounds(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.Rounds = []Round{{Title: "First"}, {Title: "Second", First: 2}}
+	player := &Player{Id: "A", Name: "A"}
+	game.Players.Map[player.Id] = player
+	game.SetState(Started)
+	for range game.Questions {
+		game.NextQuestion()
+		player.Score += 10
+	}
+	if round := game.RoundAt(game.ActiveQuestion.Index); round != 1 {
+		t.Errorf("expected the last question to be in the second quiz got %d", round)
+	}
+	game.NextQuestion() // Move past the last question to end the game
+	rounds := game.Timeline.Summary().Rounds
+	if len(rounds) != 2 {
+		t.Fatalf("expected the scores of both quizzes got %+v", rounds)
+	}
+	if rounds[0].Next != "Second" || rounds[0].Scores[player.Id] != 20 {
+		t.Errorf("expected 20 points in the first quiz got %+v", rounds[0])
+	}
+	if rounds[1].Next != "" || rounds[1].Scores[player.Id] != 40 {
+		t.Errorf("expected 40 points in the second quiz got %+v", rounds[1])
+	}
+}
+
+// TestWarmUp tests that votes in the warm-up poll are counted without touching
+// the answers or scores and that voting ends once the game starts
+func TestWarmUp(t *testing.T) {
+	game := newTestGame(testQuestions())
+	player := &Player{Id: "A", Name: "A", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.Players.Map[player.Id] = player
+	if err := player.VoteWarmUp(game, 0); err != ErrNoWarmUp {
+		t.Errorf("expected no warm-up poll got %v", err)
+	}
+	if err := game.StartWarmUp(net.WarmUpData{Question: "Ready?", Answers: []string{"👍"}}); err == nil {
+		t.Error("expected a poll with one answer to be rejected")
+	}
+	if err := game.StartWarmUp(net.WarmUpData{Question: "Ready?", Answers: []string{"👍", "👎"}}); err != nil {
+		t.Fatal(err)
+	}
+	if err := player.VoteWarmUp(game, 2); err == nil {
+		t.Error("expected a vote for a missing answer to be rejected")
+	}
+	_ = player.VoteWarmUp(game, 0)
+	_ = player.VoteWarmUp(game, 1) // Players can change their vote
+	if counts := game.warmUpCounts(game.warmUp); counts[0] != 0 || counts[1] != 1 {
+		t.Errorf("expected one vote for the second answer got %v", counts)
+	}
+	if len(player.Answers) != 0 || player.Score != 0 {
+		t.Error("expected the warm-up vote to be kept apart from the answers and score")
+	}
+	game.Start()
+	if err := player.VoteWarmUp(game, 0); err != ErrNoWarmUp {
+		t.Errorf("expected the warm-up poll to end when the game starts got %v", err)
+	}
+	if err := game.StartWarmUp(net.WarmUpData{Question: "Ready?", Answers: []string{"👍", "👎"}}); err != ErrWarmUpStarted {
+		t.Errorf("expected warm-up polls to be rejected once the game starts got %v", err)
+	}
+}
+
+// TestResults tests that the final results rank the players by score with ties
+// sharing a rank and include the points earned for each question
+func TestResults(t *testing.T) {
+	game := newTestGame(testQuestions())
+	scores := map[Identifier][]uint32{"A": {100, 0, 50}, "B": {150}, "C": {0, 20}, "D": {}}
+	for id, points := range scores {
+		player := &Player{Id: id, Name: id}
+		for index, earned := range points {
+			player.AddPoints(index, earned)
+		}
+		game.Players.Map[id] = player
+	}
+	results := game.Results()
+	want := []struct {
+		id    Identifier
+		score uint32
+		rank  int
+	}{{"A", 150, 1}, {"B", 150, 1}, {"C", 20, 3}, {"D", 0, 4}}
+	if len(results) != len(want) {
+		t.Fatalf("expected %d results got %d", len(want), len(results))
+	}
+	for i, result := range results {
+		if result.Id != want[i].id || result.Score != want[i].score || result.Rank != want[i].rank {
+			t.Errorf("result %d: got %+v want %+v", i, result, want[i])
+		}
+		if len(result.Points) != len(game.Questions) {
+			t.Errorf("expected points for each question got %v", result.Points)
+		}
+	}
+	if results[0].Points[2] != 50 {
+		t.Errorf("expected 50 points for the third question got %v", results[0].Points)
+	}
+}
+
+// TestSequence checks that events sent to everyone are numbered one after
+// another and that resyncing sends the missed events or the full state once
+// they are no longer kept
+func TestSequence(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.Fanout = &Fanout{Queue: make(chan Delivery, SequenceHistory+8)}
+	host := &Connection{}
+	game.Host = host
+	drain := func() []Packet {
+		var packets []Packet
+		for len(game.Fanout.Queue) > 0 {
+			packets = append(packets, (<-game.Fanout.Queue).Packet)
+		}
+		return packets
+	}
+	seqOf := func(packet Packet) uint64 {
+		var data struct {
+			Seq uint64 `json:"seq"`
+		}
+		encoded, _ := json.Marshal(packet.Data)
+		_ = json.Unmarshal(encoded, &data)
+		return data.Seq
+	}
+	for i := 0; i < 3; i++ {
+		game.Broadcast(net.PlayerCountPacket(i), true)
+	}
+	game.Broadcast(net.ScoresPacket(ScoreMap{}), false) // Only sent to the players so isn't numbered
+	if seq := game.Sequence(); seq != 3 {
+		t.Errorf("expected 3 numbered events got %d", seq)
+	}
+	drain()
+	game.Resync(host, nil, 1)
+	resent := drain()
+	if len(resent) != 2 || seqOf(resent[0]) != 2 || seqOf(resent[1]) != 3 {
+		t.Errorf("expected events 2 and 3 to be sent again got %v", resent)
+	}
+	for i := 0; i < SequenceHistory; i++ {
+		game.Broadcast(net.PlayerCountPacket(i), true)
+	}
+	drain()
+	game.Resync(host, nil, 1)
+	if resent := drain(); len(resent) != 1 || resent[0].Id != net.SStateSync {
+		t.Errorf("expected the full state once the missed events are forgotten got %v", resent)
+	}
+	game.Resync(host, nil, game.Sequence()-ConnectionBuffer-1)
+	if resent := drain(); len(resent) != 1 || resent[0].Id != net.SStateSync {
+		t.Errorf("expected the full state when the missed events don't fit in the outbox got %v", resent)
+	}
+}
+
+// TestJoinSequence tests that a joining player is sent the joined game packet
+// before any numbered event and that the first numbered event they receive is
+// the one after the number it includes
+func TestJoinSequence(t *testing.T) {
+	game := newTestGame(testQuestions())
+	game.Fanout = &Fanout{Queue: make(chan Delivery, 32)}
+	game.Host = &Connection{}
+	game.Broadcast(net.PlayerCountPacket(0), true)
+	game.Broadcast(net.PlayerCountPacket(0), true)
+	conn := &Connection{}
+	game.Join(conn, "Amy", Device{})
+	sentTo := func(delivery Delivery) bool {
+		for _, target := range delivery.Targets {
+			if target == conn {
+				return true
+			}
+		}
+		return false
+	}
+	var joined *net.JoinGameData
+	for len(game.Fanout.Queue) > 0 {
+		delivery := <-game.Fanout.Queue
+		if !sentTo(delivery) {
+			continue
+		}
+		switch delivery.Packet.Id {
+		case net.SJoinedGame:
+			data := delivery.Packet.Data.(net.JoinGameData)
+			joined = &data
+		case net.SSequenced:
+			if joined == nil {
+				t.Fatal("expected the joined game packet before any numbered event")
+			}
+			var data struct {
+				Seq uint64 `json:"seq"`
+			}
+			encoded, _ := json.Marshal(delivery.Packet.Data)
+			_ = json.Unmarshal(encoded, &data)
+			if data.Seq != joined.Seq+1 {
+				t.Errorf("expected the first numbered event to be %d got %d", joined.Seq+1, data.Seq)
+			}
+			return
+		}
+	}
+	t.Error("expected the player to be sent a numbered event after joining")
+}
+
+// TestSnapshot tests that snapshots list the players by name with only the
+// requesting player's own score and the countdown of the current question
+func TestSnapshot(t *testing.T) {
+	questions := testQuestions()
+	game := newTestGame(questions)
+	zed := &Player{Id: "Z", Name: "Zed", Score: 300}
+	amy := &Player{Id: "A", Name: "Amy", Score: 100}
+	game.Players.Map[zed.Id] = zed
+	game.Players.Map[amy.Id] = amy
+	if data := game.Snapshot(nil); data.Question != nil || data.Remaining != 0 {
+		t.Errorf("expected no question or countdown while waiting got %+v", data)
+	}
+	game.State = Started
+	game.ActiveQuestion = &ActiveQuestion{Question: &questions[1], Index: 1, StartTime: Time()}
+	data := game.Snapshot(amy)
+	if data.Question == nil || *data.Question != 1 {
+		t.Errorf("expected the snapshot to be at question 1 got %v", data.Question)
+	}
+	if data.Score == nil || *data.Score != amy.Score {
+		t.Errorf("expected the own score %d got %v", amy.Score, data.Score)
+	}
+	if len(data.Players) != 2 || data.Players[0].Name != "Amy" || data.Players[1].Name != "Zed" {
+		t.Errorf("expected the players sorted by name got %v", data.Players)
+	}
+	if data.Total != QuestionTime.Milliseconds() || data.Remaining <= 0 || data.Remaining > data.Total {
+		t.Errorf("expected the question countdown got %d of %d", data.Remaining, data.Total)
+	}
+	if host := game.Snapshot(nil); host.Score != nil {
+		t.Errorf("expected no score for the host got %d", *host.Score)
+	}
+}
+
+// TestWriteAnswers tests that the raw answers are written as CSV with the text of
+// the chosen answers and the typed words kept as they were submitted
+func TestWriteAnswers(t *testing.T) {
+	questions := []QuestionData{
+		{Question: "Pick", Answers: []string{"A", "B"}, Values: []AnswerIndex{0}},
+		{Question: "Say", Kind: KindWords},
+	}
+	game := newTestGame(questions)
+	player := &Player{Id: "P", Name: "=Sum", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.Players.Map[player.Id] = player
+	game.ActiveQuestion = &ActiveQuestion{Question: &questions[0], Index: 0, StartTime: Time()}
+	if err := player.Answer(game, []AnswerIndex{1}); err != nil {
+		t.Fatalf("failed to answer: %s", err)
+	}
+	game.MarkQuestion(game.ActiveQuestion)
+	game.ActiveQuestion = &ActiveQuestion{Question: &questions[1], Index: 1, StartTime: Time()}
+	if err := player.AnswerWords(game, "  Hello   World "); err != nil {
+		t.Fatalf("failed to answer: %s", err)
+	}
+	var out strings.Builder
+	if err := game.WriteAnswers(&out, ""); err != nil {
+		t.Fatalf("failed to write answers: %s", err)
+	}
+	rows := strings.Split(strings.TrimSpace(out.String()), "\n")
+	if len(rows) != 3 {
+		t.Fatalf("expected a header and 2 answers got %q", rows)
+	}
+	if !strings.HasPrefix(rows[1], "1,Pick,P,'=Sum,B,") {
+		t.Errorf("expected the chosen answer text kept after marking got %q", rows[1])
+	}
+	if !strings.HasPrefix(rows[2], "2,Say,P,'=Sum,Hello World,") {
+		t.Errorf("expected the words as they were typed once trimmed got %q", rows[2])
+	}
+}
+
+// TestWriteAnswersLocale tests that the raw answers are written with the headers,
+// translations and number format of the requested locale
+func TestWriteAnswersLocale(t *testing.T) {
+	questions := []QuestionData{
+		{Question: "Pick", Answers: []string{"Red", "Blue"}, Values: []AnswerIndex{0}, Translations: map[string]Translation{
+			"fr": {Question: "Choisir", Answers: []string{"Rouge", "Bleu"}},
+		}},
+		{Question: "How far?", Kind: KindNumber, Numeric: &NumericData{Slider: Slider{Min: 0, Max: 10}, Answer: 2}},
+	}
+	game := newTestGame(questions)
+	player := &Player{Id: "P", Name: "Amy", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.Players.Map[player.Id] = player
+	game.ActiveQuestion = &ActiveQuestion{Question: &questions[0], Index: 0, StartTime: Time()}
+	_ = player.Answer(game, []AnswerIndex{1})
+	game.ActiveQuestion = &ActiveQuestion{Question: &questions[1], Index: 1, StartTime: Time()}
+	if err := player.AnswerNumber(game, 1.5); err != nil {
+		t.Fatalf("failed to answer: %s", err)
+	}
+	var out strings.Builder
+	_ = game.WriteAnswers(&out, "fr-CA")
+	rows := strings.Split(strings.TrimSpace(out.String()), "\n")
+	if len(rows) != 3 || rows[0] != "Question;Texte de la question;Joueur;Nom;Réponse;Temps (ms)" {
+		t.Fatalf("expected french headers got %q", rows)
+	}
+	if !strings.HasPrefix(rows[1], "1;Choisir;P;Amy;Bleu;") {
+		t.Errorf("expected the translated question and answer got %q", rows[1])
+	}
+	if !strings.HasPrefix(rows[2], "2;How far?;P;Amy;1,5;") {
+		t.Errorf("expected a decimal comma got %q", rows[2])
+	}
+	out.Reset()
+	_ = game.WriteAnswers(&out, "xx")
+	if !strings.HasPrefix(out.String(), "Question,Question Text,") {
+		t.Errorf("expected unknown locales to be written in english got %q", out.String())
+	}
+}
+
+// TestHoldAnswer tests that a held answer is removed from the word cloud and has
+// its text hidden from the raw answers while still counting as answered
+func TestHoldAnswer(t *testing.T) {
+	questions := []QuestionData{{Question: "Say", Kind: KindWords}}
+	game := newTestGame(questions)
+	player := &Player{Id: "P", Name: "Amy", Answers: map[QuestionIndex][]AnswerIndex{}}
+	other := &Player{Id: "O", Name: "Zed", Answers: map[QuestionIndex][]AnswerIndex{}}
+	game.ActiveQuestion = &ActiveQuestion{Question: &questions[0], Index: 0, StartTime: Time()}
+	_ = player.AnswerWords(game, "Rude")
+	_ = other.AnswerWords(game, "rude")
+	game.HoldAnswer(player, 0)
+	if words := game.PollResults(game.ActiveQuestion).Words; words["rude"] != 1 {
+		t.Errorf("expected only the answer that wasn't held in the word cloud got %v", words)
+	}
+	if answers := game.RawAnswers(); !answers[0].Held || answers[0].Text != "" || answers[1].Held {
+		t.Errorf("expected only the held answer to be hidden got %+v", answers)
+	}
+	if !player.HasAnswered(game) {
+		t.Error("expected the held answer to still count as answered")
+	}
+}
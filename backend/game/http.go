@@ -0,0 +1,107 @@
+package game
+
+import (
+	"backend/net"
+	"backend/tools"
+	"fmt"
+	"net/http"
+	"strings"
+)
+
+// GameInfo The public details of a game which join pages can show before
+// opening a websocket
+type GameInfo struct {
+	tools.TextStyle             // How the text of the game should be shown
+	Id              string      `json:"id"`                   // The game code
+	Title           string      `json:"title"`                // The title of the game
+	Host            string      `json:"host,omitempty"`       // The display name of the host if they chose one
+	State           tools.State `json:"state"`                // The current state of the game
+	Players         int         `json:"players"`              // The number of players in the game
+	MaxPlayers      int         `json:"maxPlayers,omitempty"` // The most players that can join the game (omitted for no limit)
+	Questions       int         `json:"questions"`            // The number of questions in the game
+	Joinable        bool        `json:"joinable"`             // Whether players can currently join the game
+	Locales         []string    `json:"locales,omitempty"`    // The languages the questions are translated into
+}
+
+// HandleGame HTTP handler for the /api/game/{id}/... endpoints:
+//
+//	GET /api/game/{id}/info responds with the public GameInfo of the game
+//	GET /api/game/{id}/timeline responds with the events that happened during the game
+//	GET /api/game/{id}/answers?locale=fr responds with a CSV of the answers submitted so far
+func HandleGame(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodGet { // Only accept GET requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	parts := strings.Split(strings.TrimPrefix(request.URL.Path, "/api/game/"), "/")
+	if len(parts) != 2 {
+		tools.WriteError(writer, http.StatusNotFound, "Not found")
+		return
+	}
+	switch parts[1] {
+	case "info":
+		handleInfo(writer, parts[0])
+	case "timeline":
+		handleTimeline(writer, request, parts[0])
+	case "answers":
+		handleAnswers(writer, request, parts[0])
+	default:
+		tools.WriteError(writer, http.StatusNotFound, "Not found")
+	}
+}
+
+// handleInfo responds with the public details of the game with the provided id
+func handleInfo(writer http.ResponseWriter, id tools.Identifier) {
+	game := Get(id)
+	if game == nil {
+		tools.WriteError(writer, http.StatusNotFound, "That game code doesn't exist")
+		return
+	}
+	writer.Header().Set("Cache-Control", "no-store")
+	tools.WriteJson(writer, http.StatusOK, game.Info())
+}
+
+// handleTimeline responds with the events that happened during the game. The
+// host key of the game must be sent as the bearer token. Finished games are
+// kept for TimelineCapacity games
+func handleTimeline(writer http.ResponseWriter, request *http.Request, id tools.Identifier) {
+	key := strings.TrimPrefix(request.Header.Get("Authorization"), "Bearer ")
+	timeline := FindTimeline(id, key)
+	if timeline == nil { // Unknown games and wrong keys are treated the same
+		tools.WriteError(writer, http.StatusNotFound, "Unknown game or invalid host key")
+		return
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Events    []Event             `json:"events"`    // The events from oldest to newest
+		Questions []net.QuestionStats `json:"questions"` // The participation in each marked question
+	}{Events: timeline.Copy(), Questions: timeline.Summary().Questions})
+}
+
+// handleAnswers responds with a CSV of the answers submitted so far in the game
+// including any typed text. The host key of the game must be sent as the bearer
+// token. Only games that are still running can be fetched. The CSV is written in
+// the locale query parameter or the first language the client accepts
+func handleAnswers(writer http.ResponseWriter, request *http.Request, id tools.Identifier) {
+	key := strings.TrimPrefix(request.Header.Get("Authorization"), "Bearer ")
+	game := Get(id)
+	if game == nil || !tools.SecretEquals(game.Key, key) { // Unknown games and wrong keys are treated the same
+		tools.WriteError(writer, http.StatusNotFound, "Unknown game or invalid host key")
+		return
+	}
+	header := writer.Header()
+	header.Set("Content-Type", "text/csv; charset=utf-8")
+	header.Set("Content-Disposition", fmt.Sprintf("attachment; filename=\"%s-answers.csv\"", game.Id))
+	header.Set("Cache-Control", "no-store")
+	header.Set("Vary", "Accept-Language")
+	locale := request.URL.Query().Get("locale")
+	if locale == "" { // Use the first language the client accepts (e.g. fr-CA from fr-CA,fr;q=0.9)
+		locale, _, _ = strings.Cut(request.Header.Get("Accept-Language"), ",")
+		locale, _, _ = strings.Cut(locale, ";")
+		locale = strings.TrimSpace(locale)
+	}
+	if !tools.IsLocale(locale) {
+		locale = ""
+	}
+	writer.WriteHeader(http.StatusOK)
+	_ = game.WriteAnswers(writer, locale)
+}
@@ -0,0 +1,82 @@
+package game
+
+import (
+	. "backend/tools"
+	"log"
+	"runtime/debug"
+	"sync"
+	"sync/atomic"
+	"time"
+)
+
+// Configuration for detecting deadlocks on the game locks
+var (
+	LockTimeout = time.Duration(EnvIntOrDefault("QUIZLER_LOCK_TIMEOUT", 5)) * time.Second // How long to wait for a lock before reporting a possible deadlock
+	DebugLocks  = EnvOrDefault("QUIZLER_DEBUG_LOCKS", "false") == "true"                  // Whether to record where write locks were acquired
+)
+
+// LockReports The number of times waiting for a game lock took longer than LockTimeout
+var LockReports int64
+
+// GameLock A read write lock for the games map and the state of each game which
+// reports when acquiring it takes longer than LockTimeout so a deadlock is logged
+// rather than silently hanging every player in the game. When DebugLocks is
+// enabled the stack of the goroutine holding the write lock is included.
+//
+// Locks must be acquired in this order so that they can't deadlock:
+//
+//	GamesLock, Game.sequence.lock, Game.Players.Lock, Game.DisplaysLock, Timeline.Lock
+type GameLock struct {
+	sync.RWMutex
+	Name string // The name of the lock used in reports
+
+	ownerLock sync.Mutex // A lock for modifying the owner
+	owner     []byte     // The stack of the goroutine holding the write lock when DebugLocks is enabled
+}
+
+// Lock acquires the write lock reporting if it takes longer than LockTimeout
+func (lock *GameLock) Lock() {
+	if !lock.RWMutex.TryLock() { // Only start the timer if the lock is contended
+		timer := time.AfterFunc(LockTimeout, func() { lock.report("write") })
+		lock.RWMutex.Lock()
+		timer.Stop()
+	}
+	if DebugLocks {
+		lock.ownerLock.Lock()
+		lock.owner = debug.Stack()
+		lock.ownerLock.Unlock()
+	}
+}
+
+// Unlock releases the write lock
+func (lock *GameLock) Unlock() {
+	if DebugLocks {
+		lock.ownerLock.Lock()
+		lock.owner = nil
+		lock.ownerLock.Unlock()
+	}
+	lock.RWMutex.Unlock()
+}
+
+// RLock acquires a read lock reporting if it takes longer than LockTimeout
+func (lock *GameLock) RLock() {
+	if !lock.RWMutex.TryRLock() { // Only start the timer if the lock is contended
+		timer := time.AfterFunc(LockTimeout, func() { lock.report("read") })
+		lock.RWMutex.RLock()
+		timer.Stop()
+	}
+}
+
+// report logs that acquiring the lock has taken longer than LockTimeout along
+// with where the lock is held from when known
+func (lock *GameLock) report(kind string) {
+	atomic.AddInt64(&LockReports, 1)
+	lock.ownerLock.Lock()
+	owner := lock.owner
+	lock.ownerLock.Unlock()
+	if owner == nil {
+		log.Printf("Possible deadlock: waited %s for %s lock on %s (set QUIZLER_DEBUG_LOCKS=true to see the holder)", LockTimeout, kind, lock.Name)
+	} else {
+		log.Printf("Possible deadlock: waited %s for %s lock on %s held by:\n%s", LockTimeout, kind, lock.Name, owner)
+	}
+}
@@ -0,0 +1,81 @@
+package game
+
+import (
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// The limits for the length of player names in characters. The maximum
+// matches the limit the frontend join page applies
+const (
+	MinNameLength = 1
+	MaxNameLength = 12
+)
+
+// ReservedNames Names that players can't join with because they could be
+// mistaken for the host or messages from the server. Stored normalized
+var ReservedNames = []string{"host", "system", "server", "admin"}
+
+// Characters that look like other characters mapped to the character they look
+// like so that names can't impersonate other players using them
+var confusables = map[rune]rune{
+	// Cyrillic
+	'а': 'a', 'в': 'b', 'е': 'e', 'к': 'k', 'м': 'm', 'н': 'h', 'о': 'o', 'р': 'p',
+	'с': 'c', 'т': 't', 'у': 'y', 'х': 'x', 'і': 'l', 'ј': 'j', 'ѕ': 's', 'ԁ': 'd',
+	// Greek
+	'α': 'a', 'β': 'b', 'ε': 'e', 'η': 'n', 'ι': 'l', 'κ': 'k', 'ν': 'v', 'ο': 'o',
+	'ρ': 'p', 'τ': 't', 'υ': 'u', 'χ': 'x',
+	// Digits and symbols
+	'0': 'o', '1': 'l', '3': 'e', '5': 's', '@': 'a', '$': 's', '|': 'l', '!': 'l',
+	// Letters that look the same once lower case
+	'i': 'l',
+}
+
+// NormalizeName converts a name into the form used to compare names. Case,
+// spacing and invisible characters are removed and characters that look like
+// others are replaced so that names which look the same normalize the same
+func NormalizeName(name string) string {
+	var builder strings.Builder
+	for _, char := range name {
+		if unicode.IsSpace(char) || unicode.In(char, unicode.Mn, unicode.Cf, unicode.Cc) {
+			continue // Skip spacing, combining marks and invisible characters
+		}
+		char = unicode.ToLower(char)
+		if replacement, exists := confusables[char]; exists {
+			char = replacement
+		}
+		builder.WriteRune(char)
+	}
+	return builder.String()
+}
+
+// IsValidName checks that the name is between MinNameLength and MaxNameLength
+// characters long and has at least one visible character
+func IsValidName(name string) bool {
+	length := utf8.RuneCountInString(name)
+	return length >= MinNameLength && length <= MaxNameLength && NormalizeName(name) != ""
+}
+
+// IsNameReserved checks whether the name looks like one of the ReservedNames
+func IsNameReserved(name string) bool {
+	normalized := NormalizeName(name)
+	for _, reserved := range ReservedNames {
+		if normalized == NormalizeName(reserved) {
+			return true
+		}
+	}
+	return false
+}
+
+// IsNameTaken checks the game players and the host to see if they already have
+// a name that looks the same as the provided name
+func (game *Game) IsNameTaken(name string) bool {
+	normalized := NormalizeName(name)
+	if game.HostName != "" && NormalizeName(game.HostName) == normalized { // If the name looks like the host
+		return true
+	}
+	return game.Players.AnyMatch(func(player *Player) bool {
+		return NormalizeName(player.Name) == normalized
+	})
+}
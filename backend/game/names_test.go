@@ -0,0 +1,36 @@
+package game
+
+import "testing"
+
+// TestNormalizeName checks that names which look the same normalize the same
+func TestNormalizeName(t *testing.T) {
+	tests := []struct {
+		a, b string
+		same bool
+	}{
+		{"Jacob", "jacob", true},
+		{"Jacob", "J a c o b", true},
+		{"Jacob", "Jаcоb", true}, // Cyrillic a and o
+		{"Bill", "B1ll", true},
+		{"Bill", "BiII", true},
+		{"Bob", "Bo​b", true}, // Zero width space
+		{"Bob", "Rob", false},
+	}
+	for _, test := range tests {
+		if same := NormalizeName(test.a) == NormalizeName(test.b); same != test.same {
+			t.Errorf("%q and %q: got same %t want %t", test.a, test.b, same, test.same)
+		}
+	}
+}
+
+// TestIsNameReserved checks that reserved names are rejected in any form
+func TestIsNameReserved(t *testing.T) {
+	for _, name := range []string{"HOST", "H 0 S T", "System", "Hоst"} {
+		if !IsNameReserved(name) {
+			t.Errorf("%q should be reserved", name)
+		}
+	}
+	if IsNameReserved("Hosty") {
+		t.Error("\"Hosty\" shouldn't be reserved")
+	}
+}
@@ -0,0 +1,69 @@
+package game
+
+import (
+	. "backend/tools"
+	"errors"
+	"fmt"
+	"math"
+)
+
+// Errors returned when answering a number question isn't possible
+var (
+	ErrNumberQuestion = errors.New("number questions are answered with a number")
+	ErrNotNumber      = errors.New("the question isn't a number question")
+)
+
+// numberEpsilon Allowance for rounding when comparing the distance of a number
+// from the answer so numbers chosen with decimal slider steps aren't marked wrong
+const numberEpsilon = 1e-9
+
+// AnswerNumber sets the number the player chose as their answer to the current
+// number question. Returns an error without answering if the number isn't on
+// the slider of the question
+func (player *Player) AnswerNumber(game *Game, number float64) error {
+	q := game.ActiveQuestion           // Retrieve the active question from the game
+	if q == nil || q.Question == nil { // If the game hasn't started or has finished
+		return ErrNoQuestion
+	}
+	numeric := q.Question.Numeric
+	if q.Question.Kind != KindNumber || numeric == nil {
+		return ErrNotNumber
+	}
+	if player.HasAnswered(game) { // Keep the first answer when the player answers again
+		return player.repeatAnswer(game, q)
+	}
+	if math.IsNaN(number) || number < numeric.Min || number > numeric.Max {
+		return fmt.Errorf("choose a number between %g and %g", numeric.Min, numeric.Max)
+	}
+	player.AnswerTime = Time() // Set the time of answer
+	if player.Numbers == nil {
+		player.Numbers = map[QuestionIndex]float64{}
+	}
+	player.Numbers[q.Index] = number
+	player.Answers[q.Index] = []AnswerIndex{}
+	if q.Question.FirstBonus > 0 && q.NumberCredit(number) == 1 { // Only numbers within the tolerance can earn the bonus
+		q.ClaimFirstCorrect(player.Id)
+	}
+	game.Timeline.RecordQuestion(EventAnswer, q.Index, player.Id)
+	player.recordAnswer(game, q, RawAnswer{Number: &number})
+	player.acceptAnswer(game, q)
+	return nil
+}
+
+// NumberCredit calculates the share of the points the provided number earns for
+// a number question. Numbers within the tolerance earn every point and with
+// partial marking the share falls evenly to nothing over the margin
+func (question *ActiveQuestion) NumberCredit(number float64) float64 {
+	numeric := question.Question.Numeric
+	if numeric == nil {
+		return 0
+	}
+	over := math.Abs(number-numeric.Answer) - numeric.Tolerance // How far past the tolerance the number is
+	if over <= numberEpsilon {
+		return 1
+	}
+	if question.Question.Marking != MarkPartial || numeric.Margin <= 0 {
+		return 0
+	}
+	return math.Max(1-over/numeric.Margin, 0)
+}
@@ -3,26 +3,42 @@ package game
 import (
 	"backend/net"
 	. "backend/tools"
+	"errors"
+	"fmt"
 	"github.com/jacobtread/gowsps"
-	"sync"
 	"time"
 )
 
 type (
 	// Player A structure representing a player in the game
 	Player struct {
-		Net        *gowsps.Connection            // The connection to the player socket
-		Id         Identifier                    // The unique ID of this player
-		Name       string                        // The name of this player
-		Score      uint32                        // The score this player has
-		Answers    map[QuestionIndex]AnswerIndex // A map of the question index to the answer chosen
-		AnswerTime time.Duration                 // The time of which the player provided its answer
+		Net        *gowsps.Connection              // The connection to the player socket
+		Id         Identifier                      // The unique ID of this player
+		Name       string                          // The name of this player
+		Score      uint32                          // The score this player has
+		Answers    map[QuestionIndex][]AnswerIndex // A map of the question index to the answers chosen
+		Points     map[QuestionIndex]uint32        // A map of the question index to the points earned for it
+		Numbers    map[QuestionIndex]float64       // A map of the question index to the number chosen for number questions
+		Blanks     map[QuestionIndex][]string      // A map of the question index to the words written for fill in the blanks questions
+		AnswerTime time.Duration                   // The time of which the player provided its answer
+		Audio      bool                            // Whether the player receives the question audio
+		Compact    bool                            // Whether the player only receives the answer count for each question
+		Locale     string                          // The language the player wants the questions in or empty for the original
+		Device     Device                          // The device the player joined from
+	}
+
+	// Device A structure representing the device that a connection was made from.
+	// Two connections with the same address and user agent are treated as the
+	// same device
+	Device struct {
+		Address string // The address of the client
+		Agent   string // The user agent of the client
 	}
 
 	// PlayerStore A structure for storing, retrieving, removing and overall
 	// management of players for the game
 	PlayerStore struct {
-		Lock *sync.RWMutex          // A lock for ensuring that writes are synchronized
+		Lock *GameLock              // A lock for ensuring that writes are synchronized
 		Map  map[Identifier]*Player // The underlying map that stores the players mapped to Identifier's
 	}
 )
@@ -30,14 +46,14 @@ type (
 // NewPlayerStore Creates a new player store
 func NewPlayerStore() PlayerStore {
 	return PlayerStore{
-		Lock: &sync.RWMutex{},
+		Lock: &GameLock{Name: "players"},
 		Map:  map[Identifier]*Player{},
 	}
 }
 
-// GetAnswer retrieves the player answer for the provided question index and
+// GetAnswer retrieves the player answers for the provided question index and
 // returns both the value and weather it exists or not
-func (player *Player) GetAnswer(index QuestionIndex) (AnswerIndex, bool) {
+func (player *Player) GetAnswer(index QuestionIndex) ([]AnswerIndex, bool) {
 	// Retrieve the value
 	answer, exists := player.Answers[index]
 	return answer, exists
@@ -45,21 +61,139 @@ func (player *Player) GetAnswer(index QuestionIndex) (AnswerIndex, bool) {
 
 // HasAnswered Checks whether the player has already answered the current question
 func (player *Player) HasAnswered(game *Game) bool {
-	q := game.ActiveQuestion               // Retrieve the active question from the game
+	q := game.ActiveQuestion // Retrieve the active question from the game
+	if q == nil {            // There is no question to have answered
+		return false
+	}
 	_, contains := player.Answers[q.Index] // Retrieve the player answer
 	return contains
 }
 
-// Answer sets the player answer to the provided answer index for the current quest
-func (player *Player) Answer(game *Game, id AnswerIndex) {
-	player.AnswerTime = Time()                       // Set the time of answer
-	q := game.ActiveQuestion                         // Retrieve the active question from the game
-	max := len(game.ActiveQuestion.Question.Answers) // Get the maximum question index
-	if id >= max {                                   // If the provided answer is greater
-		id = max - 1 // Set the answer to the last answer
+// ErrNoQuestion The error returned when a player answers while there is no
+// question being asked
+var ErrNoQuestion = errors.New("there is no question to answer")
+
+// ErrIncompleteOrder The error returned when a player answers an order question
+// without placing every answer exactly once
+var ErrIncompleteOrder = errors.New("put every answer in order")
+
+// ErrAlreadyAnswered The error returned when a player answers a question they
+// have already answered and repeated answers aren't kept
+var ErrAlreadyAnswered = errors.New("you have already answered the question")
+
+// The ways a repeated answer to the same question can be handled. Clients may
+// resend their answer when the connection is slow to acknowledge it
+const (
+	DuplicateError = "error" // Repeated answers are rejected with ErrAlreadyAnswered
+	DuplicateKeep  = "keep"  // Repeated answers are ignored and the first answer is acknowledged again
+)
+
+// DuplicateAnswers How repeated answers to the same question are handled. The
+// first answer and its time are always the ones that are kept
+var DuplicateAnswers = EnvOrDefault("QUIZLER_DUPLICATE_ANSWERS", DuplicateError)
+
+// IsDuplicateMode checks whether the provided value is one of the ways repeated
+// answers can be handled
+func IsDuplicateMode(value string) bool {
+	return value == DuplicateError || value == DuplicateKeep
+}
+
+// Answer sets the player answers to the provided answer indexes for the current
+// question. Questions that only allow one answer use the first index and any
+// out of range or repeated indexes are ignored for other questions. Returns an
+// error without answering if too few or too many answers were chosen or if there
+// is no question being asked. Answering the same question again never replaces
+// the first answer (see DuplicateAnswers)
+func (player *Player) Answer(game *Game, ids []AnswerIndex) error {
+	q := game.ActiveQuestion           // Retrieve the active question from the game
+	if q == nil || q.Question == nil { // If the game hasn't started or has finished
+		return ErrNoQuestion
 	}
-	// Set the index of the answer in the player answers map
-	player.Answers[q.Index] = id
+	if q.Question.Kind == KindBuzzer { // Buzzer questions are answered by buzzing
+		return ErrBuzzerQuestion
+	}
+	if q.Question.Kind == KindWords { // Word cloud questions are answered with words
+		return ErrWordsQuestion
+	}
+	if q.Question.Kind == KindNumber { // Number questions are answered with a number
+		return ErrNumberQuestion
+	}
+	if q.Question.Kind == KindBlanks { // Fill in the blanks questions are answered with words
+		return ErrBlanksQuestion
+	}
+	if player.HasAnswered(game) { // Keep the first answer when the player answers again
+		return player.repeatAnswer(game, q)
+	}
+	max := len(q.Question.Answers) // Get the maximum question index
+	var answers []AnswerIndex
+	if q.Question.Kind == KindOrder { // Order questions are answered with every answer in order
+		if !IsOrder(ids, max) {
+			return ErrIncompleteOrder
+		}
+		answers = append([]AnswerIndex(nil), ids...)
+	} else if !q.Question.IsMultiple() { // If only one answer can be chosen
+		id := 0
+		if len(ids) > 0 {
+			id = ids[0]
+		}
+		if id >= max { // If the provided answer is greater
+			id = max - 1 // Set the answer to the last answer
+		}
+		answers = []AnswerIndex{id}
+	} else {
+		chosen := map[AnswerIndex]bool{}
+		for _, id := range ids {
+			if id >= 0 && id < max && !chosen[id] { // Only keep each valid answer once
+				chosen[id] = true
+				answers = append(answers, id)
+			}
+		}
+	}
+	if min, max := q.Question.ChoiceLimits(); len(answers) < min || len(answers) > max { // If too few or too many answers were chosen
+		return &ChoicesError{Min: min, Max: max}
+	}
+	player.AnswerTime = Time() // Set the time of answer
+	// Set the indexes of the answers in the player answers map
+	player.Answers[q.Index] = answers
+	if q.Question.FirstBonus > 0 && q.Credit(answers) == 1 { // Only fully correct answers can earn the bonus
+		q.ClaimFirstCorrect(player.Id)
+	}
+	game.Timeline.RecordQuestion(EventAnswer, q.Index, player.Id)
+	player.recordAnswer(game, q, RawAnswer{Answers: answers})
+	player.acceptAnswer(game, q)
+	return nil
+}
+
+// acceptAnswer tells the player their answer to the question was recorded along
+// with the time it took them to answer according to the server
+func (player *Player) acceptAnswer(game *Game, q *ActiveQuestion) {
+	game.Fanout.Push(net.AnswerAcceptedPacket(q.Index, player.AnswerTime-q.StartTime), player.Net)
+}
+
+// repeatAnswer handles the player answering a question they have already answered.
+// The first answer is acknowledged again when repeated answers are kept otherwise
+// ErrAlreadyAnswered is returned
+func (player *Player) repeatAnswer(game *Game, q *ActiveQuestion) error {
+	if DuplicateAnswers != DuplicateKeep {
+		return ErrAlreadyAnswered
+	}
+	player.acceptAnswer(game, q)
+	return nil
+}
+
+// ChoicesError An error describing the number of answers that a player must
+// choose when they chose too few or too many
+type ChoicesError struct {
+	Min int `json:"min"` // The fewest answers that can be chosen
+	Max int `json:"max"` // The most answers that can be chosen
+}
+
+// Error describes how many answers must be chosen
+func (err *ChoicesError) Error() string {
+	if err.Min == err.Max {
+		return fmt.Sprintf("Choose %d answers", err.Min)
+	}
+	return fmt.Sprintf("Choose between %d and %d answers", err.Min, err.Max)
 }
 
 // CreatePlayerId Creates a new unique player identifier. Safely establishes read
@@ -76,25 +210,18 @@ func (store *PlayerStore) CreatePlayerId() Identifier {
 	}
 }
 
-// Create a new player and add it to the PlayerStore. Sends the player
-// data of all other players in the game to that player and adds them to
-// player map. Returns a pointer to the created player
-func (store *PlayerStore) Create(conn *gowsps.Connection, name string) *Player {
+// Create a new player and add it to the PlayerStore player map. Returns
+// a pointer to the created player
+func (store *PlayerStore) Create(conn *gowsps.Connection, name string, device Device) *Player {
 	id := store.CreatePlayerId() // Create a unique player ID
 	player := Player{
-		Net:     conn,                            // Set the net connection
-		Id:      id,                              // Set the unique id
-		Name:    name,                            // Set the name
-		Score:   0,                               // Initial score of zero
-		Answers: map[QuestionIndex]AnswerIndex{}, // Empty answers map
+		Net:     conn,                              // Set the net connection
+		Id:      id,                                // Set the unique id
+		Name:    name,                              // Set the name
+		Score:   0,                                 // Initial score of zero
+		Answers: map[QuestionIndex][]AnswerIndex{}, // Empty answers map
+		Device:  device,                            // Set the device
 	}
-
-	// Iterate over all the players in the game
-	store.ForEach(func(otherId Identifier, other *Player) {
-		// Send the player the data for each other player in the game
-		player.Net.Send(net.PlayerDataPacket(otherId, other.Name, net.AddMode))
-	})
-
 	store.Lock.Lock()       // Establish write lock over the players map
 	store.Map[id] = &player // Set the identifier to the player pointer in the player map
 	store.Lock.Unlock()     // Release write lock
@@ -177,12 +304,23 @@ func (store *PlayerStore) Get(id Identifier) *Player {
 	}
 }
 
+// Count returns the number of players in the store. Concurrency safe
+// because locks are established
+func (store *PlayerStore) Count() int {
+	store.Lock.RLock()         // Establish a read lock on the players map
+	defer store.Lock.RUnlock() // Defer the releasing of the read lock
+	return len(store.Map)
+}
+
 // Remove Safely removes the player with the provided Identifier from the players
-// map. This is concurrency safe because it uses locks
-func (store *PlayerStore) Remove(id Identifier) {
+// map. Returns whether the player was in the map. This is concurrency safe
+// because it uses locks
+func (store *PlayerStore) Remove(id Identifier) bool {
 	store.Lock.Lock() // Establish write lock on the players map
+	_, exists := store.Map[id]
 	delete(store.Map, id)
 	store.Lock.Unlock() // Release write lock
+	return exists
 }
 
 // RemoveEach Runs the provided action on each player in the map
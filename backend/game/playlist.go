@@ -0,0 +1,68 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+)
+
+// MaxPlaylist The most quizzes that can be played one after another in a single game
+const MaxPlaylist = 5
+
+// Round A single quiz of a playlist. The questions of every quiz are played as
+// one list of questions so players stay in the game between quizzes
+type Round struct {
+	Title string        // The title of the quiz
+	First QuestionIndex // The index of the first question of the quiz in the game questions
+}
+
+// RoundAt finds the position in the playlist of the quiz containing the question
+// at the provided index
+func (game *Game) RoundAt(index QuestionIndex) int {
+	for i := len(game.Rounds) - 1; i > 0; i-- {
+		if index >= game.Rounds[i].First {
+			return i
+		}
+	}
+	return 0
+}
+
+// RoundTitles returns the titles of the quizzes in the playlist or nil when the
+// game is a single quiz
+func (game *Game) RoundTitles() []string {
+	if len(game.Rounds) < 2 {
+		return nil
+	}
+	titles := make([]string, len(game.Rounds))
+	for i, round := range game.Rounds {
+		titles[i] = round.Title
+	}
+	return titles
+}
+
+// endRound sends the points each player earned in the quiz of the playlist that
+// has just finished along with the title of the next quiz. Unless everyone is
+// shown the leaderboard the players are only sent the titles
+func (game *Game) endRound(round int) {
+	scores := game.Players.CollectScores()
+	earned := map[string]uint32{}
+	for id, score := range scores {
+		if start := game.roundScores[id]; score > start {
+			earned[id] = score - start
+		} else {
+			earned[id] = 0
+		}
+	}
+	game.roundScores = scores
+	data := net.RoundData{Round: round, Title: game.Rounds[round].Title, Scores: earned}
+	if round+1 < len(game.Rounds) {
+		data.Next = game.Rounds[round+1].Title
+	}
+	game.Timeline.RecordRound(data)
+	if game.Settings().Reveal == RevealLeaderboard {
+		game.Broadcast(net.RoundOverPacket(data), true)
+		return
+	}
+	game.Fanout.Push(net.RoundOverPacket(data), game.HostTargets()...)
+	data.Scores = nil // Players only see every score once the game is over
+	game.Broadcast(net.RoundOverPacket(data), false)
+}
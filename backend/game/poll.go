@@ -0,0 +1,78 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"errors"
+	"strings"
+)
+
+// Errors returned when answering a word cloud question isn't possible
+var (
+	ErrWordsQuestion = errors.New("word cloud questions are answered with words")
+	ErrNotWords      = errors.New("the question isn't a word cloud question")
+)
+
+// NormalizeWords trims the words a player wrote, collapses any repeated spaces
+// and lowercases them so the same response is counted together
+func NormalizeWords(text string) string {
+	return strings.ToLower(strings.Join(strings.Fields(text), " "))
+}
+
+// AnswerWords sets the words the player wrote as their response to the current
+// word cloud question. Returns a TextError without answering if the words aren't
+// allowed (see CleanText)
+func (player *Player) AnswerWords(game *Game, text string) error {
+	q := game.ActiveQuestion           // Retrieve the active question from the game
+	if q == nil || q.Question == nil { // If the game hasn't started or has finished
+		return ErrNoQuestion
+	}
+	if q.Question.Kind != KindWords {
+		return ErrNotWords
+	}
+	if player.HasAnswered(game) { // Keep the first answer when the player answers again
+		return player.repeatAnswer(game, q)
+	}
+	text, err := CleanText(text)
+	if err != nil {
+		return err
+	}
+	words := NormalizeWords(text)
+	player.AnswerTime = Time() // Set the time of answer
+	player.Answers[q.Index] = []AnswerIndex{}
+	q.wordsLock.Lock() // Establish lock on the words
+	if q.words == nil {
+		q.words = map[string]int{}
+	}
+	q.words[words]++
+	q.wordsLock.Unlock() // Release lock
+	game.Timeline.RecordQuestion(EventAnswer, q.Index, player.Id)
+	player.recordAnswer(game, q, RawAnswer{Text: text})
+	player.acceptAnswer(game, q)
+	return nil
+}
+
+// PollResults collects the responses to the provided poll or word cloud question.
+// Polls count how many of the players still in the game chose each answer
+func (game *Game) PollResults(question *ActiveQuestion) net.PollResultsData {
+	var data net.PollResultsData
+	if question.Question.Kind == KindWords {
+		question.wordsLock.Lock()         // Establish lock on the words
+		defer question.wordsLock.Unlock() // Defer the releasing of the lock
+		data.Words = map[string]int{}
+		for words, count := range question.words {
+			data.Words[words] = count
+		}
+		return data
+	}
+	data.Counts = make([]int, len(question.Question.Answers))
+	game.Players.ForEach(func(id Identifier, player *Player) {
+		answers, _ := player.GetAnswer(question.Index)
+		for _, answer := range answers {
+			if answer >= 0 && answer < len(data.Counts) {
+				data.Counts[answer]++
+			}
+		}
+	})
+	return data
+}
@@ -0,0 +1,117 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/http"
+	"os"
+	"strings"
+	"sync"
+)
+
+// Profile A named set of settings that pre-fills the settings of a new game so
+// hosts can pick one profile rather than choosing every setting themselves
+type Profile struct {
+	net.GameSettings        // The settings games using the profile start with
+	Name             string `json:"name"`        // The name hosts choose the profile by
+	Description      string `json:"description"` // A short description of what the profile is for
+}
+
+// ProfilesFile The path of a JSON file containing a list of profiles that replace
+// the DefaultProfiles. The DefaultProfiles are used if no file is configured
+var ProfilesFile = EnvOrDefault("QUIZLER_PROFILES", "")
+
+// DefaultProfiles The profiles available when no ProfilesFile is configured
+var DefaultProfiles = []Profile{
+	{
+		Name:         "Classroom",
+		Description:  "Warns about shared devices and only shows players their own score",
+		GameSettings: net.GameSettings{Devices: DevicesWarn, Reveal: RevealAnswers},
+	},
+	{
+		Name:         "Party",
+		Description:  "Timed questions with the leaderboard after every question",
+		GameSettings: net.GameSettings{Devices: DevicesAllowed, Reveal: RevealLeaderboard},
+	},
+	{
+		Name:         "Exam",
+		Description:  "One player per device, no timer and no scores until the end",
+		GameSettings: net.GameSettings{Devices: DevicesBlock, Reveal: RevealResult, Scoring: ScoreExam},
+	},
+}
+
+var (
+	profilesLock = sync.RWMutex{}  // A lock for replacing the profiles
+	profiles     = DefaultProfiles // The profiles hosts can choose from
+)
+
+// LoadProfiles replaces the profiles with the profiles in the provided JSON file.
+// Every profile must have a unique name and valid settings otherwise none of the
+// profiles are replaced
+func LoadProfiles(path string) error {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return err
+	}
+	var loaded []Profile
+	if err := json.Unmarshal(data, &loaded); err != nil {
+		return err
+	}
+	if len(loaded) == 0 {
+		return errors.New("no profiles were defined")
+	}
+	names := map[string]bool{}
+	for i := range loaded { // Iterate over the profiles
+		profile := &loaded[i]
+		profile.Name = strings.TrimSpace(profile.Name)
+		if profile.Name == "" {
+			return fmt.Errorf("profile %d doesn't have a name", i+1)
+		}
+		key := strings.ToLower(profile.Name)
+		if names[key] {
+			return fmt.Errorf("there is more than one profile named '%s'", profile.Name)
+		}
+		names[key] = true
+		if err := ValidateSettings(profile.GameSettings); err != nil {
+			return fmt.Errorf("profile '%s' has invalid settings: %w", profile.Name, err)
+		}
+	}
+	profilesLock.Lock() // Establish lock on the profiles
+	profiles = loaded
+	profilesLock.Unlock() // Release lock
+	return nil
+}
+
+// Profiles returns a copy of the profiles hosts can choose from
+func Profiles() []Profile {
+	profilesLock.RLock()         // Establish a read lock on the profiles
+	defer profilesLock.RUnlock() // Defer the releasing of the read lock
+	return append([]Profile(nil), profiles...)
+}
+
+// FindProfile finds the profile with the provided name ignoring case or nil if
+// there isn't one
+func FindProfile(name string) *Profile {
+	name = strings.TrimSpace(name)
+	for _, profile := range Profiles() {
+		if strings.EqualFold(profile.Name, name) {
+			return &profile
+		}
+	}
+	return nil
+}
+
+// HandleProfiles HTTP handler for GET /api/profiles responds with the profiles
+// hosts can choose from when creating a game
+func HandleProfiles(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodGet { // Only accept GET requests
+		WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	WriteJson(writer, http.StatusOK, struct {
+		Profiles []Profile `json:"profiles"` // The profiles in the order they should be listed
+	}{Profiles: Profiles()})
+}
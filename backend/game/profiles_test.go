@@ -0,0 +1,39 @@
+package game
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+// TestLoadProfiles checks that profiles are loaded from a file and that a file
+// with any invalid profile keeps the existing profiles
+func TestLoadProfiles(t *testing.T) {
+	defer func(previous []Profile) { profiles = previous }(profiles)
+	path := filepath.Join(t.TempDir(), "profiles.json")
+	tests := []struct {
+		contents string
+		valid    bool
+	}{
+		{`[{"name":"Quiet","reveal":2,"maxPlayers":30}]`, true},
+		{`[]`, false},
+		{`[{"name":" "}]`, false},
+		{`[{"name":"A"},{"name":"a"}]`, false},
+		{`[{"name":"A","scoring":9}]`, false},
+	}
+	for _, test := range tests {
+		if err := os.WriteFile(path, []byte(test.contents), 0600); err != nil {
+			t.Fatal(err)
+		}
+		if err := LoadProfiles(path); (err == nil) != test.valid {
+			t.Errorf("%s: got error %v want valid %t", test.contents, err, test.valid)
+		}
+	}
+	profile := FindProfile("quiet")
+	if profile == nil || profile.Reveal != RevealResult || profile.MaxPlayers != 30 || len(Profiles()) != 1 {
+		t.Errorf("expected only the loaded profile got %+v", Profiles())
+	}
+	if FindProfile("Classroom") != nil {
+		t.Error("expected the default profiles to be replaced")
+	}
+}
@@ -0,0 +1,47 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"sort"
+)
+
+// AddPoints adds the points earned for the question at the provided index to
+// the player score keeping them for the final results
+func (player *Player) AddPoints(index QuestionIndex, points uint32) {
+	if player.Points == nil {
+		player.Points = map[QuestionIndex]uint32{}
+	}
+	player.Points[index] += points
+	player.Score = AddScore(player.Score, points)
+}
+
+// Results ranks the players still in the game by their final score. Players
+// with the same score share the same rank and are ordered by name. The results
+// are computed by the server so clients don't need to work out the winners
+func (game *Game) Results() []net.PlayerResult {
+	var results []net.PlayerResult
+	game.Players.ForEach(func(id Identifier, player *Player) {
+		points := make([]uint32, len(game.Questions))
+		for index, earned := range player.Points {
+			if index >= 0 && index < len(points) {
+				points[index] = earned
+			}
+		}
+		results = append(results, net.PlayerResult{Id: id, Name: player.Name, Score: player.Score, Points: points})
+	})
+	sort.Slice(results, func(i, j int) bool {
+		if results[i].Score != results[j].Score {
+			return results[i].Score > results[j].Score
+		}
+		return results[i].Name < results[j].Name
+	})
+	for i := range results {
+		if i > 0 && results[i].Score == results[i-1].Score { // Players with the same score share a rank
+			results[i].Rank = results[i-1].Rank
+		} else {
+			results[i].Rank = i + 1
+		}
+	}
+	return results
+}
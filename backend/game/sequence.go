@@ -0,0 +1,61 @@
+package game
+
+import (
+	"backend/net"
+	. "github.com/jacobtread/gowsps"
+	"sync"
+)
+
+// SequenceHistory The number of the most recent numbered events kept so clients
+// that missed some can be sent them again
+const SequenceHistory = 128
+
+// sequence A structure representing the numbered events sent to everyone in the
+// game. The numbers only ever increase by one so clients can tell when they missed
+// an event or received one out of order
+type sequence struct {
+	last    uint64     // The number of the last event sent
+	history []Packet   // The most recent events already wrapped with their numbers oldest first
+	lock    sync.Mutex // A lock held while numbering and queueing events so they are sent in order
+}
+
+// Sequence returns the number of the last event sent to everyone in the game
+func (game *Game) Sequence() uint64 {
+	game.sequence.lock.Lock()         // Establish lock on the sequence
+	defer game.sequence.lock.Unlock() // Defer the releasing of the lock
+	return game.sequence.last
+}
+
+// broadcastSequenced numbers the packet and sends it to everyone in the game. The
+// lock is held while queueing so the fanout sends the events in number order
+func (game *Game) broadcastSequenced(packet Packet) {
+	seq := &game.sequence
+	seq.lock.Lock()         // Establish lock on the sequence
+	defer seq.lock.Unlock() // Defer the releasing of the lock
+	seq.last++
+	numbered := net.SequencedPacket(seq.last, packet)
+	seq.history = append(seq.history, numbered)
+	if overflow := len(seq.history) - SequenceHistory; overflow > 0 { // Forget the oldest events
+		seq.history = append(seq.history[:0:0], seq.history[overflow:]...)
+	}
+	game.BroadcastExcluding("", numbered, true)
+}
+
+// Resync sends the connection every event after the provided number. When those
+// events are no longer kept or there are more than the outbox of the connection
+// holds the connection is sent the full state of the game instead which includes
+// the number to continue from. The player is nil for the host and displays
+func (game *Game) Resync(conn *Connection, player *Player, from uint64) {
+	seq := &game.sequence
+	seq.lock.Lock() // Establish lock on the sequence
+	missed := seq.last - from
+	if from <= seq.last && missed <= uint64(len(seq.history)) && missed <= ConnectionBuffer { // Every missed event is still kept and fits in the outbox
+		for _, packet := range seq.history[uint64(len(seq.history))-missed:] {
+			game.Fanout.Push(packet, conn)
+		}
+		seq.lock.Unlock() // Release lock
+		return
+	}
+	seq.lock.Unlock() // Release lock before collecting the state which reads the number again
+	game.Fanout.Push(net.StateSyncPacket(game.StateSync(player)), conn)
+}
@@ -0,0 +1,61 @@
+package game
+
+import (
+	"backend/net"
+	"errors"
+	"fmt"
+)
+
+// ValidateSettings checks that every setting is one the server knows about and
+// that the max players is within the server limit
+func ValidateSettings(settings net.GameSettings) error {
+	if settings.Devices > DevicesBlock { // If the device mode doesn't exist
+		return errors.New("unknown device mode")
+	}
+	if settings.Reveal > RevealResult { // If the reveal mode doesn't exist
+		return errors.New("unknown answer reveal mode")
+	}
+	if settings.Scoring > ScoreExam { // If the scoring mode doesn't exist
+		return errors.New("unknown scoring mode")
+	}
+	if settings.MaxPlayers < 0 || (MaxPlayers > 0 && settings.MaxPlayers > MaxPlayers) {
+		return fmt.Errorf("max players must be between 0 and %d", MaxPlayers)
+	}
+	return nil
+}
+
+// Settings returns a copy of the current settings of the game
+func (game *Game) Settings() net.GameSettings {
+	game.settingsLock.RLock()         // Establish a read lock on the settings
+	defer game.settingsLock.RUnlock() // Defer the releasing of the read lock
+	return game.settings
+}
+
+// UpdateSettings replaces the settings of the game and tells everyone in the
+// game the new settings. Settings can only be changed while the game is waiting
+// for players and the max players can't be less than the players already joined
+func (game *Game) UpdateSettings(settings net.GameSettings) error {
+	if game.State != Waiting {
+		return errors.New("settings can only be changed before the game starts")
+	}
+	if err := ValidateSettings(settings); err != nil {
+		return err
+	}
+	if count := game.Players.Count(); settings.MaxPlayers > 0 && settings.MaxPlayers < count {
+		return fmt.Errorf("max players can't be less than the %d players already in the game", count)
+	}
+	game.settingsLock.Lock() // Establish lock on the settings
+	game.settings = settings
+	game.settingsLock.Unlock() // Release lock
+	game.checkCapacity()
+	game.Broadcast(net.SettingsPacket(settings), true)
+	return nil
+}
+
+// SetMaxPlayers changes the most players that can join the game keeping the
+// rest of the settings. 0 uses the server limit
+func (game *Game) SetMaxPlayers(value int) error {
+	settings := game.Settings()
+	settings.MaxPlayers = value
+	return game.UpdateSettings(settings)
+}
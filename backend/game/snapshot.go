@@ -0,0 +1,27 @@
+package game
+
+import (
+	"backend/net"
+	"sort"
+)
+
+// Snapshot builds a compact summary of the game for a client recovering from an
+// error without reconnecting. Players are given their own score while the host
+// and displays (nil player) are not
+func (game *Game) Snapshot(player *Player) net.SnapshotData {
+	data := net.SnapshotData{State: game.State, Players: []net.PlayerSummary{}, Seq: game.Sequence()}
+	data.Total, data.Remaining = game.countdown()
+	if q := game.ActiveQuestion; q != nil {
+		index := q.Index
+		data.Question = &index
+	}
+	if player != nil {
+		score := player.Score
+		data.Score = &score
+	}
+	for _, other := range game.Players.GetPlayerArray() {
+		data.Players = append(data.Players, net.PlayerSummary{Id: other.Id, Name: other.Name})
+	}
+	sort.Slice(data.Players, func(i, j int) bool { return data.Players[i].Name < data.Players[j].Name })
+	return data
+}
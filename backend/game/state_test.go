@@ -0,0 +1,117 @@
+package game
+
+import (
+	. "backend/tools"
+	"fmt"
+	"testing"
+	"testing/quick"
+)
+
+// newTestGame creates a game that discards all of its packets so that it can
+// be driven without any connections
+func newTestGame(questions []QuestionData) *Game {
+	return &Game{
+		Id:        "TEST",
+		Title:     "Test",
+		Questions: questions,
+		Players:   NewPlayerStore(),
+		State:     Waiting,
+		Fanout:    &Fanout{Queue: make(chan Delivery), Closed: true},
+		Timeline:  &Timeline{},
+	}
+}
+
+// testQuestions creates questions with a varying number of answers
+func testQuestions() []QuestionData {
+	return []QuestionData{
+		{Question: "One", Answers: []string{"A", "B"}, Values: []AnswerIndex{0}},
+		{Question: "Two", Answers: []string{"A", "B", "C", "D"}, Values: []AnswerIndex{1, 3}},
+		{Question: "Three", Answers: []string{"A", "B", "C"}, Values: []AnswerIndex{2}},
+		{Question: "Four", Answers: []string{"A", "B", "C", "D"}, Values: []AnswerIndex{0, 1}, Marking: MarkExact},
+		{Question: "Five", Answers: []string{"A", "B", "C", "D"}, Values: []AnswerIndex{0, 1, 2}, Marking: MarkPartial},
+		{Question: "Six", Answers: []string{"A", "B", "C", "D"}, Values: []AnswerIndex{1, 3}, Marking: MarkPenalty},
+	}
+}
+
+// applyEvent applies a single random event to the game following the same
+// checks as the packet handlers. The event kind is taken from the low bits
+// and the argument from the rest
+func applyEvent(game *Game, event uint16, joined *int) {
+	arg := int(event >> 3)
+	players := game.Players.GetPlayerArray()
+	var player *Player
+	if len(players) > 0 {
+		player = players[arg%len(players)]
+	}
+	switch event % 8 {
+	case 0: // Join
+		if game.State == Waiting {
+			*joined++
+			id := fmt.Sprintf("P%d", *joined)
+			game.Players.Map[id] = &Player{Id: id, Name: id, Answers: map[QuestionIndex][]AnswerIndex{}}
+		}
+	case 1: // Answer including out of range indexes and answering while marked or without a question
+		if player != nil && !player.HasAnswered(game) {
+			_ = player.Answer(game, []AnswerIndex{arg%12 - 2, arg%5 - 1})
+		}
+	case 2: // Mark
+		if game.ActiveQuestion != nil && !game.ActiveQuestion.Marked {
+			game.MarkQuestion(game.ActiveQuestion)
+		}
+	case 3: // Next question
+		if game.State == Started {
+			game.NextQuestion()
+		}
+	case 4: // Skip
+		if game.State == Started {
+			game.SkipQuestion()
+		}
+	case 5: // Kick at any point in the game
+		if player != nil {
+			game.KickPlayer(player, "")
+		}
+	case 6: // Start
+		if game.State == Waiting {
+			game.Start()
+			game.SetState(Started)
+		}
+	case 7: // A timer moving on after the game started such as one racing the game ending
+		if game.State != Waiting {
+			game.NextQuestion()
+		}
+	}
+}
+
+// TestGameEvents applies random sequences of events to a game checking that
+// it never panics, scores never decrease and the active question is always
+// one of the game questions
+func TestGameEvents(t *testing.T) {
+	property := func(events []uint16) bool {
+		game := newTestGame(testQuestions())
+		scores := map[Identifier]uint32{}
+		joined := 0
+		for _, event := range events {
+			previous := game.State
+			applyEvent(game, event, &joined)
+			if previous == Stopped && game.State != Stopped { // Stopped games can't be restarted
+				t.Logf("game moved from stopped to %d", game.State)
+				return false
+			}
+			if q := game.ActiveQuestion; q != nil && (q.Index < 0 || q.Index >= len(game.Questions)) {
+				t.Logf("active question index %d out of range", q.Index)
+				return false
+			}
+			for _, player := range game.Players.GetPlayerArray() {
+				if player.Score < scores[player.Id] {
+					t.Logf("score for %s decreased from %d to %d", player.Id, scores[player.Id], player.Score)
+					return false
+				}
+				scores[player.Id] = player.Score
+			}
+		}
+		return true
+	}
+	if err := quick.Check(property, &quick.Config{MaxCount: 1000}); err != nil {
+		t.Error(err)
+	}
+}
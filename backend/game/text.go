@@ -0,0 +1,64 @@
+package game
+
+import (
+	. "backend/tools"
+	"fmt"
+	"strings"
+	"unicode"
+	"unicode/utf8"
+)
+
+// The reasons a typed answer isn't allowed
+const (
+	TextEmpty   = "empty"    // Nothing but spaces was written
+	TextTooLong = "too_long" // More than MaxWordLength characters were written
+	TextControl = "control"  // The text contains control characters or invalid UTF-8
+)
+
+// TextError An error describing why the text a player typed as their answer
+// isn't allowed
+type TextError struct {
+	Reason string `json:"reason"`          // Why the text isn't allowed (TextEmpty, TextTooLong or TextControl)
+	Max    int    `json:"max"`             // The most characters that can be written
+	Blank  *int   `json:"blank,omitempty"` // The index of the blank the text was written for
+}
+
+// Error describes why the text isn't allowed
+func (err *TextError) Error() string {
+	var message string
+	switch err.Reason {
+	case TextEmpty:
+		message = "Answers can't be empty"
+	case TextTooLong:
+		message = fmt.Sprintf("Answers must be at most %d characters", err.Max)
+	default:
+		message = "Answers can't contain control characters"
+	}
+	if err.Blank != nil {
+		return fmt.Sprintf("%s (blank %d)", message, *err.Blank+1)
+	}
+	return message
+}
+
+// CleanText trims the text a player typed as their answer and collapses any
+// repeated spaces keeping its case. Returns a TextError if the text is empty,
+// longer than MaxWordLength or contains control characters. Overly long text is
+// rejected before it is checked any further
+func CleanText(text string) (string, error) {
+	if len(text) > MaxWordLength*utf8.UTFMax+256 { // Far too long even with spaces around it
+		return "", &TextError{Reason: TextTooLong, Max: MaxWordLength}
+	}
+	if !utf8.ValidString(text) || strings.IndexFunc(text, func(r rune) bool {
+		return unicode.IsControl(r) && !unicode.IsSpace(r)
+	}) >= 0 {
+		return "", &TextError{Reason: TextControl, Max: MaxWordLength}
+	}
+	text = strings.Join(strings.Fields(text), " ")
+	if text == "" {
+		return "", &TextError{Reason: TextEmpty, Max: MaxWordLength}
+	}
+	if utf8.RuneCountInString(text) > MaxWordLength {
+		return "", &TextError{Reason: TextTooLong, Max: MaxWordLength}
+	}
+	return text, nil
+}
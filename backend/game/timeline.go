@@ -0,0 +1,162 @@
+package game
+
+import (
+	"backend/media"
+	"backend/net"
+	. "backend/tools"
+	"sync"
+	"time"
+)
+
+// Types of events recorded on a game timeline
+const (
+	EventState    = "state"    // The game moved into a new state
+	EventJoin     = "join"     // A player joined the game
+	EventLeave    = "leave"    // A player left the game
+	EventKick     = "kick"     // The host removed a player from the game
+	EventQuestion = "question" // A question was sent to the players
+	EventAnswer   = "answer"   // A player submitted an answer
+	EventSkip     = "skip"     // The host skipped the question
+	EventMark     = "mark"     // The question was marked
+	EventBuzz     = "buzz"     // A player buzzed to answer a buzzer question
+	EventJudge    = "judge"    // The host judged the answer of a player that buzzed
+)
+
+// MaxTimelineEvents The maximum number of events recorded for a single game. Later
+// events are dropped so a game can't use unbounded memory
+const MaxTimelineEvents = 10_000
+
+// TimelineCapacity The number of finished games whose timelines are kept so they
+// can be retrieved after the game is over
+var TimelineCapacity = EnvIntOrDefault("QUIZLER_TIMELINE_SIZE", 100)
+
+type (
+	// Event A single timestamped event that happened during a game
+	Event struct {
+		Time     time.Time      `json:"time"`               // The time the event happened
+		Type     string         `json:"type"`               // The type of event (e.g. state, join, answer)
+		State    *State         `json:"state,omitempty"`    // The new state for state events
+		Player   Identifier     `json:"player,omitempty"`   // The player the event is about
+		Name     string         `json:"name,omitempty"`     // The name of the player for join events
+		Question *QuestionIndex `json:"question,omitempty"` // The question the event is about
+	}
+
+	// Timeline The events that happened during a game from oldest to newest
+	Timeline struct {
+		Lock      sync.Mutex          // A lock for modifying the events
+		Events    []Event             // The recorded events
+		Questions []net.QuestionStats // The stats of each marked question
+		Rounds    []net.RoundData     // The scores of each finished quiz of a playlist
+	}
+
+	// finishedGame The parts of a finished game kept for retrieving its timeline
+	finishedGame struct {
+		Id       Identifier // The id the game had
+		Key      string     // The host key of the game
+		Timeline *Timeline  // The timeline of the game
+		Time     time.Time  // When the game finished
+	}
+)
+
+var (
+	finishedLock = sync.Mutex{} // A lock for modifying the finished games
+	finished     []finishedGame // The most recently finished games from oldest to newest
+)
+
+// Record adds the provided event to the timeline setting its time to now
+func (timeline *Timeline) Record(event Event) {
+	event.Time = time.Now()
+	timeline.Lock.Lock() // Establish lock on the events
+	if len(timeline.Events) < MaxTimelineEvents {
+		timeline.Events = append(timeline.Events, event)
+	}
+	timeline.Lock.Unlock() // Release lock
+}
+
+// Copy creates a copy of the recorded events that is safe to use while more
+// events are recorded
+func (timeline *Timeline) Copy() []Event {
+	timeline.Lock.Lock()         // Establish lock on the events
+	defer timeline.Lock.Unlock() // Defer the releasing of the lock
+	return append([]Event{}, timeline.Events...)
+}
+
+// RecordStats records the stats of a question once it has been marked
+func (timeline *Timeline) RecordStats(stats net.QuestionStats) {
+	timeline.Lock.Lock() // Establish lock on the events
+	timeline.Questions = append(timeline.Questions, stats)
+	timeline.Lock.Unlock() // Release lock
+}
+
+// RecordRound records the scores of a quiz of a playlist once it has finished
+func (timeline *Timeline) RecordRound(round net.RoundData) {
+	timeline.Lock.Lock() // Establish lock on the events
+	timeline.Rounds = append(timeline.Rounds, round)
+	timeline.Lock.Unlock() // Release lock
+}
+
+// Summary creates the summary of the game from the recorded question stats that
+// is safe to use while more stats are recorded
+func (timeline *Timeline) Summary() net.SummaryData {
+	timeline.Lock.Lock()         // Establish lock on the events
+	defer timeline.Lock.Unlock() // Defer the releasing of the lock
+	return net.SummaryData{
+		Questions: append([]net.QuestionStats{}, timeline.Questions...),
+		Rounds:    append([]net.RoundData(nil), timeline.Rounds...),
+	}
+}
+
+// RecordState records the game moving into the provided state
+func (timeline *Timeline) RecordState(state State) {
+	timeline.Record(Event{Type: EventState, State: &state})
+}
+
+// RecordQuestion records an event of the provided type about a question
+// optionally made by a player
+func (timeline *Timeline) RecordQuestion(eventType string, index QuestionIndex, player Identifier) {
+	timeline.Record(Event{Type: eventType, Question: &index, Player: player})
+}
+
+// finish keeps the timeline of the game after it has been removed from Games
+// discarding the oldest finished games past TimelineCapacity, or archiving them
+// when archiving is enabled, and releases the uploaded images used by the game
+func (game *Game) finish() {
+	finishedLock.Lock() // Establish lock on the finished games
+	if game.Finished {  // The game may be stopped after it is already over
+		finishedLock.Unlock()
+		return
+	}
+	game.Finished = true
+	finished = append(finished, finishedGame{Id: game.Id, Key: game.Key, Timeline: game.Timeline, Time: time.Now()})
+	var evicted []finishedGame
+	if overflow := len(finished) - TimelineCapacity; overflow > 0 { // If the capacity was exceeded
+		evicted = append(evicted, finished[:overflow]...)
+		finished = append(finished[:0:0], finished[overflow:]...) // Copy so the old timelines can be freed
+	}
+	finishedLock.Unlock() // Release lock
+	if ArchiveEnabled() && len(evicted) > 0 {
+		archiveAll(evicted)
+	}
+	media.Release(game.Media)
+}
+
+// FindTimeline finds the timeline of the game with the provided id and host key
+// whether it is still running, finished or archived. Returns nil if there is no match
+func FindTimeline(id Identifier, key string) *Timeline {
+	if game := Get(id); game != nil && SecretEquals(game.Key, key) {
+		return game.Timeline
+	}
+	finishedLock.Lock()                       // Establish lock on the finished games
+	for i := len(finished) - 1; i >= 0; i-- { // Newest first as game ids can be reused
+		if finished[i].Id == id && SecretEquals(finished[i].Key, key) {
+			timeline := finished[i].Timeline
+			finishedLock.Unlock()
+			return timeline
+		}
+	}
+	finishedLock.Unlock() // Release lock
+	if ArchiveEnabled() { // Older timelines are only kept in the archive
+		return findArchived(id, key)
+	}
+	return nil
+}
@@ -0,0 +1,100 @@
+package game
+
+import (
+	"backend/net"
+	. "backend/tools"
+	"errors"
+	. "github.com/jacobtread/gowsps"
+	"sync"
+)
+
+// Errors returned when a warm-up poll can't be started or voted in
+var (
+	ErrWarmUpStarted = errors.New("warm-up polls can only be asked before the game starts")
+	ErrNoWarmUp      = errors.New("there is no warm-up poll to vote in")
+)
+
+// WarmUp An unscored poll the host asks the players while waiting for the game
+// to start. Votes are kept apart from the player answers so they never affect
+// the scores or the timeline of the game
+type WarmUp struct {
+	Data  net.WarmUpData             // The question and answers of the poll
+	votes map[Identifier]AnswerIndex // The answer each player voted for
+	lock  sync.Mutex                 // A lock for modifying the votes
+}
+
+// StartWarmUp asks everyone in the game the provided warm-up poll replacing any
+// poll that was already being asked. The poll is checked the same as a poll
+// question of a quiz
+func (game *Game) StartWarmUp(data net.WarmUpData) error {
+	if game.State != Waiting {
+		return ErrWarmUpStarted
+	}
+	question := QuestionData{Question: data.Question, Answers: data.Answers, Kind: KindPoll}
+	if problems := question.Problems(); len(problems) > 0 {
+		return errors.New(problems[0])
+	}
+	warmUp := &WarmUp{Data: data, votes: map[Identifier]AnswerIndex{}}
+	game.warmUpLock.Lock() // Establish lock on the warm-up
+	game.warmUp = warmUp
+	game.warmUpLock.Unlock() // Release lock
+	game.Broadcast(net.WarmUpPacket(data, nil, false), true)
+	return nil
+}
+
+// EndWarmUp ends the current warm-up poll showing everyone the final votes. Does
+// nothing if there isn't a poll being asked
+func (game *Game) EndWarmUp() {
+	game.warmUpLock.Lock() // Establish lock on the warm-up
+	warmUp := game.warmUp
+	game.warmUp = nil
+	game.warmUpLock.Unlock() // Release lock
+	if warmUp != nil {
+		game.Broadcast(net.WarmUpPacket(warmUp.Data, game.warmUpCounts(warmUp), true), true)
+	}
+}
+
+// SendWarmUp sends the current warm-up poll to a player that joined while it is
+// being asked. Does nothing if there isn't a poll being asked
+func (game *Game) SendWarmUp(conn *Connection) {
+	game.warmUpLock.Lock() // Establish lock on the warm-up
+	warmUp := game.warmUp
+	game.warmUpLock.Unlock() // Release lock
+	if warmUp != nil {
+		game.Fanout.Push(net.WarmUpPacket(warmUp.Data, nil, false), conn)
+	}
+}
+
+// VoteWarmUp sets the answer the player voted for in the current warm-up poll.
+// Players can change their vote until the poll ends. The host and displays are
+// sent the updated votes
+func (player *Player) VoteWarmUp(game *Game, id AnswerIndex) error {
+	game.warmUpLock.Lock() // Establish lock on the warm-up
+	warmUp := game.warmUp
+	game.warmUpLock.Unlock() // Release lock
+	if warmUp == nil {
+		return ErrNoWarmUp
+	}
+	if id < 0 || id >= len(warmUp.Data.Answers) {
+		return errors.New("that answer doesn't exist")
+	}
+	warmUp.lock.Lock() // Establish lock on the votes
+	warmUp.votes[player.Id] = id
+	warmUp.lock.Unlock() // Release lock
+	game.Fanout.Push(net.WarmUpPacket(warmUp.Data, game.warmUpCounts(warmUp), false), game.HostTargets()...)
+	return nil
+}
+
+// warmUpCounts counts how many of the players still in the game voted for each
+// answer of the warm-up poll
+func (game *Game) warmUpCounts(warmUp *WarmUp) []int {
+	counts := make([]int, len(warmUp.Data.Answers))
+	warmUp.lock.Lock()         // Establish lock on the votes
+	defer warmUp.lock.Unlock() // Defer the releasing of the lock
+	game.Players.ForEach(func(id Identifier, player *Player) {
+		if vote, voted := warmUp.votes[id]; voted {
+			counts[vote]++
+		}
+	})
+	return counts
+}
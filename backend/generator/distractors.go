@@ -0,0 +1,112 @@
+package generator
+
+import (
+	"backend/tools"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net/http"
+	"strings"
+)
+
+// MaxAnswerLength The maximum length of the question answer and generated distractors
+const MaxAnswerLength = 200
+
+// The instructions given to the provider describing the format to respond with
+const distractorPrompt = `You write plausible but incorrect answers for multiple choice quiz
+questions. Respond with only JSON in the form {"distractors":["...","..."]}.
+Each distractor must be short, clearly wrong and different from the correct answer.`
+
+// DistractorsRequest A structure representing a request to suggest wrong answers
+// for a question
+type DistractorsRequest struct {
+	Question string `json:"question"` // The contents of the question
+	Answer   string `json:"answer"`   // The correct answer to the question
+	Count    int    `json:"count"`    // The number of distractors to suggest
+}
+
+// Validate checks the request values are within the allowed bounds returning
+// an error describing the first invalid value
+func (request *DistractorsRequest) Validate() error {
+	request.Question = strings.TrimSpace(request.Question)
+	request.Answer = strings.TrimSpace(request.Answer)
+	if len(request.Question) == 0 {
+		return errors.New("question must not be empty")
+	}
+	if len(request.Answer) == 0 || len(request.Answer) > MaxAnswerLength {
+		return fmt.Errorf("answer must be between 1 and %d characters", MaxAnswerLength)
+	}
+	if request.Count < 1 || request.Count >= tools.MaxAnswers {
+		return fmt.Errorf("count must be between 1 and %d", tools.MaxAnswers-1)
+	}
+	return nil
+}
+
+// HandleDistractors HTTP handler for POST /api/generate/distractors. Suggests
+// plausible wrong answers for a question which the author can choose to accept
+func HandleDistractors(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodPost { // Only accept POST requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	if !limiter.Allow(tools.ClientAddress(request)) { // If the client has made too many requests
+		tools.WriteError(writer, http.StatusTooManyRequests, "Too many generation requests. Try again later")
+		return
+	}
+	var body DistractorsRequest
+	if err := tools.ReadJson(writer, request, &body); err != nil { // If the body isn't valid JSON
+		tools.WriteError(writer, http.StatusBadRequest, "Invalid request body")
+		return
+	}
+	if err := body.Validate(); err != nil { // If the request values aren't valid
+		tools.WriteError(writer, http.StatusBadRequest, err.Error())
+		return
+	}
+	distractors, err := GenerateDistractors(request.Context(), body)
+	if err != nil { // If the provider failed to suggest any distractors
+		log.Printf("Failed to generate distractors for '%s': %s", body.Question, err)
+		tools.WriteError(writer, http.StatusBadGateway, "Failed to generate distractors")
+		return
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Distractors []string `json:"distractors"` // The suggested wrong answers
+	}{Distractors: distractors})
+}
+
+// GenerateDistractors asks the provider for wrong answers to the question. Any
+// suggestions that are empty, too long, duplicated or match the correct answer
+// are discarded
+func GenerateDistractors(ctx context.Context, request DistractorsRequest) ([]string, error) {
+	prompt := fmt.Sprintf("Write %d distractors for the question %q where the correct answer is %q",
+		request.Count, request.Question, request.Answer)
+	content, err := Complete(ctx, distractorPrompt, prompt)
+	if err != nil {
+		return nil, err
+	}
+	var result struct {
+		Distractors []string `json:"distractors"`
+	}
+	if err = json.Unmarshal([]byte(content), &result); err != nil {
+		return nil, fmt.Errorf("provider responded with invalid JSON: %w", err)
+	}
+	seen := map[string]bool{strings.ToLower(request.Answer): true} // Case-insensitive set of used answers
+	distractors := make([]string, 0, request.Count)
+	for _, distractor := range result.Distractors { // Iterate over the suggestions
+		distractor = strings.TrimSpace(distractor)
+		key := strings.ToLower(distractor)
+		if len(distractor) == 0 || len(distractor) > MaxAnswerLength || seen[key] {
+			continue
+		}
+		seen[key] = true
+		distractors = append(distractors, distractor)
+		if len(distractors) == request.Count {
+			break
+		}
+	}
+	if len(distractors) == 0 {
+		return nil, errors.New("provider didn't suggest any valid distractors")
+	}
+	return distractors, nil
+}
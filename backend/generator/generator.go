@@ -0,0 +1,187 @@
+package generator
+
+import (
+	"backend/tools"
+	"bytes"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"log"
+	"net/http"
+	"strings"
+	"time"
+)
+
+// Configuration for the OpenAI compatible chat completions provider used to
+// generate questions. Generation is disabled unless QUIZLER_GENERATOR_URL is set
+var (
+	Url   = strings.TrimSuffix(tools.EnvOrDefault("QUIZLER_GENERATOR_URL", ""), "/") // The base url of the provider API
+	Key   = tools.EnvOrDefault("QUIZLER_GENERATOR_KEY", "")                          // The API key sent as a bearer token
+	Model = tools.EnvOrDefault("QUIZLER_GENERATOR_MODEL", "gpt-3.5-turbo")           // The model to request completions from
+)
+
+// Limits for generation requests
+const (
+	RequestTimeout = 30 * time.Second // The maximum time to wait for the provider to respond
+	MaxTopicLength = 100              // The maximum length of a topic
+)
+
+// A client for sending requests to the provider
+var client = &http.Client{Timeout: RequestTimeout}
+
+// Limits the number of generation requests each client address can make per hour
+var limiter = tools.NewRateLimiter(tools.EnvIntOrDefault("QUIZLER_GENERATOR_LIMIT", 10), time.Hour)
+
+// The instructions given to the provider describing the format to respond with
+const systemPrompt = `You write multiple choice quiz questions. Respond with only JSON in the
+form {"questions":[{"question":"...","answers":["...","..."],"values":[0]}]} where
+"values" contains the indexes of the correct answers. Each question must have
+between 2 and 9 short answers.`
+
+// QuestionsRequest A structure representing a request to generate questions
+type QuestionsRequest struct {
+	Topic      string `json:"topic"`      // The topic to generate questions about
+	Difficulty string `json:"difficulty"` // The difficulty of the questions (easy, medium or hard)
+	Count      int    `json:"count"`      // The number of questions to generate
+}
+
+// Enabled returns whether a provider has been configured and the server isn't offline
+func Enabled() bool {
+	return Url != "" && !tools.Offline
+}
+
+// Validate checks the request values are within the allowed bounds returning
+// an error describing the first invalid value
+func (request *QuestionsRequest) Validate() error {
+	request.Topic = strings.TrimSpace(request.Topic)
+	if len(request.Topic) == 0 || len(request.Topic) > MaxTopicLength {
+		return fmt.Errorf("topic must be between 1 and %d characters", MaxTopicLength)
+	}
+	if !tools.IsDifficulty(request.Difficulty) {
+		return errors.New("difficulty must be one of easy, medium or hard")
+	}
+	if request.Count < 1 || request.Count > tools.MaxQuestions {
+		return fmt.Errorf("count must be between 1 and %d", tools.MaxQuestions)
+	}
+	return nil
+}
+
+// HandleQuestions HTTP handler for POST /api/generate/questions. Generates draft
+// questions for the provided topic which are returned for the editor to review
+func HandleQuestions(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodPost { // Only accept POST requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	if !limiter.Allow(tools.ClientAddress(request)) { // If the client has made too many requests
+		tools.WriteError(writer, http.StatusTooManyRequests, "Too many generation requests. Try again later")
+		return
+	}
+	var body QuestionsRequest
+	if err := tools.ReadJson(writer, request, &body); err != nil { // If the body isn't valid JSON
+		tools.WriteError(writer, http.StatusBadRequest, "Invalid request body")
+		return
+	}
+	if err := body.Validate(); err != nil { // If the request values aren't valid
+		tools.WriteError(writer, http.StatusBadRequest, err.Error())
+		return
+	}
+	questions, err := GenerateQuestions(request.Context(), body)
+	if err != nil { // If the provider failed to generate any questions
+		log.Printf("Failed to generate questions about '%s': %s", body.Topic, err)
+		tools.WriteError(writer, http.StatusBadGateway, "Failed to generate questions")
+		return
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Questions []tools.QuestionData `json:"questions"` // The generated questions
+	}{Questions: questions})
+}
+
+// GenerateQuestions asks the provider for questions matching the request. Any
+// questions that are not valid are discarded and an error is returned if there
+// are no valid questions left
+func GenerateQuestions(ctx context.Context, request QuestionsRequest) ([]tools.QuestionData, error) {
+	prompt := fmt.Sprintf("Write %d %s questions about: %s", request.Count, request.Difficulty, request.Topic)
+	content, err := Complete(ctx, systemPrompt, prompt)
+	if err != nil {
+		return nil, err
+	}
+	var result struct {
+		Questions []tools.QuestionData `json:"questions"`
+	}
+	if err = json.Unmarshal([]byte(content), &result); err != nil {
+		return nil, fmt.Errorf("provider responded with invalid JSON: %w", err)
+	}
+	questions := make([]tools.QuestionData, 0, request.Count)
+	for _, question := range result.Questions { // Iterate over the generated questions
+		question.Image = ""                      // Generated questions never have images
+		question.Difficulty = request.Difficulty // Generated questions are marked with the requested difficulty
+		if question.IsValid() && len(questions) < request.Count {
+			questions = append(questions, question)
+		}
+	}
+	if len(questions) == 0 {
+		return nil, errors.New("provider didn't generate any valid questions")
+	}
+	return questions, nil
+}
+
+// Complete sends a chat completion request to the provider with the provided
+// system and user messages and returns the content of the response message
+// with any markdown code fences removed
+func Complete(ctx context.Context, system string, user string) (string, error) {
+	type message struct {
+		Role    string `json:"role"`
+		Content string `json:"content"`
+	}
+	body, err := json.Marshal(struct {
+		Model    string    `json:"model"`
+		Messages []message `json:"messages"`
+	}{Model: Model, Messages: []message{{Role: "system", Content: system}, {Role: "user", Content: user}}})
+	if err != nil {
+		return "", err
+	}
+	request, err := http.NewRequestWithContext(ctx, http.MethodPost, Url+"/chat/completions", bytes.NewReader(body))
+	if err != nil {
+		return "", err
+	}
+	request.Header.Set("Content-Type", "application/json")
+	if Key != "" { // Only send the authorization if a key is configured
+		request.Header.Set("Authorization", "Bearer "+Key)
+	}
+	response, err := client.Do(request)
+	if err != nil {
+		return "", err
+	}
+	defer response.Body.Close()
+	if response.StatusCode != http.StatusOK {
+		return "", fmt.Errorf("provider responded with status %d", response.StatusCode)
+	}
+	var result struct {
+		Choices []struct {
+			Message message `json:"message"`
+		} `json:"choices"`
+	}
+	if err = json.NewDecoder(response.Body).Decode(&result); err != nil {
+		return "", err
+	}
+	if len(result.Choices) == 0 {
+		return "", errors.New("provider responded without any choices")
+	}
+	return stripCodeFence(result.Choices[0].Message.Content), nil
+}
+
+// stripCodeFence removes the markdown code fence that some models wrap their
+// JSON responses in
+func stripCodeFence(content string) string {
+	content = strings.TrimSpace(content)
+	if !strings.HasPrefix(content, "```") {
+		return content
+	}
+	content = strings.TrimPrefix(content, "```")
+	if index := strings.IndexByte(content, '\n'); index != -1 { // Remove the language of the fence
+		content = content[index+1:]
+	}
+	return strings.TrimSpace(strings.TrimSuffix(strings.TrimSpace(content), "```"))
+}
@@ -2,9 +2,9 @@ module backend
 
 go 1.18
 
-require github.com/jacobtread/gowsps v0.0.0-20220307042916-78f2facec237
-
 require (
-	github.com/gorilla/websocket v1.5.0 // indirect
-	github.com/mitchellh/mapstructure v1.4.3 // indirect
+	github.com/gorilla/websocket v1.5.0
+	github.com/jacobtread/gowsps v0.0.0-20220307042916-78f2facec237
 )
+
+require github.com/mitchellh/mapstructure v1.4.3 // indirect
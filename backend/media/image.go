@@ -0,0 +1,104 @@
+package media
+
+import (
+	"backend/tools"
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"net/http"
+	"strings"
+)
+
+// ImageTypes The types of image that questions can use. Only raster images are
+// allowed as vector images (SVG) can contain scripts and references to external
+// resources which would be served back to every player as they were uploaded
+var ImageTypes = []string{"image/png", "image/jpeg", "image/gif", "image/webp"}
+
+// Non-standard names for image types that browsers and other tools declare
+// mapped to the standard name
+var imageAliases = map[string]string{
+	"image/jpg":   "image/jpeg",
+	"image/pjpeg": "image/jpeg",
+	"image/x-png": "image/png",
+}
+
+// ImageType returns the type declared by the provided image data url or an empty
+// string if the image isn't a base64 data url
+func ImageType(image string) string {
+	header, _, found := strings.Cut(image, ";base64,")
+	if !found || !strings.HasPrefix(header, "data:") {
+		return ""
+	}
+	mime := strings.ToLower(strings.TrimSpace(strings.TrimPrefix(header, "data:")))
+	if alias, ok := imageAliases[mime]; ok {
+		return alias
+	}
+	return mime
+}
+
+// DetectImageType detects the type of the provided image data url from the first
+// bytes of its contents rather than the type it declares
+func DetectImageType(image string) (string, error) {
+	_, encoded, _ := strings.Cut(image, ";base64,")
+	if len(encoded) > 684 { // Only the first 512 bytes are needed to detect the type
+		encoded = encoded[:684]
+	}
+	data, err := base64.StdEncoding.DecodeString(encoded)
+	if err != nil {
+		return "", err
+	}
+	return http.DetectContentType(data), nil
+}
+
+// IsImageType checks whether the provided type is one of the ImageTypes
+func IsImageType(mime string) bool {
+	for _, value := range ImageTypes {
+		if value == mime {
+			return true
+		}
+	}
+	return false
+}
+
+// CheckImage checks that the image is a data url of one of the ImageTypes and
+// that its contents are the type it declares. Returns the image with the declared
+// type replaced with its standard name (e.g. image/jpg becomes image/jpeg)
+func CheckImage(image string) (string, error) {
+	mime := ImageType(image)
+	if mime == "" {
+		return "", errors.New("is not a base64 data url")
+	}
+	if mime == "image/svg+xml" {
+		return "", errors.New("is an SVG which isn't supported, use a PNG, JPEG, GIF or WebP image")
+	}
+	if !IsImageType(mime) {
+		return "", fmt.Errorf("has an unsupported type (%s)", mime)
+	}
+	detected, err := DetectImageType(image)
+	if err != nil {
+		return "", fmt.Errorf("is not valid base64: %w", err)
+	}
+	if detected != mime { // The contents don't match the declared type
+		return "", fmt.Errorf("is declared as %s but is %s", mime, detected)
+	}
+	_, encoded, _ := strings.Cut(image, ";base64,")
+	return "data:" + mime + ";base64," + encoded, nil
+}
+
+// CheckImages checks every question image using CheckImage and replaces each
+// image with its standardized data url. This must be called after
+// FetchRemoteImages so that images which were referenced by url are checked
+// too. Returns an error naming the question
+func CheckImages(questions []tools.QuestionData) error {
+	for i := range questions { // Iterate over the questions
+		if questions[i].Image == "" {
+			continue
+		}
+		image, err := CheckImage(questions[i].Image)
+		if err != nil {
+			return fmt.Errorf("the image for question %d %w", i+1, err)
+		}
+		questions[i].Image = image
+	}
+	return nil
+}
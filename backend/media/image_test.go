@@ -0,0 +1,53 @@
+package media
+
+import (
+	"backend/tools"
+	"encoding/base64"
+	"testing"
+)
+
+// encode creates a data url with the provided declared type and contents
+func encode(mime string, data string) string {
+	return "data:" + mime + ";base64," + base64.StdEncoding.EncodeToString([]byte(data))
+}
+
+// TestCheckImages tests that only raster image data urls with contents matching
+// their declared type are accepted
+func TestCheckImages(t *testing.T) {
+	png := "\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"
+	tests := []struct {
+		Image string
+		Valid bool
+	}{
+		{"", true},
+		{encode("image/png", png), true},
+		{encode("image/jpeg", "\xff\xd8\xff\xe0\x00\x10JFIF"), true},
+		{encode("image/gif", "GIF89a\x01\x00\x01\x00"), true},
+		{encode("image/webp", "RIFF\x00\x00\x00\x00WEBPVP8 "), true},
+		{encode("image/jpeg", png), false},
+		{encode("image/png", "<html><script></script></html>"), false},
+		{encode("image/svg+xml", "<svg/>"), false},
+		{encode("text/html", "<h1>"), false},
+		{"data:image/png;base64,not base64", false},
+		{"data:image/png,AAAA", false},
+		{"https://example.com/image.png", false},
+	}
+	for _, test := range tests {
+		err := CheckImages([]tools.QuestionData{{Image: test.Image}})
+		if (err == nil) != test.Valid {
+			t.Errorf("expected valid=%t for %q got %v", test.Valid, test.Image, err)
+		}
+	}
+}
+
+// TestCheckImagesNormalize tests that non-standard declared types are replaced
+// with their standard name
+func TestCheckImagesNormalize(t *testing.T) {
+	questions := []tools.QuestionData{{Image: encode("image/JPG", "\xff\xd8\xff\xe0\x00\x10JFIF")}}
+	if err := CheckImages(questions); err != nil {
+		t.Fatal(err)
+	}
+	if expected := encode("image/jpeg", "\xff\xd8\xff\xe0\x00\x10JFIF"); questions[0].Image != expected {
+		t.Errorf("expected %q got %q", expected, questions[0].Image)
+	}
+}
@@ -0,0 +1,136 @@
+package media
+
+import (
+	"backend/tools"
+	"context"
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"strings"
+	"syscall"
+	"time"
+)
+
+// Configuration for loading question images from remote urls
+var (
+	RemoteImages = tools.EnvOrDefault("QUIZLER_REMOTE_IMAGES", "true") == "true" && !tools.Offline // Whether questions can reference images by url
+	MaxImageSize = tools.EnvIntOrDefault("QUIZLER_MAX_IMAGE_SIZE", 5*1024*1024)                    // The maximum size in bytes of a remote image
+)
+
+// Limits for loading remote images
+const (
+	FetchTimeout = 15 * time.Second // The maximum time to spend loading a single image
+	MaxRedirects = 3                // The maximum number of redirects to follow
+)
+
+// The shared carrier-grade NAT range which isn't covered by net.IP.IsPrivate
+var sharedNetwork = &net.IPNet{IP: net.IPv4(100, 64, 0, 0), Mask: net.CIDRMask(10, 32)}
+
+// IsPublicAddress checks that the provided address is a public unicast address
+// and not one of the loopback, private, link local or otherwise internal ranges
+func IsPublicAddress(ip net.IP) bool {
+	return ip.IsGlobalUnicast() &&
+		!ip.IsPrivate() &&
+		!ip.IsLoopback() &&
+		!ip.IsLinkLocalUnicast() &&
+		!sharedNetwork.Contains(ip)
+}
+
+// The dialer used for remote images. The address is checked after it has been
+// resolved so that hostnames which resolve to internal addresses are rejected
+var dialer = &net.Dialer{
+	Timeout: 5 * time.Second,
+	Control: func(network string, address string, conn syscall.RawConn) error {
+		host, _, err := net.SplitHostPort(address)
+		if err != nil {
+			return err
+		}
+		if ip := net.ParseIP(host); ip == nil || !IsPublicAddress(ip) {
+			return fmt.Errorf("address %s is not public", host)
+		}
+		return nil
+	},
+}
+
+// A client for loading remote images which ignores proxy environment variables,
+// only connects to public addresses and only follows https redirects
+var client = &http.Client{
+	Timeout: FetchTimeout,
+	Transport: &http.Transport{
+		DialContext:           dialer.DialContext,
+		TLSHandshakeTimeout:   5 * time.Second,
+		ResponseHeaderTimeout: 10 * time.Second,
+	},
+	CheckRedirect: func(request *http.Request, via []*http.Request) error {
+		if len(via) >= MaxRedirects {
+			return errors.New("too many redirects")
+		}
+		if request.URL.Scheme != "https" {
+			return errors.New("redirected to a url that isn't https")
+		}
+		return nil
+	},
+}
+
+// IsRemote checks whether the provided image is a url that needs to be loaded
+// rather than an embedded data url
+func IsRemote(image string) bool {
+	return strings.HasPrefix(image, "https://")
+}
+
+// FetchRemoteImages replaces any question images that are https urls with data
+// urls containing the downloaded image so that they are served to players as if
+// they were uploaded. Returns an error naming the question that failed
+func FetchRemoteImages(questions []tools.QuestionData) error {
+	for i := range questions { // Iterate over the questions
+		question := &questions[i]
+		if !IsRemote(question.Image) {
+			continue
+		}
+		if !RemoteImages { // If remote images aren't allowed
+			return fmt.Errorf("question %d uses an image url but remote images are disabled", i+1)
+		}
+		image, err := FetchImage(question.Image)
+		if err != nil {
+			return fmt.Errorf("failed to load the image for question %d: %w", i+1, err)
+		}
+		question.Image = image
+	}
+	return nil
+}
+
+// FetchImage downloads the image at the provided https url and returns it as a
+// data url. The type of the image is detected from its contents rather than
+// trusting the content type the remote server responded with
+func FetchImage(url string) (string, error) {
+	ctx, cancel := context.WithTimeout(context.Background(), FetchTimeout)
+	defer cancel()
+	request, err := http.NewRequestWithContext(ctx, http.MethodGet, url, nil)
+	if err != nil {
+		return "", err
+	}
+	response, err := client.Do(request)
+	if err != nil {
+		return "", err
+	}
+	defer response.Body.Close()
+	if response.StatusCode != http.StatusOK {
+		return "", fmt.Errorf("server responded with status %d", response.StatusCode)
+	}
+	// Read one byte more than the limit so oversized images can be detected
+	data, err := io.ReadAll(io.LimitReader(response.Body, int64(MaxImageSize)+1))
+	if err != nil {
+		return "", err
+	}
+	if len(data) > MaxImageSize {
+		return "", fmt.Errorf("image is larger than %d bytes", MaxImageSize)
+	}
+	mime := http.DetectContentType(data)
+	if !IsImageType(mime) {
+		return "", fmt.Errorf("url is not a supported image (%s)", mime)
+	}
+	return "data:" + mime + ";base64," + base64.StdEncoding.EncodeToString(data), nil
+}
@@ -0,0 +1,177 @@
+package media
+
+import (
+	"backend/tools"
+	"crypto/rand"
+	"encoding/base64"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+	"strings"
+	"sync"
+	"time"
+)
+
+// MaxStoredMedia The maximum number of uploaded images kept at once
+const MaxStoredMedia = 512
+
+// MediaTTL How long uploaded images are kept once nothing references them
+var MediaTTL = time.Duration(tools.EnvIntOrDefault("QUIZLER_MEDIA_TTL", 60)) * time.Minute
+
+// MediaPrefix The prefix of question images that reference uploaded media
+// by its id (e.g. media:0b5f3f5e-...)
+const MediaPrefix = "media:"
+
+// StoredMedia An image uploaded separately from the quiz that uses it. Images
+// are kept while any game or stored quiz references them
+type StoredMedia struct {
+	Type       string    // The detected type of the image
+	Data       []byte    // The contents of the image
+	References int       // The number of games and stored quizzes using the image
+	Used       time.Time // The time the image was uploaded or last stopped being used
+}
+
+var (
+	storeLock = sync.RWMutex{}            // A lock for modifying the stored media
+	store     = map[string]*StoredMedia{} // The uploaded media mapped to its id
+)
+
+// NewUUID creates a random (version 4) UUID
+func NewUUID() string {
+	var value [16]byte
+	_, _ = rand.Read(value[:])
+	value[6] = (value[6] & 0x0f) | 0x40 // Version 4
+	value[8] = (value[8] & 0x3f) | 0x80 // RFC 4122 variant
+	return fmt.Sprintf("%x-%x-%x-%x-%x", value[0:4], value[4:6], value[6:8], value[8:10], value[10:])
+}
+
+// Store keeps the provided image and returns the id it can be referenced by.
+// The type of the image is detected from its contents and must be one of the
+// ImageTypes
+func Store(data []byte) (string, error) {
+	mime := http.DetectContentType(data)
+	if !IsImageType(mime) {
+		return "", fmt.Errorf("not a supported image (%s)", mime)
+	}
+	storeLock.Lock()         // Establish lock on the stored media
+	defer storeLock.Unlock() // Defer the releasing of the lock
+	if len(store) >= MaxStoredMedia {
+		return "", errors.New("too many images have been uploaded")
+	}
+	id := NewUUID()
+	store[id] = &StoredMedia{Type: mime, Data: data, Used: time.Now()}
+	return id, nil
+}
+
+// GetStored retrieves the uploaded media with the provided id or nil if there isn't any
+func GetStored(id string) *StoredMedia {
+	storeLock.RLock()         // Establish a read lock on the stored media
+	defer storeLock.RUnlock() // Defer the releasing of the read lock
+	return store[id]
+}
+
+// ResolveUploads replaces any question images that reference uploaded media with
+// a data url of the media. A reference is kept to each of the media which must be
+// given back with Release once the questions are no longer used. Returns the ids
+// of the media or an error naming the question that references media which
+// doesn't exist
+func ResolveUploads(questions []tools.QuestionData) ([]string, error) {
+	var ids []string
+	for i := range questions { // Iterate over the questions
+		question := &questions[i]
+		if !strings.HasPrefix(question.Image, MediaPrefix) {
+			continue
+		}
+		id := strings.TrimPrefix(question.Image, MediaPrefix)
+		storeLock.Lock() // Establish lock so the media can't expire before it is referenced
+		stored := store[id]
+		if stored != nil {
+			stored.References++
+		}
+		storeLock.Unlock() // Release lock
+		if stored == nil {
+			Release(ids)
+			return nil, fmt.Errorf("the image for question %d was uploaded but no longer exists", i+1)
+		}
+		ids = append(ids, id)
+		question.Image = "data:" + stored.Type + ";base64," + base64.StdEncoding.EncodeToString(stored.Data)
+	}
+	return ids, nil
+}
+
+// Release gives back the references to the media with the provided ids. Media
+// without any references expires once MediaTTL has passed
+func Release(ids []string) {
+	storeLock.Lock()         // Establish lock on the stored media
+	defer storeLock.Unlock() // Defer the releasing of the lock
+	for _, id := range ids {
+		if stored := store[id]; stored != nil && stored.References > 0 {
+			stored.References--
+			stored.Used = time.Now()
+		}
+	}
+}
+
+// ExpireMedia removes uploaded media that hasn't been referenced by any game or
+// stored quiz within the MediaTTL
+func ExpireMedia() {
+	storeLock.Lock() // Establish lock on the stored media
+	removed := 0
+	for id, stored := range store {
+		if stored.References == 0 && time.Since(stored.Used) > MediaTTL {
+			delete(store, id)
+			removed++
+		}
+	}
+	storeLock.Unlock() // Release lock
+	if removed > 0 {
+		log.Printf("Removed %d unused uploaded images", removed)
+	}
+}
+
+// HandleMedia HTTP handler for POST /api/media and GET /api/media/{id}. Images
+// are uploaded as the raw request body and the response contains the id which
+// questions reference the image with as media:{id}
+func HandleMedia(writer http.ResponseWriter, request *http.Request) {
+	id := strings.Trim(strings.TrimPrefix(request.URL.Path, "/api/media"), "/")
+	if id == "" {
+		if request.Method != http.MethodPost { // Only accept POST requests for uploads
+			tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+			return
+		}
+		// Read one byte more than the limit so oversized images can be detected
+		data, err := io.ReadAll(io.LimitReader(request.Body, int64(MaxImageSize)+1))
+		if err != nil {
+			tools.WriteError(writer, http.StatusBadRequest, "Failed to read image")
+			return
+		}
+		if len(data) > MaxImageSize {
+			tools.WriteError(writer, http.StatusRequestEntityTooLarge, fmt.Sprintf("Images must be smaller than %d bytes", MaxImageSize))
+			return
+		}
+		id, err = Store(data)
+		if err != nil {
+			tools.WriteError(writer, http.StatusBadRequest, err.Error())
+			return
+		}
+		tools.WriteJson(writer, http.StatusCreated, struct {
+			Id string `json:"id"` // The id of the uploaded image
+		}{Id: id})
+		return
+	}
+	if request.Method != http.MethodGet { // Only accept GET requests for images
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	stored := GetStored(id)
+	if stored == nil {
+		tools.WriteError(writer, http.StatusNotFound, "Image not found")
+		return
+	}
+	writer.Header().Set("Content-Type", stored.Type)
+	writer.Header().Set("X-Content-Type-Options", "nosniff")
+	writer.Header().Set("Cache-Control", "private, max-age=3600")
+	_, _ = writer.Write(stored.Data)
+}
@@ -0,0 +1,61 @@
+package media
+
+import (
+	"backend/tools"
+	"testing"
+	"time"
+)
+
+// TestResolveUploads tests that questions referencing uploaded media are given
+// the media as a data url and that missing media is reported
+func TestResolveUploads(t *testing.T) {
+	gif := []byte("GIF89a\x01\x00\x01\x00")
+	id, err := Store(gif)
+	if err != nil {
+		t.Fatal(err)
+	}
+	questions := []tools.QuestionData{{Image: MediaPrefix + id}, {Image: ""}}
+	ids, err := ResolveUploads(questions)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(ids) != 1 || ids[0] != id {
+		t.Errorf("expected ids [%s] got %v", id, ids)
+	}
+	if expected := encode("image/gif", string(gif)); questions[0].Image != expected {
+		t.Errorf("expected %q got %q", expected, questions[0].Image)
+	}
+	if _, err = ResolveUploads([]tools.QuestionData{{Image: MediaPrefix + NewUUID()}}); err == nil {
+		t.Error("expected missing media to fail")
+	}
+	if _, err = Store([]byte("<svg xmlns=\"http://www.w3.org/2000/svg\"/>")); err == nil {
+		t.Error("expected SVG upload to be rejected")
+	}
+}
+
+// TestExpireMedia tests that uploaded media is only removed once nothing
+// references it and MediaTTL has passed
+func TestExpireMedia(t *testing.T) {
+	id, err := Store([]byte("GIF89a\x01\x00\x01\x00"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err = ResolveUploads([]tools.QuestionData{{Image: MediaPrefix + id}}); err != nil {
+		t.Fatal(err)
+	}
+	GetStored(id).Used = time.Now().Add(-2 * MediaTTL)
+	ExpireMedia()
+	if GetStored(id) == nil {
+		t.Fatal("expected referenced media to be kept")
+	}
+	Release([]string{id})
+	ExpireMedia()
+	if GetStored(id) == nil {
+		t.Fatal("expected released media to be kept until MediaTTL passes")
+	}
+	GetStored(id).Used = time.Now().Add(-2 * MediaTTL)
+	ExpireMedia()
+	if GetStored(id) != nil {
+		t.Error("expected unused media to be removed")
+	}
+}
@@ -0,0 +1,125 @@
+package media
+
+import (
+	"backend/tools"
+	"bytes"
+	"encoding/base64"
+	"errors"
+	"image"
+	"image/color"
+	_ "image/gif" // Register the GIF decoder
+	"image/jpeg"
+	_ "image/png" // Register the PNG decoder
+	"log"
+	"runtime"
+	"strings"
+	"sync"
+)
+
+// Configuration for question image thumbnails
+const (
+	ThumbnailSize    = 160 // The maximum width and height of a thumbnail in pixels
+	ThumbnailQuality = 80  // The JPEG quality thumbnails are encoded with
+)
+
+// Decoding and resizing images is CPU heavy so only one thumbnail is generated
+// per CPU at a time no matter how many quizzes are being prepared
+var thumbnailSlots = make(chan struct{}, runtime.NumCPU())
+
+// GenerateThumbnails creates the thumbnails for every question with an image.
+// Images that can't be decoded are logged and left without a thumbnail
+func GenerateThumbnails(questions []tools.QuestionData) {
+	var group sync.WaitGroup
+	for i := range questions { // Iterate over the questions
+		question := &questions[i]
+		question.Thumbnail = "" // Thumbnails are only ever generated by the server
+		if question.Image == "" {
+			continue
+		}
+		group.Add(1)
+		go func() {
+			defer group.Done()
+			thumbnailSlots <- struct{}{}        // Wait for a free slot
+			defer func() { <-thumbnailSlots }() // Free the slot once finished
+			thumbnail, err := Thumbnail(question.Image)
+			if err != nil {
+				log.Printf("Failed to create thumbnail for question '%s': %s", question.Question, err)
+				return
+			}
+			question.Thumbnail = thumbnail
+		}()
+	}
+	group.Wait()
+}
+
+// Thumbnail decodes the provided image data url and returns a JPEG data url of
+// the image scaled down to fit within ThumbnailSize
+func Thumbnail(dataUrl string) (string, error) {
+	_, encoded, found := strings.Cut(dataUrl, ";base64,")
+	if !found || !strings.HasPrefix(dataUrl, "data:image/") {
+		return "", errors.New("image is not a base64 data url")
+	}
+	data, err := base64.StdEncoding.DecodeString(encoded)
+	if err != nil {
+		return "", err
+	}
+	source, _, err := image.Decode(bytes.NewReader(data))
+	if err != nil {
+		return "", err
+	}
+	var out bytes.Buffer
+	if err = jpeg.Encode(&out, scale(source, ThumbnailSize), &jpeg.Options{Quality: ThumbnailQuality}); err != nil {
+		return "", err
+	}
+	return "data:image/jpeg;base64," + base64.StdEncoding.EncodeToString(out.Bytes()), nil
+}
+
+// scale shrinks the image to fit within a square of the provided size keeping its
+// aspect ratio. Each pixel is the average of the source pixels it covers so
+// detail isn't lost the way it would be by skipping pixels
+func scale(source image.Image, size int) image.Image {
+	bounds := source.Bounds()
+	width, height := bounds.Dx(), bounds.Dy()
+	if width == 0 || height == 0 { // There is nothing to scale
+		return source
+	}
+	if width <= size && height <= size { // Small images are only re-encoded
+		size = width
+		if height > width {
+			size = height
+		}
+	}
+	outWidth, outHeight := size, height*size/width
+	if height > width {
+		outWidth, outHeight = width*size/height, size
+	}
+	if outWidth < 1 {
+		outWidth = 1
+	}
+	if outHeight < 1 {
+		outHeight = 1
+	}
+	out := image.NewRGBA(image.Rect(0, 0, outWidth, outHeight))
+	for y := 0; y < outHeight; y++ {
+		startY, endY := bounds.Min.Y+y*height/outHeight, bounds.Min.Y+(y+1)*height/outHeight
+		for x := 0; x < outWidth; x++ {
+			startX, endX := bounds.Min.X+x*width/outWidth, bounds.Min.X+(x+1)*width/outWidth
+			var r, g, b, a, count uint64
+			for sy := startY; sy < endY; sy++ {
+				for sx := startX; sx < endX; sx++ {
+					pr, pg, pb, pa := source.At(sx, sy).RGBA()
+					r, g, b, a, count = r+uint64(pr), g+uint64(pg), b+uint64(pb), a+uint64(pa), count+1
+				}
+			}
+			// JPEG has no transparency so transparent pixels are placed on white
+			white := 0xffff - a/count
+			out.Set(x, y, color.RGBA64{
+				R: uint16(r/count + white),
+				G: uint16(g/count + white),
+				B: uint16(b/count + white),
+				A: 0xffff,
+			})
+		}
+	}
+	return out
+}
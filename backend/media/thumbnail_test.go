@@ -0,0 +1,45 @@
+package media
+
+import (
+	"bytes"
+	"encoding/base64"
+	"image"
+	"image/color"
+	"image/jpeg"
+	"image/png"
+	"strings"
+	"testing"
+)
+
+// TestThumbnail tests that thumbnails fit within ThumbnailSize and keep the
+// aspect ratio of the image
+func TestThumbnail(t *testing.T) {
+	source := image.NewRGBA(image.Rect(0, 0, 800, 400))
+	for y := 0; y < 400; y++ {
+		for x := 0; x < 800; x++ {
+			source.Set(x, y, color.RGBA{R: 255, A: 255})
+		}
+	}
+	var encoded bytes.Buffer
+	if err := png.Encode(&encoded, source); err != nil {
+		t.Fatal(err)
+	}
+	thumbnail, err := Thumbnail("data:image/png;base64," + base64.StdEncoding.EncodeToString(encoded.Bytes()))
+	if err != nil {
+		t.Fatal(err)
+	}
+	data, err := base64.StdEncoding.DecodeString(strings.TrimPrefix(thumbnail, "data:image/jpeg;base64,"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	decoded, err := jpeg.Decode(bytes.NewReader(data))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if size := decoded.Bounds().Size(); size.X != ThumbnailSize || size.Y != ThumbnailSize/2 {
+		t.Errorf("expected %dx%d thumbnail got %dx%d", ThumbnailSize, ThumbnailSize/2, size.X, size.Y)
+	}
+	if _, err = Thumbnail("https://example.com/image.png"); err == nil {
+		t.Error("expected urls to be rejected")
+	}
+}
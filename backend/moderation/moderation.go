@@ -0,0 +1,105 @@
+package moderation
+
+import (
+	"backend/tools"
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"log"
+	"net/http"
+	"time"
+)
+
+// Verdict What should happen to content after it has been moderated
+type Verdict = string
+
+// The verdicts the moderation webhook can respond with
+const (
+	Allow Verdict = "allow" // The content is shown as normal
+	Flag  Verdict = "flag"  // The content is shown but recorded in the audit log for review
+	Hold  Verdict = "hold"  // The content is held back and the sender is told it wasn't accepted
+)
+
+// Kinds of content sent to the moderation webhook
+const (
+	KindName   = "name"   // The name of a player or host
+	KindAnswer = "answer" // The typed answer to a word cloud or fill in the blanks question
+)
+
+// Configuration for the moderation webhook which user written content is sent to
+// before it is shown to anyone. Moderation is disabled unless QUIZLER_MODERATION_URL is set
+var (
+	Url      = tools.EnvOrDefault("QUIZLER_MODERATION_URL", "")                                            // The url content is posted to
+	Key      = tools.EnvOrDefault("QUIZLER_MODERATION_KEY", "")                                            // The API key sent as a bearer token
+	Timeout  = time.Duration(tools.EnvIntOrDefault("QUIZLER_MODERATION_TIMEOUT", 2000)) * time.Millisecond // How long to wait for a verdict
+	Fallback = tools.EnvOrDefault("QUIZLER_MODERATION_FALLBACK", Allow)                                    // The verdict used when the webhook fails or times out
+)
+
+// A client for sending requests to the webhook
+var client = &http.Client{}
+
+// Content A piece of user written content that needs a verdict
+type Content struct {
+	Kind string `json:"kind"`           // The kind of content (name or answer)
+	Game string `json:"game,omitempty"` // The code of the game the content is for
+	Text string `json:"text"`           // The text written by the user
+}
+
+// Enabled returns whether a webhook has been configured and the server isn't offline
+func Enabled() bool {
+	return Url != "" && !tools.Offline
+}
+
+// IsVerdict checks whether the provided value is one of the verdicts
+func IsVerdict(value string) bool {
+	return value == Allow || value == Flag || value == Hold
+}
+
+// Check asks the webhook for the verdict on the provided content. The Fallback
+// verdict is used if the webhook doesn't respond within the Timeout or responds
+// with something other than a verdict so a slow webhook can't stall the game
+func Check(content Content) Verdict {
+	ctx, cancel := context.WithTimeout(context.Background(), Timeout)
+	defer cancel()
+	verdict, err := Request(ctx, content)
+	if err != nil {
+		log.Printf("Failed to moderate %s '%s' using fallback '%s': %s", content.Kind, content.Text, Fallback, err)
+		return Fallback
+	}
+	return verdict
+}
+
+// Request sends the content to the webhook and returns the verdict it responds with
+func Request(ctx context.Context, content Content) (Verdict, error) {
+	body, err := json.Marshal(content)
+	if err != nil {
+		return "", err
+	}
+	request, err := http.NewRequestWithContext(ctx, http.MethodPost, Url, bytes.NewReader(body))
+	if err != nil {
+		return "", err
+	}
+	request.Header.Set("Content-Type", "application/json")
+	if Key != "" { // Only send the authorization if a key is configured
+		request.Header.Set("Authorization", "Bearer "+Key)
+	}
+	response, err := client.Do(request)
+	if err != nil {
+		return "", err
+	}
+	defer response.Body.Close()
+	if response.StatusCode != http.StatusOK {
+		return "", fmt.Errorf("webhook responded with status %d", response.StatusCode)
+	}
+	var result struct {
+		Verdict Verdict `json:"verdict"` // The verdict on the content
+	}
+	if err := json.NewDecoder(response.Body).Decode(&result); err != nil {
+		return "", err
+	}
+	if !IsVerdict(result.Verdict) {
+		return "", fmt.Errorf("webhook responded with unknown verdict '%s'", result.Verdict)
+	}
+	return result.Verdict, nil
+}
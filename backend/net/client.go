@@ -13,6 +13,15 @@ const (
 	CStateChange          = 0x04
 	CAnswer               = 0x05
 	CKick                 = 0x06
+	CReconnectHost        = 0x07
+	CJoinDisplay          = 0x08
+	CSetMaxPlayers        = 0x09
+	CUpdateSettings       = 0x0A
+	CBuzz                 = 0x0B
+	CJudgeBuzz            = 0x0C
+	CWarmUp               = 0x0D
+	CResync               = 0x0E
+	CRequestSnapshot      = 0x0F
 )
 
 type StateChangeId = uint8
@@ -28,13 +37,31 @@ type (
 
 	// KickData A structure representing the data a client will send to kick a player
 	KickData struct {
-		Id string `json:"id"` // The id of the player to kick
+		Id     string `json:"id"`     // The id of the player to kick
+		Reason string `json:"reason"` // Optional - why the player was kicked which is shown to them
+	}
+
+	// GameSettings A structure representing the settings of a game which the host chooses
+	// when creating it and can change while the game is waiting for players
+	GameSettings struct {
+		Devices    tools.DeviceMode  `json:"devices"`    // How to handle multiple players on the same device
+		Present    bool              `json:"present"`    // Whether questions are only shown on displays
+		Reveal     tools.RevealMode  `json:"reveal"`     // What players are shown after each question is marked
+		Scoring    tools.ScoringMode `json:"scoring"`    // How the points for each question are decided
+		MaxPlayers int               `json:"maxPlayers"` // The most players that can join (0 for the server limit)
 	}
 
 	// CreateGameData A structure representing the data a client will send to create a game
 	CreateGameData struct {
-		Title     string               `json:"title"`     // The title of the game
-		Questions []tools.QuestionData `json:"questions"` // The questions to include in the game
+		GameSettings                         // Optional - the settings of the game
+		tools.TextStyle                      // Optional - how the text of the quiz should be shown
+		Title           string               `json:"title"`     // The title of the game
+		Questions       []tools.QuestionData `json:"questions"` // The questions to include in the game
+		Quiz            string               `json:"quiz"`      // Optional - the id of a stored quiz to use instead
+		Host            string               `json:"host"`      // Optional - the display name of the host
+		Upload          string               `json:"upload"`    // Optional - the id of a complete upload of the quiz to use instead
+		Profile         string               `json:"profile"`   // Optional - the name of a settings profile to use instead of the settings
+		Playlist        []string             `json:"playlist"`  // Optional - the ids of stored quizzes played after this quiz in the same game
 	}
 
 	// CheckNameTakenData A structure representing a client checking the server for if a name
@@ -53,8 +80,11 @@ type (
 	// RequestJoinData A structure representing a client requesting to join a game with the
 	// provided Id using the provided Name
 	RequestJoinData struct {
-		Id   string `json:"id"`   // The id of the game (game code)
-		Name string `json:"name"` // The name to join the game with
+		Id      string `json:"id"`      // The id of the game (game code)
+		Name    string `json:"name"`    // The name to join the game with
+		Audio   bool   `json:"audio"`   // Whether the player wants questions read aloud
+		Compact bool   `json:"compact"` // Whether the player only wants the answer count for each question to save data
+		Locale  string `json:"locale"`  // Optional - the language the player wants the questions in (e.g. fr or pt-BR)
 	}
 
 	// StateChangeData A structure representing a client requesting state change
@@ -62,8 +92,59 @@ type (
 		State StateChangeId `json:"state"` // The state to update
 	}
 
+	// ReconnectHostData A structure representing a host reconnecting to their paused game
+	ReconnectHostData struct {
+		Id  string `json:"id"`  // The id of the game (game code)
+		Key string `json:"key"` // The host key sent when the game was created
+	}
+
+	// JoinDisplayData A structure representing a client connecting as a read-only display
+	// of the host view of a game
+	JoinDisplayData struct {
+		Id  string `json:"id"`  // The id of the game (game code)
+		Key string `json:"key"` // The host key sent when the game was created
+	}
+
+	// SetMaxPlayersData A structure representing the host changing the most players
+	// that can join their game
+	SetMaxPlayersData struct {
+		Value int `json:"value"` // The most players that can join (0 for no limit when the server allows it)
+	}
+
+	// RequestSnapshotData A structure representing a client asking for a compact
+	// snapshot of the game it is in to recover without reconnecting
+	RequestSnapshotData struct{}
+
+	// BuzzData A structure representing a player buzzing to answer a buzzer question
+	// which has no data
+	BuzzData struct{}
+
+	// JudgeBuzzData A structure representing the host judging the answer of the player
+	// whose turn it is to answer a buzzer question
+	JudgeBuzzData struct {
+		Correct bool `json:"correct"` // Whether the player answered correctly
+	}
+
+	// WarmUpData A structure representing an unscored poll the host asks the players
+	// while waiting for the game to start. An empty question ends the current poll
+	WarmUpData struct {
+		Question string   `json:"question"` // The question to ask or empty to end the warm-up
+		Answers  []string `json:"answers"`  // The answers the players can vote for (e.g. emoji)
+	}
+
+	// ResyncData A structure representing a client asking for the events it missed
+	// after noticing a gap in the sequence numbers
+	ResyncData struct {
+		Seq uint64 `json:"seq"` // The sequence number of the last event the client received
+	}
+
 	// AnswerData A structure representing a client answering a question with the index
 	AnswerData struct {
-		Id tools.AnswerIndex `json:"id"` // The index of the answer
+		Question *tools.QuestionIndex `json:"question"` // The index of the question being answered (not needed for warm-up votes)
+		Id       tools.AnswerIndex    `json:"id"`       // The index of the answer
+		Ids      []tools.AnswerIndex  `json:"ids"`      // Optional - the indexes of the answers for questions with more than one
+		Text     string               `json:"text"`     // Optional - the words written for word cloud questions
+		Number   *float64             `json:"number"`   // Optional - the number chosen for number questions
+		Blanks   []string             `json:"blanks"`   // Optional - the words written for each blank of fill in the blanks questions
 	}
 )
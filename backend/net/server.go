@@ -2,6 +2,7 @@ package net
 
 import (
 	"backend/tools"
+	"fmt"
 	. "github.com/jacobtread/gowsps"
 	"time"
 )
@@ -18,6 +19,24 @@ const (
 	SQuestion            = 0x07
 	SAnswerResult        = 0x08
 	SScores              = 0x09
+	SDuplicateDevice     = 0x0A
+	SStateSync           = 0x0B
+	SPlayerCount         = 0x0C
+	SKicked              = 0x0D
+	SCapacityWarning     = 0x0E
+	SGameFull            = 0x0F
+	SSettings            = 0x10
+	SSummary             = 0x11
+	SFirstCorrect        = 0x12
+	SBuzzer              = 0x13
+	SPollResults         = 0x14
+	SRoundOver           = 0x15
+	SWarmUp              = 0x16
+	SResults             = 0x17
+	SNextQuestion        = 0x18
+	SAnswerAccepted      = 0x19
+	SSequenced           = 0x1A
+	SSnapshot            = 0x1B
 )
 
 // DisconnectPacket creates a new disconnect packet with the provided reason
@@ -31,15 +50,45 @@ type PlayerDataMode = uint8
 
 const (
 	AddMode    PlayerDataMode = iota // Add the player to player lists
-	RemoveMode                       // Remove the player from player lists
+	RemoveMode                       // Remove the player from player lists because they left
 	SelfMode                         // Set this as the player for whoever this is sent to
+	KickMode                         // Remove the player from player lists because the host kicked them
+)
+
+// Codes identifying errors which have details that clients can use to explain
+// the error to the player
+const (
+	InvalidName     = "invalid_name"     // The name is too short or too long
+	InvalidAnswer   = "invalid_answer"   // Too few or too many answers were chosen or typed text isn't allowed
+	InvalidQuiz     = "invalid_quiz"     // The quiz has problems that need fixing
+	GameFull        = "game_full"        // The game already has as many players as it allows
+	Moderated       = "moderated"        // The text was held back by the moderation webhook
+	AlreadyAnswered = "already_answered" // The question was already answered so the first answer was kept
+	StaleQuestion   = "stale_question"   // The answer was meant for a question that is no longer being asked
 )
 
 // ErrorPacket creates a new error packet with the provided cause
 func ErrorPacket(cause string) Packet {
+	return ErrorDetailsPacket("", cause, nil)
+}
+
+// ErrorDetailsPacket creates a new error packet with the provided cause along
+// with a code identifying the error and details describing it
+func ErrorDetailsPacket(code string, cause string, details any) Packet {
 	return Packet{Id: SError, Data: struct {
-		Cause string `json:"cause"`
-	}{Cause: cause}}
+		Cause   string `json:"cause"`
+		Code    string `json:"code,omitempty"`
+		Details any    `json:"details,omitempty"`
+	}{Cause: cause, Code: code, Details: details}}
+}
+
+// InvalidNamePacket creates a new error packet for a name that isn't between
+// the provided minimum and maximum number of characters
+func InvalidNamePacket(min int, max int) Packet {
+	return ErrorDetailsPacket(InvalidName, fmt.Sprintf("Names must be between %d and %d characters", min, max), struct {
+		Min int `json:"min"` // The fewest characters a name can have
+		Max int `json:"max"` // The most characters a name can have
+	}{Min: min, Max: max})
 }
 
 // PlayerDataPacket creates a new player data packet with the provided id and name
@@ -51,13 +100,78 @@ func PlayerDataPacket(id string, name string, mode PlayerDataMode) Packet {
 	}{Id: id, Name: name, Mode: mode}}
 }
 
-// JoinGamePacket creates a new join game data packet with the provided values
-func JoinGamePacket(owner bool, id string, title string) Packet {
-	return Packet{Id: SJoinedGame, Data: struct {
-		Owner bool   `json:"owner"` // Whether the player is the host/owner of the quiz
-		Id    string `json:"id"`    // The id of the joined game
-		Title string `json:"title"` // The title of the joined game
-	}{Id: id, Title: title, Owner: owner}}
+// PlayerRemovedPacket creates a new player data packet for a player that was
+// removed from the game with the reason they were removed
+func PlayerRemovedPacket(id string, name string, mode PlayerDataMode, reason string) Packet {
+	return Packet{Id: SPlayerData, Data: struct {
+		Id     string         `json:"id"`     // The id of the player
+		Name   string         `json:"name"`   // The name of the player
+		Mode   PlayerDataMode `json:"mode"`   // Whether the player left or was kicked
+		Reason string         `json:"reason"` // Why the player was removed
+	}{Id: id, Name: name, Mode: mode, Reason: reason}}
+}
+
+// KickedPacket creates a new packet telling a player that the host kicked
+// them from the game along with the reason
+func KickedPacket(reason string) Packet {
+	return Packet{Id: SKicked, Data: struct {
+		Reason string `json:"reason"` // Why the player was kicked
+	}{Reason: reason}}
+}
+
+// PlayerCountPacket creates a new packet with the number of players in the game
+// which is sent whenever a player joins or is removed
+func PlayerCountPacket(count int) Packet {
+	return Packet{Id: SPlayerCount, Data: struct {
+		Count int `json:"count"` // The number of players in the game
+	}{Count: count}}
+}
+
+// capacityData The number of players in a game compared to the most it allows
+type capacityData struct {
+	Players int `json:"players"` // The number of players in the game
+	Max     int `json:"max"`     // The most players that can join the game
+}
+
+// CapacityWarningPacket creates a new packet warning the host that their game
+// is nearly full
+func CapacityWarningPacket(players int, max int) Packet {
+	return Packet{Id: SCapacityWarning, Data: capacityData{Players: players, Max: max}}
+}
+
+// GameFullPacket creates a new packet telling the host that players are being
+// turned away because their game is full
+func GameFullPacket(players int, max int) Packet {
+	return Packet{Id: SGameFull, Data: capacityData{Players: players, Max: max}}
+}
+
+// JoinGameData The data sent to a client when they join a game
+type JoinGameData struct {
+	GameSettings             // The current settings of the game
+	tools.TextStyle          // How the text of the quiz should be shown
+	Owner           bool     `json:"owner"`              // Whether the player is the host/owner of the quiz
+	Id              string   `json:"id"`                 // The id of the joined game
+	Title           string   `json:"title"`              // The title of the joined game
+	Host            string   `json:"host,omitempty"`     // The display name of the host if they chose one
+	Questions       int      `json:"questions"`          // The number of questions in the game
+	Duration        int64    `json:"duration"`           // The estimated length of the game in milliseconds
+	TooLong         bool     `json:"tooLong,omitempty"`  // Whether the game is longer than the server recommends
+	Key             string   `json:"key,omitempty"`      // The secret host key only sent to the owner and displays
+	Display         bool     `json:"display,omitempty"`  // Whether the client joined as a read-only display
+	Warnings        []string `json:"warnings,omitempty"` // Warnings about the quiz only sent to the owner when the game is created
+	Rounds          []string `json:"rounds,omitempty"`   // The titles of the quizzes in the playlist when there is more than one
+	Seq             uint64   `json:"seq"`                // The sequence number of the last event sent to everyone in the game
+}
+
+// SettingsPacket creates a new packet with the settings of the game which is
+// sent to everyone in the game whenever the host changes them
+func SettingsPacket(settings GameSettings) Packet {
+	return Packet{Id: SSettings, Data: settings}
+}
+
+// JoinGamePacket creates a new join game data packet with the provided data
+func JoinGamePacket(data JoinGameData) Packet {
+	return Packet{Id: SJoinedGame, Data: data}
 }
 
 // NameTakenResultPacket creates a new name taken result packet with the provided result
@@ -84,21 +198,102 @@ func TimeSyncPacket(total time.Duration, remaining time.Duration) Packet {
 }
 
 // QuestionPacket creates a new question packet which informs the client which
-// question they are currently answering
-func QuestionPacket(data tools.QuestionData) Packet {
+// question they are currently answering. The question audio is only included
+// for clients that asked for audio when joining. The style describes how the text
+// of the question should be shown. Answers must be sent with the index so answers
+// meant for an earlier question can be rejected
+func QuestionPacket(index tools.QuestionIndex, data tools.QuestionData, style tools.TextStyle, audio bool) Packet {
+	packet := struct {
+		tools.TextStyle
+		Index      tools.QuestionIndex `json:"index"`
+		Image      string              `json:"image,omitempty"`
+		Question   string              `json:"question"`
+		Answers    []string            `json:"answers"`
+		Marking    tools.MarkingMode   `json:"marking,omitempty"`
+		MinChoices int                 `json:"minChoices,omitempty"`
+		MaxChoices int                 `json:"maxChoices,omitempty"`
+		Audio      string              `json:"audio,omitempty"`
+		Kind       tools.QuestionKind  `json:"kind,omitempty"`
+		Slider     *tools.Slider       `json:"slider,omitempty"`
+		BlankCount int                 `json:"blankCount,omitempty"`
+	}{
+		TextStyle:  style,
+		Index:      index,
+		Image:      data.Image,
+		Question:   data.Question,
+		Answers:    data.Answers,
+		Marking:    data.Marking,
+		MinChoices: data.MinChoices,
+		MaxChoices: data.MaxChoices,
+		Kind:       data.Kind,
+		Slider:     data.Slider(),
+		BlankCount: len(data.Blanks),
+	}
+	if audio {
+		packet.Audio = data.Audio
+	}
+	if data.Kind == tools.KindBuzzer { // The answers to buzzer questions are only for the host
+		packet.Answers = []string{}
+	}
+	return Packet{Id: SQuestion, Data: packet}
+}
+
+// PresentedQuestionPacket creates a new question packet for players in games using
+// presentation mode or players that asked for compact questions. The question and
+// answers are only shown on the displays so players are only told how many answers
+// there are and how to choose them
+func PresentedQuestionPacket(index tools.QuestionIndex, data tools.QuestionData) Packet {
+	count := len(data.Answers)
+	if data.Kind == tools.KindBuzzer { // Players buzz rather than choosing an answer
+		count = 0
+	}
 	return Packet{Id: SQuestion, Data: struct {
-		Image    string   `json:"image,omitempty"`
-		Question string   `json:"question"`
-		Answers  []string `json:"answers"`
-	}{Image: data.Image, Question: data.Question, Answers: data.Answers}}
+		Index      tools.QuestionIndex `json:"index"`
+		Presented  bool                `json:"presented"`
+		Count      int                 `json:"count"`
+		Marking    tools.MarkingMode   `json:"marking,omitempty"`
+		MinChoices int                 `json:"minChoices,omitempty"`
+		MaxChoices int                 `json:"maxChoices,omitempty"`
+		Kind       tools.QuestionKind  `json:"kind,omitempty"`
+		Slider     *tools.Slider       `json:"slider,omitempty"`
+		BlankCount int                 `json:"blankCount,omitempty"`
+	}{
+		Index:      index,
+		Presented:  true,
+		Count:      count,
+		Marking:    data.Marking,
+		MinChoices: data.MinChoices,
+		MaxChoices: data.MaxChoices,
+		Kind:       data.Kind,
+		Slider:     data.Slider(),
+		BlankCount: len(data.Blanks),
+	}}
+}
+
+// AnswerResultData A structure representing the result of marking a player's
+// answer. What is included depends on the reveal mode of the game
+type AnswerResultData struct {
+	Result bool                `json:"result"`           // Whether the answer was correct
+	Values []tools.AnswerIndex `json:"values,omitempty"` // The correct answers when they are revealed
+	Number *float64            `json:"number,omitempty"` // The correct number for number questions when it is revealed
+	Blanks []string            `json:"blanks,omitempty"` // The first accepted answer of each blank when they are revealed
+	Score  *uint32             `json:"score,omitempty"`  // The player's score when only their own score is revealed
+}
+
+// AnswerAcceptedPacket creates a new answer accepted packet which tells the player
+// their answer was recorded and how long after the question was sent the server
+// recorded it. This is the time used for their score
+func AnswerAcceptedPacket(question int, elapsed time.Duration) Packet {
+	return Packet{Id: SAnswerAccepted, Data: struct {
+		Question int   `json:"question"` // The index of the question that was answered
+		Elapsed  int64 `json:"elapsed"`  // The milliseconds between the question being sent and the answer being recorded
+	}{Question: question, Elapsed: elapsed.Milliseconds()}}
 }
 
 // AnswerResultPacket creates a new answer result packet which informs the client
 // whether the answer they chose was correct after marking
-func AnswerResultPacket(result bool) Packet {
-	return Packet{Id: SAnswerResult, Data: struct {
-		Result bool `json:"result"`
-	}{Result: result}}
+func AnswerResultPacket(data AnswerResultData) Packet {
+	return Packet{Id: SAnswerResult, Data: data}
 }
 
 // ScoresPacket creates a new score packet which contains the scores of all the
@@ -108,3 +303,182 @@ func ScoresPacket(data tools.ScoreMap) Packet {
 		Scores tools.ScoreMap `json:"scores"`
 	}{Scores: data}}
 }
+
+// StateSyncData A structure representing the full current state of a game sent to
+// connections that rejoin a game that is already in progress
+type StateSyncData struct {
+	State     tools.State                  `json:"state"`              // The current state of the game
+	Scores    tools.ScoreMap               `json:"scores"`             // The scores of every player
+	Question  any                          `json:"question,omitempty"` // The question being answered in the same form as the question packet
+	Total     int64                        `json:"total"`              // The total time in ms of the current countdown
+	Remaining int64                        `json:"remaining"`          // The time in ms remaining on the current countdown
+	Results   map[tools.QuestionIndex]bool `json:"results,omitempty"`  // Whether the player was right for each marked question they answered
+	Seq       uint64                       `json:"seq"`                // The sequence number of the last event sent to everyone in the game
+}
+
+// SequencedPacket creates a new packet wrapping an event sent to everyone in the
+// game with its sequence number. Clients that see a gap in the numbers missed an
+// event and can ask for everything after the last one they saw
+func SequencedPacket(seq uint64, packet Packet) Packet {
+	return Packet{Id: SSequenced, Data: struct {
+		Seq    uint64 `json:"seq"`    // The number of the event which is one more than the event before it
+		Packet Packet `json:"packet"` // The event itself
+	}{Seq: seq, Packet: packet}}
+}
+
+type (
+	// SnapshotData A structure representing a compact summary of the game that a
+	// client can ask for at any time to recover from errors without reconnecting
+	SnapshotData struct {
+		State     tools.State          `json:"state"`              // The current state of the game
+		Question  *tools.QuestionIndex `json:"question,omitempty"` // The index of the current question once one has been asked
+		Total     int64                `json:"total"`              // The total time in ms of the current countdown
+		Remaining int64                `json:"remaining"`          // The time in ms remaining on the current countdown
+		Score     *uint32              `json:"score,omitempty"`    // The score of the player that asked (players only)
+		Players   []PlayerSummary      `json:"players"`            // Every player in the game sorted by name
+		Seq       uint64               `json:"seq"`                // The sequence number of the last event sent to everyone in the game
+	}
+
+	// PlayerSummary A structure representing a player listed in a snapshot
+	PlayerSummary struct {
+		Id   string `json:"id"`   // The id of the player
+		Name string `json:"name"` // The name of the player
+	}
+)
+
+// SnapshotPacket creates a new snapshot packet with the compact summary of the game
+func SnapshotPacket(data SnapshotData) Packet {
+	return Packet{Id: SSnapshot, Data: data}
+}
+
+// StateSyncPacket creates a new state sync packet which gives a rejoining
+// connection everything it needs to continue from where the game is now
+func StateSyncPacket(data StateSyncData) Packet {
+	return Packet{Id: SStateSync, Data: data}
+}
+
+type (
+	// QuestionStats A structure representing how many players took part in a
+	// question when it was marked
+	QuestionStats struct {
+		Question tools.QuestionIndex `json:"question"`          // The index of the question
+		Players  int                 `json:"players"`           // The number of players in the game when the question was marked
+		Answered int                 `json:"answered"`          // The number of players who answered the question
+		Stopped  int                 `json:"stopped"`           // The number of players who answered the previous question but not this one
+		Median   int64               `json:"median"`            // The median time in ms the players who answered took to answer
+		P90      int64               `json:"p90"`               // The time in ms that 90% of the players who answered answered within
+		Fastest  string              `json:"fastest,omitempty"` // The name of the player who answered first
+		Quickest int64               `json:"quickest"`          // The time in ms the fastest player took to answer
+	}
+
+	// SummaryData A structure representing the results of a game sent to the host
+	// once the game is over
+	SummaryData struct {
+		Questions []QuestionStats `json:"questions"`        // The stats of each marked question in the order they were marked
+		Rounds    []RoundData     `json:"rounds,omitempty"` // The scores of each quiz when the game was a playlist
+	}
+)
+
+// SummaryPacket creates a new summary packet which gives the host the results
+// of their game once it is over
+func SummaryPacket(data SummaryData) Packet {
+	return Packet{Id: SSummary, Data: data}
+}
+
+// FirstCorrectPacket creates a new first correct packet which announces the
+// player who answered the question correctly first and the bonus they earned
+func FirstCorrectPacket(id string, name string, bonus int) Packet {
+	return Packet{Id: SFirstCorrect, Data: struct {
+		Id    string `json:"id"`    // The id of the player that answered correctly first
+		Name  string `json:"name"`  // The name of the player
+		Bonus int    `json:"bonus"` // The bonus points they earned
+	}{Id: id, Name: name, Bonus: bonus}}
+}
+
+// PollResultsData A structure representing the responses to a poll or word cloud
+// question which are shown to everyone once the question is over
+type PollResultsData struct {
+	Counts []int          `json:"counts,omitempty"` // The number of players that chose each answer of a poll
+	Words  map[string]int `json:"words,omitempty"`  // The number of players that wrote each response to a word cloud
+}
+
+// PollResultsPacket creates a new poll results packet which shows everyone how
+// the players responded to a question that isn't marked
+func PollResultsPacket(data PollResultsData) Packet {
+	return Packet{Id: SPollResults, Data: data}
+}
+
+// RoundData A structure representing the points the players earned in one quiz
+// of a playlist which is sent once the last question of the quiz is marked
+type RoundData struct {
+	Round  int               `json:"round"`            // The position of the quiz in the playlist starting at 0
+	Title  string            `json:"title"`            // The title of the quiz
+	Scores map[string]uint32 `json:"scores,omitempty"` // The points each player earned in the quiz (only sent to players with the leaderboard)
+	Next   string            `json:"next,omitempty"`   // The title of the next quiz or empty after the last quiz
+}
+
+// RoundOverPacket creates a new round over packet which tells the clients that a
+// quiz of the playlist has finished and which quiz is next
+func RoundOverPacket(data RoundData) Packet {
+	return Packet{Id: SRoundOver, Data: data}
+}
+
+// NextQuestionPacket creates a new packet previewing the next question for the
+// host while the results of the current question are shown so they can introduce
+// it. Only the host is sent the preview as the notes aren't meant for players
+func NextQuestionPacket(index int, data tools.QuestionData) Packet {
+	return Packet{Id: SNextQuestion, Data: struct {
+		Index      int                `json:"index"`                // The index of the next question
+		Question   string             `json:"question"`             // The contents of the next question
+		Kind       tools.QuestionKind `json:"kind"`                 // How players respond to the next question
+		Difficulty string             `json:"difficulty,omitempty"` // How hard the next question is
+		Notes      string             `json:"notes,omitempty"`      // The notes for the host to read out
+	}{Index: index, Question: data.Question, Kind: data.Kind, Difficulty: data.Difficulty, Notes: data.Notes}}
+}
+
+// PlayerResult A structure representing the final score and rank of a player
+type PlayerResult struct {
+	Id     string   `json:"id"`     // The id of the player
+	Name   string   `json:"name"`   // The name of the player
+	Score  uint32   `json:"score"`  // The final score of the player
+	Rank   int      `json:"rank"`   // The position of the player starting at 1 which is shared by players with the same score
+	Points []uint32 `json:"points"` // The points the player earned for each question in order
+}
+
+// ResultsPacket creates a new results packet which tells everyone the final
+// rankings of the players once the game is over
+func ResultsPacket(players []PlayerResult) Packet {
+	return Packet{Id: SResults, Data: struct {
+		Players []PlayerResult `json:"players"` // The players ordered by their rank
+	}{Players: players}}
+}
+
+// WarmUpPacket creates a new warm-up packet with the question of the unscored
+// poll asked in the lobby and the number of votes for each answer. Closed polls
+// have ended and their counts are final
+func WarmUpPacket(data WarmUpData, counts []int, closed bool) Packet {
+	return Packet{Id: SWarmUp, Data: struct {
+		WarmUpData
+		Counts []int `json:"counts,omitempty"` // The number of players that voted for each answer
+		Closed bool  `json:"closed,omitempty"` // Whether voting has ended
+	}{WarmUpData: data, Counts: counts, Closed: closed}}
+}
+
+// BuzzerPacket creates a new buzzer packet which announces the player whose turn
+// it is to answer a buzzer question. An empty id means nobody is answering and
+// the players who haven't buzzed yet can buzz
+func BuzzerPacket(id string, name string) Packet {
+	return Packet{Id: SBuzzer, Data: struct {
+		Id   string `json:"id"`   // The id of the player answering
+		Name string `json:"name"` // The name of the player
+	}{Id: id, Name: name}}
+}
+
+// DuplicateDevicePacket creates a new duplicate device packet which warns the host
+// that the player with the provided id joined from the same device as another player
+func DuplicateDevicePacket(id string, other string) Packet {
+	return Packet{Id: SDuplicateDevice, Data: struct {
+		Id    string `json:"id"`    // The id of the player that just joined
+		Other string `json:"other"` // The id of the player already using the device
+	}{Id: id, Other: other}}
+}
@@ -0,0 +1,53 @@
+package main
+
+import (
+	"backend/generator"
+	"backend/media"
+	"backend/speech"
+	"backend/tools"
+	"errors"
+	"fmt"
+	"log"
+	"os"
+	"regexp"
+)
+
+// Matches assets in the page that would be loaded from another server
+var externalAsset = regexp.MustCompile(`(?i)(src=["']?|url\(["']?|<link[^>]+href=["']?)(https?:)?//`)
+
+// CheckOffline verifies that the server can run without any outbound network
+// access. The page must be embedded and must not load any assets from another
+// server. Features that were configured but need external services are logged
+// as disabled
+func CheckOffline(index []byte) error {
+	if len(index) == 0 { // If the page wasn't embedded when building
+		return errors.New("the web page isn't embedded in the server")
+	}
+	if asset := externalAsset.Find(index); asset != nil { // If the page loads assets from another server
+		return fmt.Errorf("the web page loads external assets (%s)", asset)
+	}
+	if _, set := os.LookupEnv("QUIZLER_GENERATOR_URL"); set && !generator.Enabled() {
+		log.Printf("Offline: Question generation is disabled")
+	}
+	if _, set := os.LookupEnv("QUIZLER_SPEECH_URL"); set && !speech.Enabled() {
+		log.Printf("Offline: Question audio is disabled")
+	}
+	if !media.RemoteImages {
+		log.Printf("Offline: Remote question images are disabled")
+	}
+	return nil
+}
+
+// PrintJoinUrls prints the urls that players on the local network can open to
+// join games hosted on this server
+func PrintJoinUrls(urls []string) {
+	if len(urls) == 0 { // If no network addresses were found
+		fmt.Println("  No local network addresses found. Players on this machine can join on http://localhost")
+		return
+	}
+	fmt.Println("  Players on your network can join on:")
+	for _, url := range urls {
+		fmt.Printf("    %s\n", url)
+	}
+	fmt.Println()
+}
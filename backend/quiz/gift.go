@@ -0,0 +1,168 @@
+package quiz
+
+import (
+	"backend/tools"
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// The characters that can be escaped with a backslash in GIFT text
+const giftEscapes = "~=#{}:\\"
+
+// ParseGift converts questions written in the Moodle GIFT format into a quiz with
+// the provided title. Only multiple choice and true/false questions can be
+// represented so any other type of question is reported as an error
+func ParseGift(title string, text string) (*Quiz, error) {
+	quiz := &Quiz{Title: title, Questions: []tools.QuestionData{}}
+	for number, block := range giftBlocks(text) { // Each block is a single question
+		question, err := parseGiftQuestion(block)
+		if err != nil {
+			return nil, fmt.Errorf("question %d: %w", number+1, err)
+		}
+		quiz.Questions = append(quiz.Questions, question)
+	}
+	return quiz, nil
+}
+
+// giftBlocks splits the GIFT text into its question blocks which are separated
+// by blank lines. Comments and category declarations are removed
+func giftBlocks(text string) []string {
+	var blocks []string
+	var current []string
+	flush := func() {
+		if len(current) > 0 {
+			blocks = append(blocks, strings.Join(current, "\n"))
+			current = nil
+		}
+	}
+	for _, line := range strings.Split(strings.ReplaceAll(text, "\r\n", "\n"), "\n") {
+		trimmed := strings.TrimSpace(line)
+		if trimmed == "" { // Blank lines end the current question
+			flush()
+		} else if !strings.HasPrefix(trimmed, "//") && !strings.HasPrefix(trimmed, "$CATEGORY:") {
+			current = append(current, trimmed)
+		}
+	}
+	flush()
+	return blocks
+}
+
+// parseGiftQuestion parses a single GIFT question block
+func parseGiftQuestion(block string) (tools.QuestionData, error) {
+	var question tools.QuestionData
+	if strings.HasPrefix(block, "::") { // Remove the optional question title
+		if end := indexUnescaped(block[2:], "::"); end != -1 {
+			block = block[end+4:]
+		}
+	}
+	start := indexUnescaped(block, "{")
+	end := indexUnescaped(block, "}")
+	if start == -1 || end < start {
+		return question, fmt.Errorf("missing answers")
+	}
+	question.Question = giftText(block[:start] + " " + block[end+1:])
+	answers := strings.TrimSpace(block[start+1 : end])
+	switch strings.ToUpper(answers) {
+	case "T", "TRUE":
+		question.Answers, question.Values = []string{"True", "False"}, []tools.AnswerIndex{0}
+	case "F", "FALSE":
+		question.Answers, question.Values = []string{"True", "False"}, []tools.AnswerIndex{1}
+	default:
+		if err := parseGiftChoices(&question, answers); err != nil {
+			return question, err
+		}
+	}
+	if !question.IsValid() {
+		return question, fmt.Errorf("not a valid multiple choice question")
+	}
+	return question, nil
+}
+
+// parseGiftChoices parses the answers of a multiple choice question. Answers that
+// start with = are correct and answers that start with ~ are correct only when
+// they have a positive percentage weight
+func parseGiftChoices(question *tools.QuestionData, answers string) error {
+	wrong := 0 // The number of wrong answers, questions without any are short answer questions
+	for _, choice := range splitGiftChoices(answers) {
+		correct := choice[0] == '='
+		choice = strings.TrimSpace(choice[1:])
+		if strings.Contains(choice, "->") {
+			return fmt.Errorf("matching questions are not supported")
+		}
+		if strings.HasPrefix(choice, "%") { // Parse the percentage weight
+			if end := strings.Index(choice[1:], "%"); end != -1 {
+				weight, err := strconv.ParseFloat(choice[1:end+1], 64)
+				if err == nil && !correct {
+					correct = weight > 0
+				}
+				choice = choice[end+2:]
+			}
+		}
+		if feedback := indexUnescaped(choice, "#"); feedback != -1 { // Remove the feedback
+			choice = choice[:feedback]
+		}
+		if correct {
+			question.Values = append(question.Values, len(question.Answers))
+		} else {
+			wrong++
+		}
+		question.Answers = append(question.Answers, giftText(choice))
+	}
+	if wrong == 0 {
+		return fmt.Errorf("short answer and numeric questions are not supported")
+	}
+	return nil
+}
+
+// splitGiftChoices splits the answers on each unescaped = or ~ keeping the marker
+// at the start of each choice
+func splitGiftChoices(answers string) []string {
+	var choices []string
+	start := -1
+	for i := 0; i < len(answers); i++ {
+		switch answers[i] {
+		case '\\':
+			i++ // Skip the escaped character
+		case '=', '~':
+			if start != -1 {
+				choices = append(choices, answers[start:i])
+			}
+			start = i
+		}
+	}
+	if start != -1 {
+		choices = append(choices, answers[start:])
+	}
+	return choices
+}
+
+// indexUnescaped finds the index of the first occurrence of value in text that
+// isn't preceded by a backslash or -1 if there is none
+func indexUnescaped(text string, value string) int {
+	for i := 0; i < len(text); i++ {
+		if text[i] == '\\' {
+			i++ // Skip the escaped character
+		} else if strings.HasPrefix(text[i:], value) {
+			return i
+		}
+	}
+	return -1
+}
+
+// giftText removes the format marker, escapes and surrounding whitespace from
+// a piece of GIFT text
+func giftText(text string) string {
+	text = strings.TrimSpace(text)
+	for _, format := range []string{"[html]", "[moodle]", "[plain]", "[markdown]"} {
+		text = strings.TrimPrefix(text, format)
+	}
+	var builder strings.Builder
+	for i := 0; i < len(text); i++ {
+		if text[i] == '\\' && i+1 < len(text) && strings.IndexByte(giftEscapes, text[i+1]) != -1 {
+			i++ // Write the escaped character without the backslash
+		}
+		builder.WriteByte(text[i])
+	}
+	return strings.TrimSpace(builder.String())
+}
@@ -0,0 +1,144 @@
+package quiz
+
+import (
+	"backend/game"
+	"backend/tools"
+	"backend/upload"
+	"encoding/base64"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/http"
+	"strconv"
+	"strings"
+)
+
+// MaxQuizSize The maximum size in bytes of a quiz sent to the HTTP API. This is
+// larger than tools.MaxBodySize because quizzes can contain embedded images
+const MaxQuizSize = 16 * 1024 * 1024
+
+// HandleValidate HTTP handler for POST /api/quiz/validate. Validates the quiz in
+// the request body including its images without storing anything, responding
+// with every problem found if it isn't valid or the score bounds of the quiz
+func HandleValidate(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodPost { // Only accept POST requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	var body Quiz
+	if err := tools.ReadJsonLimit(writer, request, &body, MaxQuizSize); err != nil { // If the body isn't a quiz
+		tools.WriteError(writer, http.StatusBadRequest, "Invalid request body")
+		return
+	}
+	if !checkQuiz(writer, &body) {
+		return
+	}
+	duration := game.EstimateDuration(body.Questions)
+	warnings := body.DuplicateWarnings()
+	if game.IsTooLong(duration) { // If the quiz may not fit the time hosts have
+		warnings = append(warnings, fmt.Sprintf("quiz is expected to take longer than %d minutes", game.MaxDuration))
+	}
+	maxScore := game.MaxTotalScore(body.Questions, game.ScoreTimed)
+	if maxScore == game.MaxScore { // If players could reach the score limit
+		warnings = append(warnings, fmt.Sprintf("scores are limited to %d points", game.MaxScore))
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Questions int      `json:"questions"`          // The number of questions in the quiz
+		Duration  int64    `json:"duration"`           // The estimated length of a game of the quiz in milliseconds
+		MaxScore  uint32   `json:"maxScore"`           // The highest score a player can reach with timed scoring
+		ExamScore uint32   `json:"examScore"`          // The score a player reaches answering everything correctly in exam mode
+		Warnings  []string `json:"warnings,omitempty"` // Problems with the quiz that don't stop it being played
+	}{
+		Questions: len(body.Questions),
+		Duration:  duration.Milliseconds(),
+		MaxScore:  maxScore,
+		ExamScore: game.MaxTotalScore(body.Questions, game.ScoreExam),
+		Warnings:  warnings,
+	})
+}
+
+// HandleCreate HTTP handler for POST /api/quiz/json. Checks the quiz in the request
+// body the same as HandleValidate and keeps it as a complete upload so scripts
+// can create a quiz with a single JSON request. Images can be embedded as data
+// urls or referenced by url when remote images are enabled. Responds with the
+// upload id hosts start a game from, which can only be used once
+func HandleCreate(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodPost { // Only accept POST requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	var body Quiz
+	if err := tools.ReadJsonLimit(writer, request, &body, MaxQuizSize); err != nil { // If the body isn't a quiz
+		tools.WriteError(writer, http.StatusBadRequest, "Invalid request body")
+		return
+	}
+	if !checkQuiz(writer, &body) {
+		return
+	}
+	data, err := json.Marshal(body)
+	if err == nil && len(data) > upload.MaxUploadSize {
+		err = fmt.Errorf("quizzes must be smaller than %d bytes", upload.MaxUploadSize)
+	}
+	if err != nil {
+		tools.WriteError(writer, http.StatusRequestEntityTooLarge, err.Error())
+		return
+	}
+	session, err := upload.Store(data)
+	if err != nil { // Too many uploads are waiting to be used
+		tools.WriteError(writer, http.StatusServiceUnavailable, err.Error())
+		return
+	}
+	tools.WriteJson(writer, http.StatusCreated, struct {
+		Id   string `json:"id"`   // The upload id to send in the create game packet
+		Host string `json:"host"` // The path of the page that hosts a game of the quiz
+	}{Id: session.Id, Host: "/create?upload=" + session.Id})
+}
+
+// checkQuiz checks the quiz and its images responding with every problem found
+// if it isn't valid. Returns whether the quiz is valid
+func checkQuiz(writer http.ResponseWriter, body *Quiz) bool {
+	problems := body.ImageProblems()
+	var invalid *ValidationError
+	if err := body.Validate(); errors.As(err, &invalid) { // Problems with the questions come before the images
+		problems = append(invalid.Problems, problems...)
+	}
+	if len(problems) > 0 { // If the quiz has problems
+		invalid = &ValidationError{Problems: problems}
+		tools.WriteJson(writer, http.StatusBadRequest, struct {
+			Cause    string   `json:"cause"`    // The first problem with the quiz
+			Problems []string `json:"problems"` // Every problem with the quiz
+		}{Cause: invalid.Error(), Problems: invalid.Problems})
+		return false
+	}
+	return true
+}
+
+// HandleThumbnail HTTP handler for GET /api/quiz/{id}/{question}/thumb. Responds
+// with the thumbnail of the image of the question at the provided index in the
+// stored quiz with the provided id
+func HandleThumbnail(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodGet { // Only accept GET requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	parts := strings.Split(strings.TrimPrefix(request.URL.Path, "/api/quiz/"), "/")
+	if len(parts) != 3 || parts[2] != "thumb" { // Only thumbnails are served
+		tools.WriteError(writer, http.StatusNotFound, "Not found")
+		return
+	}
+	quiz := Stored.Get(parts[0])
+	index, err := strconv.Atoi(parts[1])
+	if quiz == nil || err != nil || index < 0 || index >= len(quiz.Questions) {
+		tools.WriteError(writer, http.StatusNotFound, "Unknown quiz or question")
+		return
+	}
+	_, encoded, _ := strings.Cut(quiz.Questions[index].Thumbnail, ";base64,")
+	data, err := base64.StdEncoding.DecodeString(encoded)
+	if err != nil || len(data) == 0 { // Questions without an image don't have a thumbnail
+		tools.WriteError(writer, http.StatusNotFound, "Question doesn't have a thumbnail")
+		return
+	}
+	writer.Header().Set("Content-Type", "image/jpeg")
+	writer.Header().Set("Cache-Control", "public, max-age=3600")
+	_, _ = writer.Write(data)
+}
@@ -0,0 +1,147 @@
+package quiz
+
+import (
+	"backend/media"
+	"backend/tools"
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// ImportProgress A single line of the NDJSON response to an import describing
+// the result of one line of the request or the final result of the import
+type ImportProgress struct {
+	Line     int      `json:"line,omitempty"`     // The line of the request this progress is for
+	Problems []string `json:"problems,omitempty"` // The problems with the question on the line
+	Done     bool     `json:"done,omitempty"`     // Whether this is the final result of the import
+	Id       string   `json:"id,omitempty"`       // The id of the imported quiz once it is stored
+	Cause    string   `json:"cause,omitempty"`    // Why the import failed if it did
+	Warnings []string `json:"warnings,omitempty"` // Problems with the imported quiz that don't stop it being played
+}
+
+// Save writes the quiz to a file in the library directory with the provided id
+// and loads it. The file is written to a temporary file first so the library
+// never loads a partially written quiz. Fails if another file already has the id
+func (library *Library) Save(id string, quiz *Quiz) error {
+	if library.Dir == "" {
+		return fmt.Errorf("no quiz directory configured")
+	}
+	data, err := json.Marshal(quiz)
+	if err != nil {
+		return err
+	}
+	path := filepath.Join(library.Dir, id+".quiz")
+	library.Lock.RLock()
+	existing, exists := library.Entries[id]
+	library.Lock.RUnlock()
+	if exists && existing.Path != path { // Replacing the quiz would leave two files with the same id
+		return fmt.Errorf("the id '%s' is already used by '%s'", id, filepath.Base(existing.Path))
+	}
+	temp := path + ".tmp"
+	if err = os.WriteFile(temp, data, 0644); err != nil {
+		return err
+	}
+	if err = os.Rename(temp, path); err != nil {
+		_ = os.Remove(temp)
+		return err
+	}
+	info, err := os.Stat(path)
+	if err != nil {
+		return err
+	}
+	library.load(id, path, info.ModTime())
+	if library.Get(id) == nil { // The quiz was validated but preparing it can still fail
+		return fmt.Errorf("failed to load the imported quiz")
+	}
+	return nil
+}
+
+// HandleImport HTTP handler for POST /api/admin/quiz/import?title=...&id=... Imports
+// questions sent as JSON Lines (one question per line) into the library. Each line
+// is validated as it is read so large quizzes are never decoded in one piece. The
+// progress of every line is sent back as JSON Lines ending with a line where done
+// is true. Nothing is stored if any line is invalid
+func HandleImport(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodPost { // Only accept POST requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	if Stored.Dir == "" { // Imported quizzes are stored in the quiz directory
+		tools.WriteError(writer, http.StatusNotFound, "No quiz directory configured")
+		return
+	}
+	title := strings.TrimSpace(request.URL.Query().Get("title"))
+	id := QuizId(request.URL.Query().Get("id"))
+	if id == "" {
+		id = QuizId(title)
+	}
+	// HTTP/1 request bodies can't be read after the response has started so the
+	// progress is written once the whole body has been read
+	var lines []ImportProgress
+	progress := func(value ImportProgress) { lines = append(lines, value) }
+	defer func() {
+		writer.Header().Set("Content-Type", "application/x-ndjson")
+		writer.WriteHeader(http.StatusOK)
+		encoder := json.NewEncoder(writer)
+		for _, value := range lines {
+			_ = encoder.Encode(value)
+		}
+	}()
+
+	quiz := &Quiz{Title: title}
+	invalid := false
+	scanner := bufio.NewScanner(request.Body)
+	scanner.Buffer(make([]byte, 64*1024), MaxQuizSize) // Lines can be as large as a whole quiz as they may contain images
+	line := 0
+	for scanner.Scan() {
+		line++
+		text := strings.TrimSpace(scanner.Text())
+		if text == "" { // Ignore blank lines
+			continue
+		}
+		if len(quiz.Questions) >= tools.MaxQuestions { // Stop reading once there are too many questions
+			progress(ImportProgress{Done: true, Cause: fmt.Sprintf("quizzes can't have more than %d questions", tools.MaxQuestions)})
+			return
+		}
+		var question tools.QuestionData
+		if err := json.Unmarshal([]byte(text), &question); err != nil {
+			progress(ImportProgress{Line: line, Problems: []string{"invalid question: " + err.Error()}})
+			invalid = true
+			continue
+		}
+		if problems := question.Problems(); len(problems) > 0 {
+			progress(ImportProgress{Line: line, Problems: problems})
+			invalid = true
+			continue
+		}
+		quiz.Questions = append(quiz.Questions, question)
+		progress(ImportProgress{Line: line})
+	}
+	if err := scanner.Err(); err != nil { // The body couldn't be read or a line was too large
+		progress(ImportProgress{Done: true, Cause: "failed to read questions: " + err.Error()})
+		return
+	}
+	if invalid {
+		progress(ImportProgress{Done: true, Cause: "some questions are invalid"})
+		return
+	}
+	if err := quiz.Validate(); err != nil { // Check the title and question count
+		progress(ImportProgress{Done: true, Cause: err.Error()})
+		return
+	}
+	ids, err := media.ResolveUploads(quiz.Questions) // Stored quizzes can't reference uploads as they expire
+	if err != nil {
+		progress(ImportProgress{Done: true, Cause: err.Error()})
+		return
+	}
+	media.Release(ids) // The images are now part of the quiz file
+	if err = Stored.Save(id, quiz); err != nil {
+		progress(ImportProgress{Done: true, Cause: err.Error()})
+		return
+	}
+	progress(ImportProgress{Done: true, Id: id, Warnings: quiz.DuplicateWarnings()})
+}
@@ -0,0 +1,196 @@
+package quiz
+
+import (
+	"backend/tools"
+	"log"
+	"os"
+	"path/filepath"
+	"strings"
+	"sync"
+	"time"
+)
+
+type (
+	// Library A structure for storing the quizzes loaded from a directory so that
+	// hosts can start games from them without uploading anything
+	Library struct {
+		Lock    *sync.RWMutex            // A lock for ensuring that writes are synchronized
+		Dir     string                   // The directory the quizzes are loaded from
+		Entries map[string]*LibraryEntry // The loaded quizzes mapped to their ids
+		skipped map[string]bool          // The paths of the files skipped because another file has the same id
+	}
+
+	// LibraryEntry A structure representing a quiz loaded from a file
+	LibraryEntry struct {
+		Quiz     *Quiz     // The loaded quiz or nil if the file failed to load
+		Path     string    // The path of the file the quiz was loaded from
+		Modified time.Time // The modification time of the file when it was loaded
+		Pinned   bool      // Whether the quiz was loaded on its own so scans of the directory keep it
+	}
+)
+
+// Stored The library of quizzes loaded from QUIZLER_QUIZ_DIR. The library is empty
+// if no directory is configured
+var Stored = NewLibrary(tools.EnvOrDefault("QUIZLER_QUIZ_DIR", ""))
+
+// NewLibrary Creates a new library for the quizzes in the provided directory
+func NewLibrary(dir string) *Library {
+	return &Library{
+		Lock:    &sync.RWMutex{},
+		Dir:     dir,
+		Entries: map[string]*LibraryEntry{},
+		skipped: map[string]bool{},
+	}
+}
+
+// QuizId creates a stable id for the quiz file at the provided path from its
+// name so that the id stays the same between restarts
+func QuizId(path string) string {
+	name := strings.ToLower(strings.TrimSuffix(filepath.Base(path), filepath.Ext(path)))
+	return strings.Map(func(r rune) rune {
+		if (r >= 'a' && r <= 'z') || (r >= '0' && r <= '9') {
+			return r
+		}
+		return '-'
+	}, name)
+}
+
+// Release gives back the references the quiz of the entry has to uploaded media
+func (entry *LibraryEntry) Release() {
+	if entry.Quiz != nil {
+		entry.Quiz.Release()
+	}
+}
+
+// Get retrieves the quiz with the provided id or nil if there isn't one
+func (library *Library) Get(id string) *Quiz {
+	library.Lock.RLock()         // Establish a read lock on the entries map
+	defer library.Lock.RUnlock() // Defer the releasing of the read lock
+	entry, exists := library.Entries[id]
+	if !exists {
+		return nil
+	}
+	return entry.Quiz
+}
+
+// Pin loads, validates and prepares the quiz file at the provided path keeping it
+// in the library until the server stops even though it isn't in the library
+// directory. Returns the id of the quiz
+func (library *Library) Pin(path string) (string, error) {
+	quiz, err := Load(path)
+	if err == nil {
+		err = quiz.Validate()
+	}
+	if err == nil {
+		err = quiz.Prepare()
+	}
+	if err != nil {
+		return "", err
+	}
+	id := QuizId(path)
+	library.Lock.Lock() // Establish write lock on the entries map
+	if existing, exists := library.Entries[id]; exists {
+		existing.Release()
+	}
+	library.Entries[id] = &LibraryEntry{Quiz: quiz, Path: path, Pinned: true}
+	library.Lock.Unlock() // Release write lock
+	return id, nil
+}
+
+// Watch schedules polling the library directory at the provided interval reloading
+// any quiz files that have changed and removing quizzes whose files were deleted.
+// Games that have already started keep the questions they were created with
+func (library *Library) Watch(interval time.Duration) {
+	if library.Dir == "" { // If there is nothing to watch
+		return
+	}
+	tools.Schedule(tools.Job{Name: "quiz library", Interval: interval, Run: func() error {
+		library.Scan()
+		return nil
+	}})
+}
+
+// Scan loads every .quiz, .json and .zip file in the library directory that is
+// new or has been modified since it was last loaded and removes the quizzes for
+// any files that no longer exist. Files that fail to load are logged and the quiz
+// is unavailable until the file is fixed. When files have the same id (e.g.
+// quiz.json and quiz.quiz) only the first by name is loaded and the others are
+// logged once
+func (library *Library) Scan() {
+	if library.Dir == "" { // If there is no directory configured
+		return
+	}
+	files, err := os.ReadDir(library.Dir)
+	if err != nil {
+		log.Printf("Failed to read quiz directory '%s': %s", library.Dir, err)
+		return
+	}
+	seen := map[string]string{}  // The paths of the files each id was loaded from
+	skipped := map[string]bool{} // The paths of the files skipped this scan
+	for _, file := range files { // Iterate over the files in the directory (sorted by name)
+		extension := filepath.Ext(file.Name())
+		if file.IsDir() || (extension != ".quiz" && extension != ".json" && extension != ".zip") {
+			continue
+		}
+		info, err := file.Info()
+		if err != nil {
+			continue
+		}
+		path := filepath.Join(library.Dir, file.Name())
+		id := QuizId(path)
+		library.Lock.RLock()
+		existing, exists := library.Entries[id]
+		library.Lock.RUnlock()
+		first, taken := seen[id]
+		if !taken && exists && existing.Pinned && existing.Path != path { // The id belongs to a quiz from the host command
+			first, taken = existing.Path, true
+		}
+		if taken { // Another file already has the id
+			skipped[path] = true
+			if !library.skipped[path] {
+				log.Printf("Skipped quiz '%s' its id '%s' is already used by '%s'", path, id, first)
+			}
+			continue
+		}
+		seen[id] = path
+		if exists && (existing.Pinned || (existing.Path == path && existing.Modified.Equal(info.ModTime()))) { // Already up-to-date
+			continue
+		}
+		library.load(id, path, info.ModTime())
+	}
+	library.Lock.Lock() // Establish write lock on the entries map
+	for id, entry := range library.Entries {
+		if _, exists := seen[id]; !exists && !entry.Pinned { // If the file for the quiz was removed
+			entry.Release()
+			delete(library.Entries, id)
+			log.Printf("Removed quiz '%s' its file '%s' no longer exists", id, entry.Path)
+		}
+	}
+	library.Lock.Unlock() // Release write lock
+	library.skipped = skipped
+}
+
+// load loads, validates and prepares the quiz at the provided path storing it
+// in the library under the provided id
+func (library *Library) load(id string, path string, modified time.Time) {
+	quiz, err := Load(path)
+	if err == nil {
+		err = quiz.Validate()
+	}
+	if err == nil {
+		err = quiz.Prepare()
+	}
+	if err != nil {
+		log.Printf("Failed to load quiz '%s': %s", path, err)
+		quiz = nil // Stale versions of the quiz shouldn't be used either
+	}
+	library.Lock.Lock() // Establish write lock on the entries map
+	if existing, exists := library.Entries[id]; exists {
+		existing.Release() // The previous version no longer uses its media
+	}
+	library.Entries[id] = &LibraryEntry{Quiz: quiz, Path: path, Modified: modified}
+	library.Lock.Unlock() // Release write lock
+	if quiz != nil {
+		log.Printf("Loaded quiz '%s' (%s) with %d questions", quiz.Title, id, len(quiz.Questions))
+	}
+}
@@ -0,0 +1,195 @@
+package quiz
+
+import (
+	"backend/media"
+	"backend/speech"
+	"backend/tools"
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+)
+
+// MaxTitleLength The maximum length of a quiz title which matches the limit the
+// frontend editor applies
+const MaxTitleLength = 30
+
+// Quiz A structure representing a quiz file. This is the same format that the
+// editor imports and exports as .quiz files
+type Quiz struct {
+	tools.TextStyle                      // How the text of the quiz should be shown
+	Title           string               `json:"title"`     // The title of the quiz
+	Questions       []tools.QuestionData `json:"questions"` // The questions in the quiz
+	Media           []string             `json:"-"`         // The ids of the uploaded media the quiz references
+}
+
+// Load reads and parses the quiz JSON file at the provided path. Zip files are
+// loaded using LoadZip
+func Load(path string) (*Quiz, error) {
+	if filepath.Ext(path) == ".zip" {
+		return LoadZip(path)
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	var quiz Quiz
+	if err = json.Unmarshal(data, &quiz); err != nil {
+		return nil, fmt.Errorf("invalid quiz file: %w", err)
+	}
+	return &quiz, nil
+}
+
+// ValidationError A structure describing every problem found while validating
+// a quiz. Each problem starts with the path of the value it applies to
+// (e.g. questions[12].answers[3] must not be empty)
+type ValidationError struct {
+	Problems []string `json:"problems"` // The problems with the quiz
+}
+
+// Error describes the first problem and how many others there are
+func (err *ValidationError) Error() string {
+	if len(err.Problems) == 1 {
+		return err.Problems[0]
+	}
+	return fmt.Sprintf("%s (and %d more problems)", err.Problems[0], len(err.Problems)-1)
+}
+
+// Validate checks that the quiz has a title, a valid text style and between one
+// and MaxQuestions questions which are all valid. Returns a ValidationError
+// listing every problem
+func (quiz *Quiz) Validate() error {
+	var problems []string
+	if len(quiz.Title) == 0 || len(quiz.Title) > MaxTitleLength {
+		problems = append(problems, fmt.Sprintf("title must be between 1 and %d characters", MaxTitleLength))
+	}
+	if len(quiz.Questions) == 0 || len(quiz.Questions) > tools.MaxQuestions {
+		problems = append(problems, fmt.Sprintf("questions must have between 1 and %d questions", tools.MaxQuestions))
+	}
+	problems = append(problems, quiz.TextStyle.Problems()...)
+	for i := range quiz.Questions {
+		for _, problem := range quiz.Questions[i].Problems() {
+			problems = append(problems, fmt.Sprintf("questions[%d].%s", i, problem))
+		}
+	}
+	if len(problems) > 0 {
+		return &ValidationError{Problems: problems}
+	}
+	return nil
+}
+
+// MinDuplicateAnswers The fewest answers a question needs before another question
+// with the same answers is reported as a duplicate. Questions with fewer answers
+// such as true or false questions often share their answers on purpose
+const MinDuplicateAnswers = 3
+
+// normalizeText lowercases the text and collapses its whitespace so that text
+// which only differs by case or spacing is treated as the same
+func normalizeText(text string) string {
+	return strings.Join(strings.Fields(strings.ToLower(text)), " ")
+}
+
+// DuplicateWarnings finds questions which have the same text or the same set of
+// answers as an earlier question, which often happens by accident when quizzes
+// are merged. Duplicates don't stop the quiz from being played so each one is
+// returned as a warning naming both questions
+func (quiz *Quiz) DuplicateWarnings() []string {
+	var warnings []string
+	texts := map[string]int{}   // The index of the first question with each text
+	answers := map[string]int{} // The index of the first question with each set of answers
+	for i, question := range quiz.Questions {
+		text := normalizeText(question.Question)
+		if first, exists := texts[text]; exists {
+			warnings = append(warnings, fmt.Sprintf("questions[%d] has the same question as questions[%d]", i, first))
+		} else {
+			texts[text] = i
+		}
+		if len(question.Answers) < MinDuplicateAnswers {
+			continue
+		}
+		set := make([]string, len(question.Answers))
+		for j, answer := range question.Answers {
+			set[j] = normalizeText(answer)
+		}
+		sort.Strings(set) // The same answers in a different order are still the same set
+		key := strings.Join(set, "\x00")
+		if first, exists := answers[key]; exists {
+			warnings = append(warnings, fmt.Sprintf("questions[%d] has the same answers as questions[%d]", i, first))
+		} else {
+			answers[key] = i
+		}
+	}
+	return warnings
+}
+
+// ImageProblems checks the question images without loading or storing anything.
+// Uploaded images must still exist, urls can only be used when remote images are
+// enabled and embedded images must pass media.CheckImage. Returns every problem
+// in the same form as a ValidationError
+func (quiz *Quiz) ImageProblems() []string {
+	var problems []string
+	for i, question := range quiz.Questions { // Iterate over the questions
+		image := question.Image
+		switch {
+		case image == "":
+			continue
+		case strings.HasPrefix(image, media.MediaPrefix):
+			if media.GetStored(strings.TrimPrefix(image, media.MediaPrefix)) == nil {
+				problems = append(problems, fmt.Sprintf("questions[%d].image was uploaded but no longer exists", i))
+			}
+		case media.IsRemote(image):
+			if !media.RemoteImages { // Remote images are only loaded once a game is created
+				problems = append(problems, fmt.Sprintf("questions[%d].image uses a url but remote images are disabled", i))
+			}
+		default:
+			if _, err := media.CheckImage(image); err != nil {
+				problems = append(problems, fmt.Sprintf("questions[%d].image %s", i, err))
+			}
+		}
+	}
+	return problems
+}
+
+// Prepare loads any images that were uploaded separately or referenced by url,
+// checks the image types, generates the image thumbnails and generates the
+// question audio when a speech provider is configured. Any audio that didn't come
+// from the server is removed. The quiz keeps a reference to the uploaded images
+// until Release is called
+func (quiz *Quiz) Prepare() error {
+	ids, err := media.ResolveUploads(quiz.Questions) // Load any images uploaded to /api/media
+	if err != nil {
+		return err
+	}
+	if err = media.FetchRemoteImages(quiz.Questions); err == nil { // Load any images referenced by url
+		err = media.CheckImages(quiz.Questions) // Reject vector and unknown image types
+	}
+	if err != nil {
+		media.Release(ids)
+		return err
+	}
+	quiz.Media = ids
+	media.GenerateThumbnails(quiz.Questions) // Create the small versions of the images
+	if speech.Enabled() {                    // If question audio should be generated
+		speech.Pregenerate(quiz.Questions)
+	} else {
+		for i := range quiz.Questions { // Audio is only ever generated by the server
+			quiz.Questions[i].Audio = ""
+		}
+	}
+	return nil
+}
+
+// Release gives back the references the quiz has to uploaded media so it can
+// expire once nothing else uses it
+func (quiz *Quiz) Release() {
+	media.Release(quiz.Media)
+	quiz.Media = nil
+}
+
+// CopyQuestions creates a copy of the questions so that a game can modify
+// them without changing the quiz
+func (quiz *Quiz) CopyQuestions() []tools.QuestionData {
+	return append([]tools.QuestionData(nil), quiz.Questions...)
+}
@@ -0,0 +1,254 @@
+package quiz
+
+import (
+	"archive/zip"
+	"backend/tools"
+	"backend/upload"
+	"encoding/base64"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+// FuzzQuizJson checks that decoding and validating any quiz file never panics
+// and that quizzes which pass validation are within the limits
+func FuzzQuizJson(f *testing.F) {
+	f.Add([]byte(`{"title":"Quiz","questions":[{"question":"Q","answers":["A","B"],"values":[0]}]}`))
+	f.Add([]byte(`{"title":"Quiz","questions":[{"question":"Q","answers":["A","B"],"values":[9223372036854775807]}]}`))
+	f.Add([]byte(`{"title":"Quiz","questions":[{"question":"Q","answers":[],"values":[-1]}]}`))
+	f.Add([]byte(`{"title":"\xff\xfe","questions":[[[[[[[[[[]]]]]]]]]]}`))
+	f.Add([]byte(`{"title":"Quiz","questions":[{"values":[1e400]}]}`))
+	f.Fuzz(func(t *testing.T, data []byte) {
+		var quiz Quiz
+		if err := json.Unmarshal(data, &quiz); err != nil {
+			return
+		}
+		if quiz.Validate() != nil {
+			return
+		}
+		if len(quiz.Questions) > tools.MaxQuestions {
+			t.Fatalf("valid quiz has %d questions", len(quiz.Questions))
+		}
+		for _, question := range quiz.Questions {
+			for _, value := range question.Values {
+				if value < 0 || value >= len(question.Answers) {
+					t.Fatalf("valid question has out of range answer %d", value)
+				}
+			}
+		}
+	})
+}
+
+// FuzzParseGift checks that parsing any GIFT text never panics and that every
+// question it produces is valid
+func FuzzParseGift(f *testing.F) {
+	f.Add("::Title::What is 2+2? {=4 ~3 ~5}")
+	f.Add("Is the sky blue? {T}")
+	f.Add("Pick one {~%50%A ~%-50%B #feedback =C}")
+	f.Add("Escaped \\{ \\} {=\\= ~\\~ ~\\")
+	f.Add("Match {=a -> b =c -> d}")
+	f.Add("{}")
+	f.Fuzz(func(t *testing.T, text string) {
+		quiz, err := ParseGift("Quiz", text)
+		if err != nil {
+			return
+		}
+		for i := range quiz.Questions {
+			if !quiz.Questions[i].IsValid() {
+				t.Fatalf("question %d is not valid", i+1)
+			}
+		}
+	})
+}
+
+// TestImageProblems checks that every problem with the question images is found
+// without loading or storing any of them
+func TestImageProblems(t *testing.T) {
+	quiz := Quiz{Title: "Quiz", Questions: []tools.QuestionData{
+		{Image: ""},
+		{Image: "media:missing"},
+		{Image: "data:image/svg+xml;base64,PHN2Zy8+"},
+		{Image: "data:image/png;base64,R0lGODlhAQABAAAAACw="},
+	}}
+	problems := quiz.ImageProblems()
+	if len(problems) != 3 {
+		t.Fatalf("expected 3 problems got %q", problems)
+	}
+	for i, problem := range problems {
+		if prefix := fmt.Sprintf("questions[%d].image ", i+1); !strings.HasPrefix(problem, prefix) {
+			t.Errorf("expected %q to start with %q", problem, prefix)
+		}
+	}
+}
+
+// TestDuplicateWarnings checks that questions with the same text or the same set
+// of answers are reported while short answer sets like true or false aren't
+func TestDuplicateWarnings(t *testing.T) {
+	quiz := Quiz{Title: "Quiz", Questions: []tools.QuestionData{
+		{Question: "What is 2+2?", Answers: []string{"3", "4", "5"}},
+		{Question: "what is  2+2? ", Answers: []string{"1", "2"}},
+		{Question: "What is 1+3?", Answers: []string{"5", "4", "3"}},
+		{Question: "Is the sky blue?", Answers: []string{"True", "False"}},
+		{Question: "Is grass green?", Answers: []string{"True", "False"}},
+	}}
+	warnings := quiz.DuplicateWarnings()
+	expected := []string{
+		"questions[1] has the same question as questions[0]",
+		"questions[2] has the same answers as questions[0]",
+	}
+	if strings.Join(warnings, "\n") != strings.Join(expected, "\n") {
+		t.Errorf("expected %q got %q", expected, warnings)
+	}
+}
+
+// TestTextStyle checks that invalid text styles are rejected and that the text
+// direction is chosen from the locale when the quiz doesn't set one
+func TestTextStyle(t *testing.T) {
+	questions := []tools.QuestionData{{Question: "Q", Answers: []string{"A", "B"}, Values: []int{0}}}
+	tests := []struct {
+		style tools.TextStyle
+		valid bool
+	}{
+		{tools.TextStyle{}, true},
+		{tools.TextStyle{Locale: "ar", Font: "Noto Naskh Arabic"}, true},
+		{tools.TextStyle{Locale: "arabic!"}, false},
+		{tools.TextStyle{Direction: "up"}, false},
+		{tools.TextStyle{Font: "a;}body{"}, false},
+		{tools.TextStyle{Font: strings.Repeat("a", tools.MaxFontLength+1)}, false},
+	}
+	for _, test := range tests {
+		quiz := Quiz{TextStyle: test.style, Title: "Quiz", Questions: questions}
+		if err := quiz.Validate(); (err == nil) != test.valid {
+			t.Errorf("%+v: got error %v want valid %t", test.style, err, test.valid)
+		}
+	}
+	style := tools.TextStyle{Locale: "he-IL", Font: "David"}
+	if original := style.For(""); original.Direction != tools.DirectionRTL || original.Font != "David" {
+		t.Errorf("expected the original text to be rtl with the font got %+v", original)
+	}
+	if translated := style.For("en"); translated.Direction != tools.DirectionLTR || translated.Font != "" {
+		t.Errorf("expected a translation to be ltr without the font got %+v", translated)
+	}
+}
+
+// TestHostNotes checks that questions can only be marked with a known difficulty
+// and that long host notes are rejected
+func TestHostNotes(t *testing.T) {
+	tests := []struct {
+		difficulty string
+		notes      string
+		valid      bool
+	}{
+		{"", "", true},
+		{tools.DifficultyHard, "Read the answers slowly", true},
+		{"impossible", "", false},
+		{tools.DifficultyEasy, strings.Repeat("a", tools.MaxNotes+1), false},
+	}
+	for _, test := range tests {
+		question := tools.QuestionData{Question: "Q", Answers: []string{"A", "B"}, Values: []int{0}}
+		question.Difficulty = test.difficulty
+		question.Notes = test.notes
+		quiz := Quiz{Title: "Quiz", Questions: []tools.QuestionData{question}}
+		if err := quiz.Validate(); (err == nil) != test.valid {
+			t.Errorf("%q: got error %v want valid %t", test.difficulty, err, test.valid)
+		}
+	}
+}
+
+// libraryQuiz A valid quiz file for the library tests
+const libraryQuiz = `{"title":"Quiz","questions":[{"question":"Q","answers":["A","B"],"values":[0]}]}`
+
+// TestLibraryCollisions checks that files with the same id only load the first
+// file by name and that scanning again doesn't reload either of them
+func TestLibraryCollisions(t *testing.T) {
+	dir := t.TempDir()
+	for _, name := range []string{"My Quiz.json", "my-quiz.quiz", "quiz.json", "quiz.quiz"} {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(libraryQuiz), 0644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	library := NewLibrary(dir)
+	library.Scan()
+	if len(library.Entries) != 2 {
+		t.Fatalf("expected 2 quizzes got %d", len(library.Entries))
+	}
+	first := map[string]*LibraryEntry{}
+	for id, name := range map[string]string{"my-quiz": "My Quiz.json", "quiz": "quiz.json"} {
+		entry := library.Entries[id]
+		if entry == nil || filepath.Base(entry.Path) != name {
+			t.Fatalf("expected '%s' to be loaded from '%s' got %+v", id, name, entry)
+		}
+		first[id] = entry
+	}
+	library.Scan()
+	for id, entry := range first {
+		if library.Entries[id] != entry {
+			t.Errorf("expected '%s' not to be reloaded", id)
+		}
+	}
+	if err := library.Save("quiz", &Quiz{Title: "Quiz"}); err == nil {
+		t.Error("expected saving over an id used by another file to fail")
+	}
+}
+
+// TestLoadZip checks that a quiz in a zip is loaded with the images it names
+// embedded from the zip
+func TestLoadZip(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "quiz.zip")
+	file, err := os.Create(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	archive := zip.NewWriter(file)
+	image, _ := base64.StdEncoding.DecodeString("R0lGODlhAQABAAAAACw=")
+	for name, contents := range map[string][]byte{
+		"quiz.json":       []byte(`{"title":"Quiz","questions":[{"question":"Q","answers":["A","B"],"values":[0],"image":"images/1.gif"}]}`),
+		"images/1.gif":    image,
+		"images/notes.md": []byte("Not a quiz"),
+	} {
+		writer, _ := archive.Create(name)
+		_, _ = writer.Write(contents)
+	}
+	_ = archive.Close()
+	_ = file.Close()
+	quiz, err := Load(path)
+	if err != nil {
+		t.Fatalf("expected the zip to load got %s", err)
+	}
+	if expected := "data:image/gif;base64,R0lGODlhAQABAAAAACw="; quiz.Questions[0].Image != expected {
+		t.Errorf("expected the image to be embedded got %q", quiz.Questions[0].Image)
+	}
+}
+
+// TestHandleCreate checks that a valid quiz sent as JSON is kept as an upload
+// that a game can be created from and that invalid quizzes list their problems
+func TestHandleCreate(t *testing.T) {
+	upload.Dir = t.TempDir()
+	recorder := httptest.NewRecorder()
+	HandleCreate(recorder, httptest.NewRequest(http.MethodPost, "/api/quiz/json", strings.NewReader(libraryQuiz)))
+	var created struct {
+		Id   string `json:"id"`
+		Host string `json:"host"`
+	}
+	if err := json.NewDecoder(recorder.Body).Decode(&created); err != nil || recorder.Code != http.StatusCreated {
+		t.Fatalf("expected the quiz to be created got %d (%v)", recorder.Code, err)
+	}
+	data, err := upload.Take(created.Id)
+	if err != nil || created.Host != "/create?upload="+created.Id {
+		t.Fatalf("expected an upload to host got %+v (%v)", created, err)
+	}
+	var quiz Quiz
+	if err = json.Unmarshal(data, &quiz); err != nil || quiz.Title != "Quiz" || len(quiz.Questions) != 1 {
+		t.Errorf("expected the upload to hold the quiz got %s", data)
+	}
+	recorder = httptest.NewRecorder()
+	HandleCreate(recorder, httptest.NewRequest(http.MethodPost, "/api/quiz/json", strings.NewReader(`{"title":"","questions":[]}`)))
+	if recorder.Code != http.StatusBadRequest || !strings.Contains(recorder.Body.String(), "problems") {
+		t.Errorf("expected the invalid quiz to be rejected got %d %s", recorder.Code, recorder.Body)
+	}
+}
@@ -0,0 +1,81 @@
+package quiz
+
+import (
+	"archive/zip"
+	"backend/media"
+	"encoding/base64"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"path"
+)
+
+// LoadZip reads a quiz bundled in a zip file along with its images. The zip must
+// hold a single .quiz or .json file. Question images that name another file in
+// the zip (e.g. images/1.png) are embedded as data urls so the quiz is checked
+// and prepared like any other quiz
+func LoadZip(file string) (*Quiz, error) {
+	archive, err := zip.OpenReader(file)
+	if err != nil {
+		return nil, err
+	}
+	defer archive.Close()
+	var quizFile *zip.File
+	files := map[string]*zip.File{} // The other files in the zip mapped to their names
+	for _, entry := range archive.File {
+		if entry.FileInfo().IsDir() {
+			continue
+		}
+		if extension := path.Ext(entry.Name); extension == ".quiz" || extension == ".json" {
+			if quizFile != nil {
+				return nil, errors.New("zip contains more than one quiz file")
+			}
+			quizFile = entry
+		} else {
+			files[path.Clean(entry.Name)] = entry
+		}
+	}
+	if quizFile == nil {
+		return nil, errors.New("zip doesn't contain a .quiz or .json file")
+	}
+	data, err := readZipFile(quizFile, MaxQuizSize)
+	if err != nil {
+		return nil, err
+	}
+	var quiz Quiz
+	if err = json.Unmarshal(data, &quiz); err != nil {
+		return nil, fmt.Errorf("invalid quiz file: %w", err)
+	}
+	for i := range quiz.Questions { // Embed the images that are files in the zip
+		image, exists := files[path.Clean(quiz.Questions[i].Image)]
+		if quiz.Questions[i].Image == "" || !exists {
+			continue
+		}
+		contents, err := readZipFile(image, media.MaxImageSize)
+		if err != nil {
+			return nil, fmt.Errorf("questions[%d].image %w", i, err)
+		}
+		quiz.Questions[i].Image = "data:" + http.DetectContentType(contents) + ";base64," + base64.StdEncoding.EncodeToString(contents)
+	}
+	return &quiz, nil
+}
+
+// readZipFile reads the contents of the file in a zip failing if it is larger
+// than the provided limit rather than trusting the size the zip declares
+func readZipFile(file *zip.File, limit int) ([]byte, error) {
+	reader, err := file.Open()
+	if err != nil {
+		return nil, err
+	}
+	defer reader.Close()
+	data, err := io.ReadAll(io.LimitReader(reader, int64(limit)+1))
+	if err != nil {
+		return nil, err
+	}
+	if len(data) > limit {
+		return nil, fmt.Errorf("%s is larger than %d bytes", file.Name, limit)
+	}
+	return data, nil
+}
@@ -0,0 +1,68 @@
+package main
+
+import (
+	"backend/tools"
+	"context"
+	"log"
+	"net"
+	"net/http"
+	"time"
+)
+
+// Configuration for tuning the HTTP server for games with many players
+var (
+	TcpKeepAlive      = tools.EnvIntOrDefault("QUIZLER_TCP_KEEPALIVE", 15)             // Seconds between TCP keep-alive probes (negative to disable)
+	HttpKeepAlive     = tools.EnvOrDefault("QUIZLER_HTTP_KEEPALIVE", "true") == "true" // Whether HTTP connections are reused between requests
+	IdleTimeout       = tools.EnvIntOrDefault("QUIZLER_IDLE_TIMEOUT", 120)             // Seconds an idle keep-alive connection is kept open
+	ReadHeaderTimeout = tools.EnvIntOrDefault("QUIZLER_READ_HEADER_TIMEOUT", 10)       // Seconds a client has to send the request headers
+)
+
+// Listen binds to each of the provided addresses on the port and serves HTTP
+// requests on all of them. IPv4 and IPv6 addresses are bound separately so
+// that 0.0.0.0 and :: can be listened on at the same time. Blocks until one
+// of the listeners fails. No addresses listens on all addresses of both
+func Listen(addresses []string, port string) error {
+	if len(addresses) == 0 {
+		addresses = []string{""}
+	}
+	config := net.ListenConfig{KeepAlive: time.Duration(TcpKeepAlive) * time.Second}
+	server := &http.Server{
+		IdleTimeout:       time.Duration(IdleTimeout) * time.Second,
+		ReadHeaderTimeout: time.Duration(ReadHeaderTimeout) * time.Second,
+	}
+	server.SetKeepAlivesEnabled(HttpKeepAlive)
+
+	listeners := make([]net.Listener, 0, len(addresses))
+	for _, address := range addresses { // Bind all the addresses before serving any
+		listener, err := config.Listen(context.Background(), listenNetwork(address), net.JoinHostPort(address, port))
+		if err != nil {
+			for _, bound := range listeners {
+				_ = bound.Close()
+			}
+			return err
+		}
+		log.Printf("Listening on %s", listener.Addr())
+		listeners = append(listeners, listener)
+	}
+	errs := make(chan error, len(listeners))
+	for _, listener := range listeners {
+		go func(listener net.Listener) {
+			errs <- server.Serve(listener)
+		}(listener)
+	}
+	return <-errs
+}
+
+// listenNetwork returns the network to listen on for the address. IPv6 addresses
+// use tcp6 so that wildcard addresses only accept IPv6 connections and don't
+// conflict with an IPv4 wildcard on the same port
+func listenNetwork(address string) string {
+	ip := net.ParseIP(address)
+	if ip == nil { // Host names are left to resolve to either
+		return "tcp"
+	}
+	if ip.To4() != nil {
+		return "tcp4"
+	}
+	return "tcp6"
+}
@@ -0,0 +1,105 @@
+package speech
+
+import (
+	"backend/tools"
+	"bytes"
+	"context"
+	"encoding/base64"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Configuration for the OpenAI compatible text-to-speech provider used to
+// pre-generate question audio. Audio is disabled unless QUIZLER_SPEECH_URL is set
+var (
+	Url   = strings.TrimSuffix(tools.EnvOrDefault("QUIZLER_SPEECH_URL", ""), "/") // The base url of the provider API
+	Key   = tools.EnvOrDefault("QUIZLER_SPEECH_KEY", "")                          // The API key sent as a bearer token
+	Model = tools.EnvOrDefault("QUIZLER_SPEECH_MODEL", "tts-1")                   // The speech model to use
+	Voice = tools.EnvOrDefault("QUIZLER_SPEECH_VOICE", "alloy")                   // The voice to speak with
+)
+
+// Limits for speech generation
+const (
+	RequestTimeout = 30 * time.Second // The maximum time to wait for the provider to respond
+	MaxAudioSize   = 2 * 1024 * 1024  // The maximum size in bytes of the audio for a single question
+	MaxConcurrent  = 4                // The maximum number of questions to generate audio for at once
+)
+
+// A client for sending requests to the provider
+var client = &http.Client{Timeout: RequestTimeout}
+
+// Enabled returns whether a provider has been configured and the server isn't offline
+func Enabled() bool {
+	return Url != "" && !tools.Offline
+}
+
+// Pregenerate generates the audio for each of the provided questions storing it
+// on the question as a data url. Questions that fail to generate are logged and
+// left without audio so the quiz can still be played
+func Pregenerate(questions []tools.QuestionData) {
+	var group sync.WaitGroup
+	slots := make(chan struct{}, MaxConcurrent) // Limits the number of concurrent requests
+	for i := range questions {                  // Iterate over the questions
+		question := &questions[i]
+		question.Audio = "" // Audio is only ever generated by the server
+		group.Add(1)
+		go func() {
+			defer group.Done()
+			slots <- struct{}{}
+			defer func() { <-slots }()
+			audio, err := Synthesize(context.Background(), question.Question)
+			if err != nil {
+				log.Printf("Failed to generate audio for question '%s': %s", question.Question, err)
+				return
+			}
+			question.Audio = audio
+		}()
+	}
+	group.Wait()
+}
+
+// Synthesize asks the provider to speak the provided text and returns the
+// resulting audio encoded as a data url
+func Synthesize(ctx context.Context, text string) (string, error) {
+	body, err := json.Marshal(struct {
+		Model  string `json:"model"`
+		Input  string `json:"input"`
+		Voice  string `json:"voice"`
+		Format string `json:"response_format"`
+	}{Model: Model, Input: text, Voice: Voice, Format: "mp3"})
+	if err != nil {
+		return "", err
+	}
+	request, err := http.NewRequestWithContext(ctx, http.MethodPost, Url+"/audio/speech", bytes.NewReader(body))
+	if err != nil {
+		return "", err
+	}
+	request.Header.Set("Content-Type", "application/json")
+	if Key != "" { // Only send the authorization if a key is configured
+		request.Header.Set("Authorization", "Bearer "+Key)
+	}
+	response, err := client.Do(request)
+	if err != nil {
+		return "", err
+	}
+	defer response.Body.Close()
+	if response.StatusCode != http.StatusOK {
+		return "", fmt.Errorf("provider responded with status %d", response.StatusCode)
+	}
+	// Read one byte more than the limit so oversized audio can be detected
+	audio, err := io.ReadAll(io.LimitReader(response.Body, MaxAudioSize+1))
+	if err != nil {
+		return "", err
+	}
+	if len(audio) > MaxAudioSize {
+		return "", errors.New("provider responded with audio that was too large")
+	}
+	return "data:audio/mpeg;base64," + base64.StdEncoding.EncodeToString(audio), nil
+}
@@ -0,0 +1,204 @@
+package stats
+
+import (
+	"backend/tools"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"os"
+	"strconv"
+	"sync"
+	"time"
+)
+
+// Configuration for the usage statistics. Statistics are only collected when
+// QUIZLER_STATS_FILE is set
+var (
+	File = tools.EnvOrDefault("QUIZLER_STATS_FILE", "")    // The JSON file the daily statistics are stored in
+	Days = tools.EnvIntOrDefault("QUIZLER_STATS_DAYS", 90) // The number of days of statistics that are kept
+)
+
+// The periods statistics can be rolled up into
+const (
+	PeriodDay  = "day"  // The statistics for each day
+	PeriodWeek = "week" // The statistics for each week starting on Monday
+)
+
+// Constants for reporting the statistics
+const (
+	DateLayout     = "2006-01-02" // The layout of the dates the statistics are grouped by
+	MaxReportLimit = 366          // The most periods returned by a single request
+)
+
+// The names of the question kinds used in the statistics
+var kindNames = map[tools.QuestionKind]string{
+	tools.KindChoice: "choice",
+	tools.KindBuzzer: "buzzer",
+	tools.KindPoll:   "poll",
+	tools.KindWords:  "words",
+	tools.KindOrder:  "order",
+	tools.KindNumber: "number",
+	tools.KindBlanks: "blanks",
+}
+
+// Rollup The usage of the server over a single period
+type Rollup struct {
+	Date          string         `json:"date"`                  // The first day of the period (UTC)
+	Games         int            `json:"games"`                 // The number of games that were started
+	Players       int            `json:"players"`               // The number of players in the started games
+	UniquePlayers int            `json:"uniquePlayers"`         // The number of different devices that played each day added together
+	AverageSize   float64        `json:"averageSize,omitempty"` // The average number of players in each game
+	QuestionKinds map[string]int `json:"questionKinds"`         // The number of questions of each kind in the started games
+}
+
+var (
+	lock    = sync.Mutex{}      // A lock for modifying the statistics
+	days    []Rollup            // The statistics for each day oldest first
+	devices = map[string]bool{} // The hashed devices that have played today
+)
+
+// Enabled returns whether a statistics file has been configured
+func Enabled() bool {
+	return File != ""
+}
+
+// Load reads the statistics stored in the File. A missing file starts with no
+// statistics
+func Load() error {
+	data, err := os.ReadFile(File)
+	if errors.Is(err, os.ErrNotExist) {
+		return nil
+	}
+	if err != nil {
+		return err
+	}
+	lock.Lock()         // Establish lock on the statistics
+	defer lock.Unlock() // Defer the releasing of the lock
+	return json.Unmarshal(data, &days)
+}
+
+// RecordGame adds a started game to the statistics for today. The devices are
+// only kept hashed and in memory to count the unique players of the day
+func RecordGame(questions []tools.QuestionData, players []string) error {
+	lock.Lock()         // Establish lock on the statistics
+	defer lock.Unlock() // Defer the releasing of the lock
+	day := today()
+	day.Games++
+	day.Players += len(players)
+	for _, player := range players {
+		hash := sha256.Sum256([]byte(day.Date + player))
+		key := hex.EncodeToString(hash[:])
+		if !devices[key] {
+			devices[key] = true
+			day.UniquePlayers++
+		}
+	}
+	for _, question := range questions {
+		day.QuestionKinds[kindNames[question.Kind]]++
+	}
+	return save()
+}
+
+// today finds the statistics for the current day adding them if they don't
+// exist yet and removing days older than Days. Must be called with the lock held
+func today() *Rollup {
+	date := time.Now().UTC().Format(DateLayout)
+	if len(days) > 0 && days[len(days)-1].Date == date {
+		return &days[len(days)-1]
+	}
+	devices = map[string]bool{} // Devices are only counted once each day
+	days = append(days, Rollup{Date: date, QuestionKinds: map[string]int{}})
+	if overflow := len(days) - Days; Days > 0 && overflow > 0 {
+		days = append(days[:0:0], days[overflow:]...)
+	}
+	return &days[len(days)-1]
+}
+
+// save writes the statistics to the File through a temporary file so a partially
+// written file is never loaded. Must be called with the lock held
+func save() error {
+	data, err := json.Marshal(days)
+	if err != nil {
+		return err
+	}
+	temp := File + ".tmp"
+	if err = os.WriteFile(temp, data, 0644); err != nil {
+		return err
+	}
+	if err = os.Rename(temp, File); err != nil {
+		_ = os.Remove(temp)
+		return err
+	}
+	return nil
+}
+
+// Report rolls the statistics up into the provided period returning up to limit
+// periods newest first
+func Report(period string, limit int) []Rollup {
+	lock.Lock()         // Establish lock on the statistics
+	defer lock.Unlock() // Defer the releasing of the lock
+	var rollups []Rollup
+	for i := len(days) - 1; i >= 0; i-- { // Iterate from newest to oldest
+		day := days[i]
+		date := day.Date
+		if period == PeriodWeek {
+			date = weekStart(date)
+		}
+		if len(rollups) == 0 || rollups[len(rollups)-1].Date != date {
+			if len(rollups) == limit {
+				break
+			}
+			rollups = append(rollups, Rollup{Date: date, QuestionKinds: map[string]int{}})
+		}
+		rollup := &rollups[len(rollups)-1]
+		rollup.Games += day.Games
+		rollup.Players += day.Players
+		rollup.UniquePlayers += day.UniquePlayers
+		for kind, count := range day.QuestionKinds {
+			rollup.QuestionKinds[kind] += count
+		}
+	}
+	for i := range rollups {
+		if rollups[i].Games > 0 {
+			rollups[i].AverageSize = float64(rollups[i].Players) / float64(rollups[i].Games)
+		}
+	}
+	return rollups
+}
+
+// weekStart finds the Monday of the week the provided date is in
+func weekStart(date string) string {
+	day, err := time.Parse(DateLayout, date)
+	if err != nil {
+		return date
+	}
+	offset := (int(day.Weekday()) + 6) % 7 // The number of days since Monday
+	return day.AddDate(0, 0, -offset).Format(DateLayout)
+}
+
+// HandleStats HTTP handler for GET /api/admin/stats?period=day|week&limit=N.
+// Responds with the usage statistics newest first
+func HandleStats(writer http.ResponseWriter, request *http.Request) {
+	if request.Method != http.MethodGet { // Only accept GET requests
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+		return
+	}
+	query := request.URL.Query()
+	period := query.Get("period")
+	if period == "" {
+		period = PeriodDay
+	} else if period != PeriodDay && period != PeriodWeek {
+		tools.WriteError(writer, http.StatusBadRequest, "Period must be day or week")
+		return
+	}
+	limit, err := strconv.Atoi(query.Get("limit"))
+	if err != nil || limit < 1 || limit > MaxReportLimit { // Use the maximum for missing or invalid limits
+		limit = MaxReportLimit
+	}
+	tools.WriteJson(writer, http.StatusOK, struct {
+		Period  string   `json:"period"`  // The period the statistics are rolled up into
+		Rollups []Rollup `json:"rollups"` // The statistics for each period newest first
+	}{Period: period, Rollups: Report(period, limit)})
+}
@@ -0,0 +1,39 @@
+package stats
+
+import (
+	"backend/tools"
+	"path/filepath"
+	"testing"
+)
+
+// TestReport checks that days are rolled up into the weeks they start on and
+// that the same device is only counted once each day
+func TestReport(t *testing.T) {
+	defer func(previous string) { File = previous }(File)
+	File = filepath.Join(t.TempDir(), "stats.json")
+	days = []Rollup{
+		{Date: "2020-01-06", Games: 1, Players: 4, UniquePlayers: 4, QuestionKinds: map[string]int{"choice": 5}},
+		{Date: "2020-01-12", Games: 1, Players: 2, UniquePlayers: 2, QuestionKinds: map[string]int{"poll": 1}},
+	}
+	questions := []tools.QuestionData{{Kind: tools.KindChoice}, {Kind: tools.KindWords}}
+	for i := 0; i < 2; i++ {
+		if err := RecordGame(questions, []string{"a", "b"}); err != nil {
+			t.Fatal(err)
+		}
+	}
+	latest := days[len(days)-1]
+	if latest.Games != 2 || latest.Players != 4 || latest.UniquePlayers != 2 || latest.QuestionKinds["words"] != 2 {
+		t.Errorf("expected the devices to be counted once got %+v", latest)
+	}
+	if err := Load(); err != nil || len(days) != 3 {
+		t.Fatalf("expected the saved days to load got %d days (%v)", len(days), err)
+	}
+	days = days[:2]
+	weeks := Report(PeriodWeek, MaxReportLimit)
+	if len(weeks) != 1 || weeks[0].Date != "2020-01-06" || weeks[0].Games != 2 || weeks[0].AverageSize != 3 {
+		t.Errorf("expected a single week starting on Monday got %+v", weeks)
+	}
+	if daily := Report(PeriodDay, 1); len(daily) != 1 || daily[0].Date != "2020-01-12" {
+		t.Errorf("expected only the newest day got %+v", daily)
+	}
+}
@@ -0,0 +1,129 @@
+package tools
+
+import (
+	"log"
+	"net"
+	"net/http"
+	"strings"
+)
+
+// TrustedProxies The networks of the reverse proxies in front of the server which
+// are trusted to report the address of the client they are forwarding for. This
+// is a comma separated list of addresses or CIDR ranges (e.g. 10.0.0.0/8,127.0.0.1)
+var TrustedProxies = ParseNetworks(EnvOrDefault("QUIZLER_TRUSTED_PROXIES", ""))
+
+// ParseNetworks parses a comma separated list of addresses and CIDR ranges into
+// a list of networks. Single addresses are treated as networks containing only
+// that address. Invalid entries are logged and skipped
+func ParseNetworks(value string) []*net.IPNet {
+	var networks []*net.IPNet
+	for _, entry := range strings.Split(value, ",") { // Iterate over the entries
+		entry = strings.TrimSpace(entry)
+		if entry == "" {
+			continue
+		}
+		if !strings.Contains(entry, "/") { // Single addresses use the full mask
+			if ip := net.ParseIP(entry); ip != nil && ip.To4() != nil {
+				entry += "/32"
+			} else {
+				entry += "/128"
+			}
+		}
+		_, network, err := net.ParseCIDR(entry)
+		if err != nil {
+			log.Printf("Ignoring invalid network '%s': %s", entry, err)
+			continue
+		}
+		networks = append(networks, network)
+	}
+	return networks
+}
+
+// IsTrustedProxy checks whether the provided address belongs to one of the
+// TrustedProxies networks
+func IsTrustedProxy(ip net.IP) bool {
+	for _, network := range TrustedProxies {
+		if network.Contains(ip) {
+			return true
+		}
+	}
+	return false
+}
+
+// ClientAddress retrieves the address of the client that made the provided
+// request without the port. If the request came from a trusted proxy the
+// forwarded addresses are walked from the closest hop outwards and the first
+// address that isn't a trusted proxy is used
+func ClientAddress(request *http.Request) string {
+	address := stripPort(request.RemoteAddr)
+	ip := net.ParseIP(address)
+	if ip == nil || !IsTrustedProxy(ip) { // If the request didn't come from a trusted proxy
+		return address
+	}
+	chain := ForwardedChain(request)
+	for i := len(chain) - 1; i >= 0; i-- { // Walk the chain from the closest hop
+		ip = net.ParseIP(chain[i])
+		if ip == nil { // Obfuscated or invalid hops can't be trusted any further
+			break
+		}
+		address = chain[i]
+		if !IsTrustedProxy(ip) { // The first address that isn't a proxy is the client
+			break
+		}
+	}
+	return address
+}
+
+// ForwardedChain retrieves the list of addresses the request was forwarded for
+// ordered from the original client to the closest proxy. The standard Forwarded
+// header is used when present otherwise the X-Forwarded-For header is used
+func ForwardedChain(request *http.Request) []string {
+	var chain []string
+	if values := request.Header.Values("Forwarded"); len(values) > 0 {
+		for _, value := range values {
+			for _, element := range strings.Split(value, ",") { // Each element is one hop
+				for _, pair := range strings.Split(element, ";") {
+					key, param, found := strings.Cut(strings.TrimSpace(pair), "=")
+					if found && strings.EqualFold(key, "for") {
+						chain = append(chain, stripPort(strings.Trim(param, `"`)))
+					}
+				}
+			}
+		}
+		return chain
+	}
+	for _, value := range request.Header.Values("X-Forwarded-For") {
+		for _, entry := range strings.Split(value, ",") {
+			chain = append(chain, stripPort(strings.TrimSpace(entry)))
+		}
+	}
+	return chain
+}
+
+// stripPort removes the port and any IPv6 brackets from the provided address
+func stripPort(address string) string {
+	if host, _, err := net.SplitHostPort(address); err == nil {
+		return host
+	}
+	return strings.Trim(address, "[]")
+}
+
+// LocalAddresses retrieves the IPv4 addresses of this machine excluding loopback
+// addresses. These are the addresses other devices on the LAN can reach it on
+func LocalAddresses() []net.IP {
+	var addresses []net.IP
+	interfaceAddresses, err := net.InterfaceAddrs()
+	if err != nil {
+		return addresses
+	}
+	for _, address := range interfaceAddresses {
+		network, ok := address.(*net.IPNet)
+		if !ok || network.IP.IsLoopback() {
+			continue
+		}
+		if ip := network.IP.To4(); ip != nil {
+			addresses = append(addresses, ip)
+		}
+	}
+	return addresses
+}
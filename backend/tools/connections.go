@@ -0,0 +1,50 @@
+package tools
+
+import "sync"
+
+// ConnectionLimiter A structure for limiting the number of concurrent connections
+// for each key (usually a client address)
+type ConnectionLimiter struct {
+	Lock   sync.Mutex     // A lock for ensuring that writes are synchronized
+	Limit  int            // The maximum number of concurrent connections per key. Zero for no limit
+	Counts map[string]int // The number of open connections for each key
+}
+
+// NewConnectionLimiter Creates a new connection limiter which allows limit
+// concurrent connections per key. A limit of zero or less disables the limit
+func NewConnectionLimiter(limit int) *ConnectionLimiter {
+	return &ConnectionLimiter{
+		Limit:  limit,
+		Counts: map[string]int{},
+	}
+}
+
+// Acquire attempts to open a connection for the provided key returning whether
+// the connection is within the limit. Every successful Acquire must be followed
+// by a Release once the connection is closed
+func (limiter *ConnectionLimiter) Acquire(key string) bool {
+	if limiter.Limit <= 0 { // If there is no limit
+		return true
+	}
+	limiter.Lock.Lock()                       // Establish a lock on the counts map
+	defer limiter.Lock.Unlock()               // Defer the releasing of the lock
+	if limiter.Counts[key] >= limiter.Limit { // If the limit has been reached
+		return false
+	}
+	limiter.Counts[key]++
+	return true
+}
+
+// Release closes a connection for the provided key that was opened by Acquire
+func (limiter *ConnectionLimiter) Release(key string) {
+	if limiter.Limit <= 0 { // If there is no limit
+		return
+	}
+	limiter.Lock.Lock()           // Establish a lock on the counts map
+	defer limiter.Lock.Unlock()   // Defer the releasing of the lock
+	if limiter.Counts[key] <= 1 { // If this was the last connection remove the key
+		delete(limiter.Counts, key)
+	} else {
+		limiter.Counts[key]--
+	}
+}
@@ -0,0 +1,39 @@
+package tools
+
+import (
+	"encoding/json"
+	"net/http"
+)
+
+// MaxBodySize The maximum size in bytes of a JSON request body accepted by ReadJson
+const MaxBodySize = 64 * 1024
+
+// ReadJson decodes the JSON body of the provided request into the value. Bodies
+// larger than MaxBodySize are rejected
+func ReadJson(writer http.ResponseWriter, request *http.Request, value any) error {
+	return ReadJsonLimit(writer, request, value, MaxBodySize)
+}
+
+// ReadJsonLimit decodes the JSON body of the provided request into the value.
+// Bodies larger than the provided limit in bytes are rejected
+func ReadJsonLimit(writer http.ResponseWriter, request *http.Request, value any, limit int64) error {
+	body := http.MaxBytesReader(writer, request.Body, limit) // Limit the size of the body
+	decoder := json.NewDecoder(body)                         // Create a decoder for the body
+	decoder.DisallowUnknownFields()                          // Reject fields that we don't know about
+	return decoder.Decode(value)
+}
+
+// WriteJson encodes the provided value as JSON and writes it as the response
+// body with the provided status code
+func WriteJson(writer http.ResponseWriter, status int, value any) {
+	writer.Header().Set("Content-Type", "application/json") // Set the Content-Type as JSON
+	writer.WriteHeader(status)                              // Write the status code
+	_ = json.NewEncoder(writer).Encode(value)               // Write the encoded value
+}
+
+// WriteError writes a JSON error response with the provided status code and cause
+func WriteError(writer http.ResponseWriter, status int, cause string) {
+	WriteJson(writer, status, struct {
+		Cause string `json:"cause"` // The cause of the error
+	}{Cause: cause})
+}
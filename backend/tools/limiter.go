@@ -0,0 +1,61 @@
+package tools
+
+import (
+	"sync"
+	"time"
+)
+
+type (
+	// RateLimiter A fixed window rate limiter which limits the number of uses
+	// for each key (usually a client address) within each window of time
+	RateLimiter struct {
+		Lock    sync.Mutex             // A lock for ensuring that writes are synchronized
+		Limit   int                    // The maximum number of uses allowed within a window
+		Window  time.Duration          // The length of each window
+		Windows map[string]*RateWindow // The current window for each key
+	}
+
+	// RateWindow A structure representing the uses of a key within a window
+	RateWindow struct {
+		Start time.Duration // The time that this window started at
+		Count int           // The number of uses within this window
+	}
+)
+
+// NewRateLimiter Creates a new rate limiter which allows limit uses per window
+func NewRateLimiter(limit int, window time.Duration) *RateLimiter {
+	return &RateLimiter{
+		Limit:   limit,
+		Window:  window,
+		Windows: map[string]*RateWindow{},
+	}
+}
+
+// Allow records a use for the provided key and returns whether that use is
+// within the limit. Uses that are over the limit are not counted
+func (limiter *RateLimiter) Allow(key string) bool {
+	limiter.Lock.Lock()         // Establish a lock on the windows map
+	defer limiter.Lock.Unlock() // Defer the releasing of the lock
+	t := Time()
+	window, exists := limiter.Windows[key]
+	if !exists || t-window.Start >= limiter.Window { // If there is no current window for this key
+		limiter.removeExpired(t)
+		limiter.Windows[key] = &RateWindow{Start: t, Count: 1}
+		return true
+	}
+	if window.Count >= limiter.Limit { // If the limit is already reached
+		return false
+	}
+	window.Count++
+	return true
+}
+
+// removeExpired removes all the windows that have expired at the provided time
+// so that keys which are no longer used don't stay in memory forever
+func (limiter *RateLimiter) removeExpired(t time.Duration) {
+	for key, window := range limiter.Windows {
+		if t-window.Start >= limiter.Window { // If the window has expired
+			delete(limiter.Windows, key)
+		}
+	}
+}
@@ -0,0 +1,173 @@
+package tools
+
+import (
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+)
+
+// Limits for the languages and fonts of a quiz
+const (
+	MaxTranslations = 8  // The most languages a single question can be translated into
+	MaxFontLength   = 64 // The most characters the name of a font can have
+)
+
+// Directions the text of a quiz can be written in
+const (
+	DirectionLTR = "ltr" // The text is written left to right (e.g. English)
+	DirectionRTL = "rtl" // The text is written right to left (e.g. Arabic or Hebrew)
+)
+
+// rtlLanguages The languages that are written right to left
+var rtlLanguages = map[string]bool{"ar": true, "dv": true, "fa": true, "he": true, "ps": true, "sd": true, "ug": true, "ur": true, "yi": true}
+
+// TextStyle How the text of a quiz should be shown so that quizzes in right to
+// left languages and scripts that need a particular font render correctly
+type TextStyle struct {
+	Locale    string `json:"locale,omitempty"`    // Optional - the language the quiz is written in (e.g. ar or he)
+	Direction string `json:"direction,omitempty"` // Optional - ltr or rtl, chosen from the locale when empty
+	Font      string `json:"font,omitempty"`      // Optional - the font family clients should prefer for the text
+}
+
+// Translation The text of a question in another language. The answers are in the
+// same order as the original answers so the same indexes are marked as correct
+type Translation struct {
+	Question string   `json:"question"`          // The question in the language
+	Answers  []string `json:"answers,omitempty"` // The answers in the language in the order of the original answers
+}
+
+// Patterns for the values of a TextStyle
+var (
+	localePattern = regexp.MustCompile(`^[a-zA-Z]{2,3}(-[a-zA-Z0-9]{2,8})*$`) // A language tag such as fr, pt-BR or zh-Hant
+	fontPattern   = regexp.MustCompile(`^[a-zA-Z0-9 -]+$`)                    // A font family name such as Noto Naskh Arabic
+)
+
+// IsLocale checks whether the provided value is a language tag such as fr or pt-BR
+func IsLocale(locale string) bool {
+	return localePattern.MatchString(locale)
+}
+
+// DirectionOf finds the direction text in the provided locale is written in
+func DirectionOf(locale string) string {
+	language, _, _ := strings.Cut(locale, "-")
+	if rtlLanguages[strings.ToLower(language)] {
+		return DirectionRTL
+	}
+	return DirectionLTR
+}
+
+// Problems describes everything that makes the style invalid. Each problem starts
+// with the name of the field it applies to in the same form as QuestionData.Problems
+func (style TextStyle) Problems() []string {
+	var problems []string
+	if style.Locale != "" && !IsLocale(style.Locale) {
+		problems = append(problems, "locale must be a language such as ar or he")
+	}
+	if style.Direction != "" && style.Direction != DirectionLTR && style.Direction != DirectionRTL {
+		problems = append(problems, "direction must be ltr or rtl")
+	}
+	if style.Font != "" && (len(style.Font) > MaxFontLength || !fontPattern.MatchString(style.Font)) {
+		problems = append(problems, fmt.Sprintf("font must be at most %d letters, numbers, spaces or dashes", MaxFontLength))
+	}
+	return problems
+}
+
+// For creates the style of the text sent in the provided locale. The original
+// text uses the style with the direction chosen from its locale when empty, while
+// translations use the direction of their own locale and the default font
+func (style TextStyle) For(locale string) TextStyle {
+	if locale != "" { // Translations aren't in the script the font was chosen for
+		return TextStyle{Locale: locale, Direction: DirectionOf(locale)}
+	}
+	if style.Direction == "" && style.Locale != "" {
+		style.Direction = DirectionOf(style.Locale)
+	}
+	return style
+}
+
+// Locale finds the language of the translation to show a player who prefers the
+// provided locale. A translation of the whole locale is used first followed by a
+// translation of just its language (e.g. pt for pt-BR). Returns an empty string
+// for the original question when there isn't a translation
+func (question *QuestionData) Locale(preferred string) string {
+	if preferred == "" {
+		return ""
+	}
+	language, _, _ := strings.Cut(preferred, "-")
+	match := ""
+	for locale := range question.Translations {
+		if strings.EqualFold(locale, preferred) {
+			return locale
+		}
+		if strings.EqualFold(locale, language) {
+			match = locale
+		}
+	}
+	return match
+}
+
+// Localized creates a copy of the question using the translation for the provided
+// locale. The question audio is read in the original language so it is removed
+// from translated questions. The original question is returned for an empty or
+// unknown locale
+func (question *QuestionData) Localized(locale string) QuestionData {
+	localized := *question
+	translation, exists := question.Translations[locale]
+	if locale == "" || !exists {
+		return localized
+	}
+	localized.Question = translation.Question
+	if len(translation.Answers) > 0 {
+		localized.Answers = translation.Answers
+	}
+	localized.Audio = ""
+	return localized
+}
+
+// translationProblems describes everything that makes the translations of the
+// question invalid in the same form as Problems
+func (question *QuestionData) translationProblems() []string {
+	var problems []string
+	if len(question.Translations) > MaxTranslations {
+		problems = append(problems, fmt.Sprintf("translations must have at most %d languages", MaxTranslations))
+	}
+	for locale, translation := range question.Translations { // Iterate over the translations
+		field := fmt.Sprintf("translations[%s]", locale)
+		if !IsLocale(locale) {
+			problems = append(problems, field+" must be a language such as fr or pt-BR")
+		}
+		if len(translation.Question) == 0 {
+			problems = append(problems, field+".question must not be empty")
+		} else if question.Kind == KindBlanks && strings.Count(translation.Question, BlankMarker) != len(question.Blanks) {
+			problems = append(problems, fmt.Sprintf("%s.question must have a %s for each blank", field, BlankMarker))
+		}
+		if len(translation.Answers) != 0 && len(translation.Answers) != len(question.Answers) {
+			problems = append(problems, field+".answers must have the same number of answers as the question")
+		}
+		for i, answer := range translation.Answers {
+			if len(answer) == 0 {
+				problems = append(problems, fmt.Sprintf("%s.answers[%d] must not be empty", field, i))
+			}
+		}
+	}
+	sort.Strings(problems) // Translations are a map so keep the problems in a stable order
+	return problems
+}
+
+// Locales collects every language that any of the questions are translated
+// into sorted alphabetically
+func Locales(questions []QuestionData) []string {
+	seen := map[string]bool{}
+	var locales []string
+	for _, question := range questions {
+		for locale := range question.Translations {
+			if !seen[locale] {
+				seen[locale] = true
+				locales = append(locales, locale)
+			}
+		}
+	}
+	sort.Strings(locales)
+	return locales
+}
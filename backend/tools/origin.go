@@ -0,0 +1,72 @@
+package tools
+
+import (
+	"net/http"
+	"net/url"
+	"strings"
+)
+
+// AllowedOrigins The origins other than the server itself that browsers are allowed
+// to connect from as a comma separated list (e.g. https://quiz.example.com). A
+// value of * allows every origin which is only intended for embedded deployments
+var AllowedOrigins = SplitList(EnvOrDefault("QUIZLER_ALLOWED_ORIGINS", ""))
+
+// SplitList splits a comma separated list into its trimmed non-empty values
+func SplitList(value string) []string {
+	var values []string
+	for _, entry := range strings.Split(value, ",") {
+		entry = strings.TrimSpace(entry)
+		if entry != "" {
+			values = append(values, entry)
+		}
+	}
+	return values
+}
+
+// IsAllowedOrigin checks whether the provided origin is one of the AllowedOrigins
+func IsAllowedOrigin(origin string) bool {
+	for _, allowed := range AllowedOrigins {
+		if allowed == "*" || strings.EqualFold(allowed, origin) {
+			return true
+		}
+	}
+	return false
+}
+
+// CheckOrigin checks whether the Origin header of the provided request is the
+// same origin as the server or one of the AllowedOrigins. Requests without an
+// Origin header didn't come from a browser and are allowed
+func CheckOrigin(request *http.Request) bool {
+	origin := request.Header.Get("Origin")
+	if origin == "" || IsAllowedOrigin(origin) {
+		return true
+	}
+	parsed, err := url.Parse(origin)
+	if err != nil { // Malformed origins are never allowed
+		return false
+	}
+	return strings.EqualFold(parsed.Host, request.Host)
+}
+
+// Cors wraps the provided handler so that browsers on the AllowedOrigins can make
+// cross-origin requests to it. Preflight requests are answered without invoking
+// the handler
+func Cors(handler http.HandlerFunc) http.HandlerFunc {
+	return func(writer http.ResponseWriter, request *http.Request) {
+		header := writer.Header()
+		header.Add("Vary", "Origin") // Responses differ depending on the origin
+		origin := request.Header.Get("Origin")
+		if origin != "" && IsAllowedOrigin(origin) { // Only allowed origins receive the CORS headers
+			header.Set("Access-Control-Allow-Origin", origin)
+			header.Set("Access-Control-Allow-Headers", "Content-Type, Authorization, Upload-Length, Upload-Offset")
+			header.Set("Access-Control-Allow-Methods", "GET, HEAD, POST, PATCH, DELETE, OPTIONS")
+			header.Set("Access-Control-Expose-Headers", "Location, Upload-Length, Upload-Offset")
+			header.Set("Access-Control-Max-Age", "600")
+		}
+		if request.Method == http.MethodOptions { // Preflight requests don't reach the handler
+			writer.WriteHeader(http.StatusNoContent)
+			return
+		}
+		handler(writer, request)
+	}
+}
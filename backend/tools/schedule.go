@@ -0,0 +1,79 @@
+package tools
+
+import (
+	"log"
+	"math/rand"
+	"sync"
+	"time"
+)
+
+// Job A maintenance task that is run periodically in the background
+type Job struct {
+	Name     string        // The name the job is logged with
+	Interval time.Duration // The time between runs of the job (zero or negative disables the job)
+	Jitter   time.Duration // The most random extra time waited before each run so jobs don't all run at once
+	Run      func() error  // The function run by the job
+}
+
+var (
+	jobsStop  = make(chan struct{}) // Closed once the scheduled jobs should stop
+	jobsGroup = sync.WaitGroup{}    // The group of running job loops
+	jobsOnce  = sync.Once{}         // Ensures the stop channel is only closed once
+)
+
+// Schedule runs the provided job in the background until StopJobs is called.
+// Errors returned by the job are logged and a job that panics is logged and run
+// again at the next interval so one failing job can't stop the others or take
+// down the server
+func Schedule(job Job) {
+	if job.Interval <= 0 { // Jobs without an interval are disabled
+		return
+	}
+	jobsGroup.Add(1)
+	go func() {
+		defer jobsGroup.Done()
+		for {
+			delay := job.Interval
+			if job.Jitter > 0 {
+				delay += time.Duration(rand.Int63n(int64(job.Jitter)))
+			}
+			timer := time.NewTimer(delay)
+			select {
+			case <-jobsStop:
+				timer.Stop()
+				return
+			case <-timer.C:
+				runJob(job)
+			}
+		}
+	}()
+}
+
+// StopJobs stops scheduling jobs and waits up to the provided timeout for any
+// jobs that are running to finish. Returns whether every job finished
+func StopJobs(timeout time.Duration) bool {
+	jobsOnce.Do(func() { close(jobsStop) })
+	done := make(chan struct{})
+	go func() {
+		jobsGroup.Wait()
+		close(done)
+	}()
+	select {
+	case <-done:
+		return true
+	case <-time.After(timeout):
+		return false
+	}
+}
+
+// runJob runs a single scheduled job logging any error or panic
+func runJob(job Job) {
+	defer func() {
+		if err := recover(); err != nil {
+			log.Printf("Job '%s' panicked: %v", job.Name, err)
+		}
+	}()
+	if err := job.Run(); err != nil {
+		log.Printf("Job '%s' failed: %s", job.Name, err)
+	}
+}
@@ -1,9 +1,66 @@
 package tools
 
 import (
+	crand "crypto/rand"
+	"crypto/subtle"
+	"encoding/hex"
+	"fmt"
 	"math/rand"
 	"os"
+	"regexp"
+	"strconv"
+	"strings"
 	"time"
+	"unicode/utf8"
+)
+
+// Limits for the size of a quiz these match the limits the
+// frontend editor applies when creating a quiz
+const (
+	MaxQuestions  = 16   // The maximum number of questions a quiz can have
+	MaxAnswers    = 9    // The maximum number of answers a question can have
+	MinAnswers    = 2    // The minimum number of answers a question can have
+	MaxReview     = 60   // The longest time in seconds a question can show its results for
+	MaxFirstBonus = 1000 // The most bonus points a question can award the first player to answer correctly
+	MaxWordLength = 32   // The most characters a player can write for a word cloud question
+	MaxNotes      = 500  // The most characters the host notes of a question can have
+	MaxBlanks     = 5    // The most blanks a fill in the blanks question can have
+	MaxPattern    = 100  // The most characters an accepted answer pattern of a blank can have
+)
+
+// BlankMarker The text written in a fill in the blanks question for each blank
+const BlankMarker = "___"
+
+// The difficulties a question can be marked with. Difficulties are only shown
+// to the host to help them introduce the question
+const (
+	DifficultyEasy   = "easy"
+	DifficultyMedium = "medium"
+	DifficultyHard   = "hard"
+)
+
+// Offline Whether the server must run without making any outbound network
+// requests. Features that depend on external services are disabled
+var Offline = EnvOrDefault("QUIZLER_OFFLINE", "false") == "true"
+
+// Enum for how the answers to a question are marked. Every mode other than
+// MarkSingle lets players choose more than one answer
+const (
+	MarkSingle  MarkingMode = iota // Players choose one answer which is correct if it's any of the correct answers
+	MarkExact                      // Players must choose exactly the correct answers to earn any points
+	MarkPartial                    // Players earn a share of the points for each correct answer chosen
+	MarkPenalty                    // Like MarkPartial but each wrong answer cancels out a correct answer
+)
+
+// Enum for how players respond to a question
+const (
+	KindChoice QuestionKind = iota // Players choose from the answers and are marked automatically
+	KindBuzzer                     // Players race to buzz in and the host judges the answer of whoever buzzed first
+	KindPoll                       // Players choose from the answers and everyone is shown how many chose each one
+	KindWords                      // Players write a few words and everyone is shown how often each was written
+	KindOrder                      // Players put the answers in order and earn a share of the points for each answer in the right place
+	KindNumber                     // Players choose a number on a slider and earn the points when it's close enough to the answer
+	KindBlanks                     // Players write the missing word for each blank and earn a share of the points for each one that is accepted
 )
 
 type (
@@ -13,6 +70,26 @@ type (
 	// State type for game states represented as an 8-bit integer
 	State = uint8
 
+	// DeviceMode type for how a game treats multiple players joining from the
+	// same device represented as an 8-bit integer
+	DeviceMode = uint8
+
+	// MarkingMode type for how the answers to a question are marked represented
+	// as an 8-bit integer
+	MarkingMode = uint8
+
+	// QuestionKind type for how players respond to a question represented as
+	// an 8-bit integer
+	QuestionKind = uint8
+
+	// RevealMode type for what players are shown after each question is marked
+	// represented as an 8-bit integer
+	RevealMode = uint8
+
+	// ScoringMode type for how the points for each question are decided
+	// represented as an 8-bit integer
+	ScoringMode = uint8
+
 	// AnswerIndex represents the index for an answer as an integer
 	AnswerIndex = int
 
@@ -21,10 +98,40 @@ type (
 
 	// QuestionData A structure representing a question for the quiz
 	QuestionData struct {
-		Image    string        // Optional - an image to display with the question
-		Question string        // The actual contents of the question
-		Answers  []string      // The possible answer values
-		Values   []AnswerIndex // The indexes of the correct answers
+		Image        string                 `json:"image,omitempty"`        // Optional - an image to display with the question
+		Question     string                 `json:"question"`               // The actual contents of the question
+		Answers      []string               `json:"answers"`                // The possible answer values
+		Values       []AnswerIndex          `json:"values"`                 // The indexes of the correct answers
+		Marking      MarkingMode            `json:"marking,omitempty"`      // Optional - how the answers are marked
+		MinChoices   int                    `json:"minChoices,omitempty"`   // Optional - the fewest answers a player can choose when choosing many
+		MaxChoices   int                    `json:"maxChoices,omitempty"`   // Optional - the most answers a player can choose when choosing many
+		Audio        string                 `json:"audio,omitempty"`        // Optional - the question read aloud, generated by the server
+		Thumbnail    string                 `json:"thumbnail,omitempty"`    // Optional - a small version of the image, generated by the server
+		Review       int                    `json:"review,omitempty"`       // Optional - the seconds to show the results for after marking instead of the default
+		FirstBonus   int                    `json:"firstBonus,omitempty"`   // Optional - the bonus points for the first player to answer correctly
+		Kind         QuestionKind           `json:"kind,omitempty"`         // Optional - how players respond to the question
+		Translations map[string]Translation `json:"translations,omitempty"` // Optional - the question in other languages mapped to their locale
+		Difficulty   string                 `json:"difficulty,omitempty"`   // Optional - how hard the question is (easy, medium or hard), only shown to the host
+		Notes        string                 `json:"notes,omitempty"`        // Optional - notes for the host to read out before the question, only shown to the host
+		Numeric      *NumericData           `json:"numeric,omitempty"`      // Optional - the answer and slider for number questions
+		Blanks       [][]string             `json:"blanks,omitempty"`       // Optional - the accepted answers for each blank of fill in the blanks questions
+		Patterns     [][]string             `json:"patterns,omitempty"`     // Optional - regular expressions also accepted for each blank of fill in the blanks questions
+	}
+
+	// Slider The range of numbers players choose from when answering a number question
+	Slider struct {
+		Min  float64 `json:"min"`            // The lowest number on the slider
+		Max  float64 `json:"max"`            // The highest number on the slider
+		Step float64 `json:"step,omitempty"` // The gap between the numbers on the slider or zero for any number
+	}
+
+	// NumericData The answer to a number question and how close players must be
+	// to earn points. Only the slider is sent to the players
+	NumericData struct {
+		Slider
+		Answer    float64 `json:"answer"`              // The correct number
+		Tolerance float64 `json:"tolerance,omitempty"` // How far from the answer a number can be and still earn every point
+		Margin    float64 `json:"margin,omitempty"`    // How far past the tolerance partial credit falls to nothing (partial marking only)
 	}
 
 	// ScoreMap A map of player identifiers to score values
@@ -41,11 +148,322 @@ func EnvOrDefault(key string, d string) string {
 	return value
 }
 
+// EnvIntOrDefault Used to retrieve an environment variable as an integer or
+// the provided default value if that variable doesn't exist or isn't a valid
+// integer
+func EnvIntOrDefault(key string, d int) int {
+	value, exists := os.LookupEnv(key) // Lookup the environment variable
+	if !exists {                       // If the variable doesn't exist
+		return d // Return the default value
+	}
+	parsed, err := strconv.Atoi(value) // Parse the value as an integer
+	if err != nil {                    // If the value wasn't a valid integer
+		return d // Return the default value
+	}
+	return parsed
+}
+
+// IsValid checks that the question has some contents, has between MinAnswers
+// and MaxAnswers non-empty answers and that the correct answer indexes are
+// all within the bounds of the answers
+func (question *QuestionData) IsValid() bool {
+	return len(question.Problems()) == 0
+}
+
+// Problems describes everything that makes the question invalid. Each problem
+// starts with the name of the field it applies to (e.g. answers[3] must not be empty)
+func (question *QuestionData) Problems() []string {
+	var problems []string
+	if len(question.Question) == 0 { // If the question has no contents
+		problems = append(problems, "question must not be empty")
+	}
+	count := len(question.Answers)
+	switch question.Kind {
+	case KindChoice, KindPoll:
+		if count < MinAnswers || count > MaxAnswers { // If there are too few or too many answers
+			problems = append(problems, fmt.Sprintf("answers must have between %d and %d answers", MinAnswers, MaxAnswers))
+		}
+		if question.Kind == KindChoice && len(question.Values) == 0 { // If there are no correct answers
+			problems = append(problems, "values must have at least one correct answer")
+		} else if question.Kind == KindPoll && len(question.Values) != 0 { // Poll answers are never right or wrong
+			problems = append(problems, "values can't be set for poll questions")
+		}
+	case KindBuzzer: // The host judges buzzer answers so any answers are only a reference for the host
+		if count > MaxAnswers { // If there are too many answers
+			problems = append(problems, fmt.Sprintf("answers must have at most %d answers", MaxAnswers))
+		}
+		if len(question.Values) != 0 || question.Marking != MarkSingle {
+			problems = append(problems, "values and marking can't be set for buzzer questions")
+		}
+	case KindOrder: // Players put every answer in order
+		if count < MinAnswers || count > MaxAnswers { // If there are too few or too many answers
+			problems = append(problems, fmt.Sprintf("answers must have between %d and %d answers", MinAnswers, MaxAnswers))
+		}
+		if !IsOrder(question.Values, count) { // The values are the answers in the correct order
+			problems = append(problems, "values must list the index of every answer once in the correct order")
+		}
+		if question.Marking != MarkSingle && question.Marking != MarkExact {
+			problems = append(problems, "marking must be single or exact for order questions")
+		}
+		if question.MinChoices != 0 || question.MaxChoices != 0 {
+			problems = append(problems, "minChoices and maxChoices can't be set for order questions")
+		}
+	case KindWords: // Players write their own answers
+		if count != 0 || len(question.Values) != 0 || question.Marking != MarkSingle {
+			problems = append(problems, "answers, values and marking can't be set for word cloud questions")
+		}
+	case KindBlanks: // Players write the missing words
+		if count != 0 || len(question.Values) != 0 {
+			problems = append(problems, "answers and values can't be set for fill in the blanks questions")
+		}
+		problems = append(problems, question.blankProblems()...)
+	case KindNumber: // Players choose a number on a slider
+		if count != 0 || len(question.Values) != 0 {
+			problems = append(problems, "answers and values can't be set for number questions")
+		}
+		if question.Numeric == nil {
+			problems = append(problems, "numeric must be set for number questions")
+		} else {
+			problems = append(problems, question.numericProblems()...)
+		}
+	default: // If the kind of question doesn't exist
+		problems = append(problems, "kind must be a known question kind")
+	}
+	if question.Numeric != nil && question.Kind != KindNumber {
+		problems = append(problems, "numeric can only be set for number questions")
+	}
+	if len(question.Blanks) != 0 && question.Kind != KindBlanks {
+		problems = append(problems, "blanks can only be set for fill in the blanks questions")
+	}
+	if len(question.Patterns) != 0 && question.Kind != KindBlanks {
+		problems = append(problems, "patterns can only be set for fill in the blanks questions")
+	}
+	for i, answer := range question.Answers { // Iterate over the answers
+		if len(answer) == 0 { // If the answer is empty
+			problems = append(problems, fmt.Sprintf("answers[%d] must not be empty", i))
+		}
+	}
+	for i, value := range question.Values { // Iterate over the correct answer indexes
+		if value < 0 || value >= count { // If the index is out of bounds
+			problems = append(problems, fmt.Sprintf("values[%d] must be the index of an answer", i))
+		}
+	}
+	if question.Marking > MarkPenalty { // If the marking mode doesn't exist
+		problems = append(problems, "marking must be a known marking mode")
+	}
+	if (question.MinChoices != 0 || question.MaxChoices != 0) && !question.IsMultiple() && question.Kind != KindOrder {
+		problems = append(problems, "minChoices and maxChoices can only be set when players choose many answers")
+	}
+	if question.MinChoices < 0 || question.MinChoices > count { // If the minimum can't be chosen
+		problems = append(problems, "minChoices must be between 0 and the number of answers")
+	}
+	if question.MaxChoices < 0 || question.MaxChoices > count { // If the maximum can't be chosen
+		problems = append(problems, "maxChoices must be between 0 and the number of answers")
+	} else if question.MaxChoices != 0 && question.MaxChoices < question.MinChoices {
+		problems = append(problems, "maxChoices must not be less than minChoices")
+	}
+	if question.Review < 0 || question.Review > MaxReview { // If the review time is out of bounds
+		problems = append(problems, fmt.Sprintf("review must be between 0 and %d seconds", MaxReview))
+	}
+	if question.FirstBonus < 0 || question.FirstBonus > MaxFirstBonus { // If the bonus is out of bounds
+		problems = append(problems, fmt.Sprintf("firstBonus must be between 0 and %d points", MaxFirstBonus))
+	} else if question.FirstBonus != 0 && question.IsSurvey() {
+		problems = append(problems, "firstBonus can't be set for questions that aren't marked")
+	}
+	if question.Difficulty != "" && !IsDifficulty(question.Difficulty) { // If the difficulty doesn't exist
+		problems = append(problems, "difficulty must be one of easy, medium or hard")
+	}
+	if utf8.RuneCountInString(question.Notes) > MaxNotes { // If the notes are too long
+		problems = append(problems, fmt.Sprintf("notes must be at most %d characters", MaxNotes))
+	}
+	problems = append(problems, question.translationProblems()...)
+	return problems
+}
+
+// numericProblems describes everything wrong with the answer and slider of a
+// number question
+func (question *QuestionData) numericProblems() []string {
+	var problems []string
+	numeric := question.Numeric
+	if numeric.Min >= numeric.Max {
+		problems = append(problems, "numeric.min must be less than numeric.max")
+	}
+	if numeric.Answer < numeric.Min || numeric.Answer > numeric.Max { // The answer must be on the slider
+		problems = append(problems, "numeric.answer must be between numeric.min and numeric.max")
+	}
+	if numeric.Step < 0 || numeric.Step > numeric.Max-numeric.Min {
+		problems = append(problems, "numeric.step must be between 0 and the size of the slider")
+	}
+	if numeric.Tolerance < 0 {
+		problems = append(problems, "numeric.tolerance must not be negative")
+	}
+	if question.Marking != MarkSingle && question.Marking != MarkPartial {
+		problems = append(problems, "marking must be single or partial for number questions")
+	} else if question.Marking == MarkPartial && numeric.Margin <= 0 {
+		problems = append(problems, "numeric.margin must be more than 0 for partial marking")
+	} else if question.Marking == MarkSingle && numeric.Margin != 0 {
+		problems = append(problems, "numeric.margin can only be set for partial marking")
+	}
+	return problems
+}
+
+// blankProblems describes everything wrong with the blanks of a fill in the
+// blanks question. The question must have a BlankMarker for each blank
+func (question *QuestionData) blankProblems() []string {
+	var problems []string
+	if len(question.Blanks) == 0 || len(question.Blanks) > MaxBlanks {
+		problems = append(problems, fmt.Sprintf("blanks must have between 1 and %d blanks", MaxBlanks))
+	}
+	if strings.Count(question.Question, BlankMarker) != len(question.Blanks) {
+		problems = append(problems, fmt.Sprintf("question must have a %s for each blank", BlankMarker))
+	}
+	for i, accepted := range question.Blanks {
+		if len(accepted) == 0 || len(accepted) > MaxAnswers {
+			problems = append(problems, fmt.Sprintf("blanks[%d] must have between 1 and %d accepted answers", i, MaxAnswers))
+		}
+		for j, answer := range accepted {
+			if length := utf8.RuneCountInString(strings.TrimSpace(answer)); length == 0 || length > MaxWordLength {
+				problems = append(problems, fmt.Sprintf("blanks[%d][%d] must be between 1 and %d characters", i, j, MaxWordLength))
+			}
+		}
+	}
+	if len(question.Patterns) > len(question.Blanks) {
+		problems = append(problems, "patterns can't have more entries than blanks")
+	}
+	for i, patterns := range question.Patterns {
+		if len(patterns) > MaxAnswers {
+			problems = append(problems, fmt.Sprintf("patterns[%d] must have at most %d patterns", i, MaxAnswers))
+		}
+		for j, pattern := range patterns {
+			if length := utf8.RuneCountInString(pattern); length == 0 || length > MaxPattern {
+				problems = append(problems, fmt.Sprintf("patterns[%d][%d] must be between 1 and %d characters", i, j, MaxPattern))
+			} else if _, err := CompilePattern(pattern); err != nil {
+				problems = append(problems, fmt.Sprintf("patterns[%d][%d] is not a valid pattern: %s", i, j, err))
+			}
+		}
+	}
+	if question.Marking != MarkSingle && question.Marking != MarkExact {
+		problems = append(problems, "marking must be single or exact for fill in the blanks questions")
+	}
+	return problems
+}
+
+// CompilePattern compiles an accepted answer pattern of a blank so that it must
+// match the whole answer ignoring case. Patterns use the RE2 syntax which runs in
+// linear time so matching player answers needs no timeout
+func CompilePattern(pattern string) (*regexp.Regexp, error) {
+	if _, err := regexp.Compile(pattern); err != nil { // The pattern must be complete on its own
+		return nil, err
+	}
+	return regexp.Compile("(?i)^(?:" + pattern + ")$")
+}
+
+// IsOrder checks whether the provided answer indexes contain the index of each
+// of the count answers exactly once
+func IsOrder(values []AnswerIndex, count int) bool {
+	if len(values) != count {
+		return false
+	}
+	seen := make([]bool, count)
+	for _, value := range values {
+		if value < 0 || value >= count || seen[value] {
+			return false
+		}
+		seen[value] = true
+	}
+	return true
+}
+
+// ShuffleOrder returns a copy of the order question with the answers and the
+// answers of each translation shuffled so players aren't sent them in the correct
+// order. The values are changed to the new positions of the answers
+func (question QuestionData) ShuffleOrder() QuestionData {
+	count := len(question.Answers)
+	order := rand.Perm(count)               // The original index of the answer at each new position
+	positions := make([]AnswerIndex, count) // The new position of each original answer
+	answers := make([]string, count)
+	for position, original := range order {
+		positions[original] = position
+		answers[position] = question.Answers[original]
+	}
+	values := make([]AnswerIndex, len(question.Values))
+	for i, value := range question.Values {
+		if value >= 0 && value < count {
+			values[i] = positions[value]
+		}
+	}
+	if question.Translations != nil {
+		translations := make(map[string]Translation, len(question.Translations))
+		for locale, translation := range question.Translations {
+			if len(translation.Answers) == count {
+				shuffled := make([]string, count)
+				for position, original := range order {
+					shuffled[position] = translation.Answers[original]
+				}
+				translation.Answers = shuffled
+			}
+			translations[locale] = translation
+		}
+		question.Translations = translations
+	}
+	question.Answers = answers
+	question.Values = values
+	return question
+}
+
+// Slider returns the slider players answer a number question with or nil for
+// other questions. The answer is never included
+func (question *QuestionData) Slider() *Slider {
+	if question.Numeric == nil {
+		return nil
+	}
+	slider := question.Numeric.Slider
+	return &slider
+}
+
+// IsDifficulty checks whether the provided value is one of the difficulties
+func IsDifficulty(value string) bool {
+	return value == DifficultyEasy || value == DifficultyMedium || value == DifficultyHard
+}
+
+// IsSurvey checks whether the question collects responses from the players
+// without marking them. The responses are shown to everyone instead of a score
+func (question *QuestionData) IsSurvey() bool {
+	return question.Kind == KindPoll || question.Kind == KindWords
+}
+
+// IsMultiple checks whether players can choose more than one answer
+func (question *QuestionData) IsMultiple() bool {
+	return question.Marking != MarkSingle
+}
+
+// ChoiceLimits returns the fewest and most answers a player can choose. Players
+// must choose at least one answer and can choose every answer unless limited.
+// Order questions are answered with every answer
+func (question *QuestionData) ChoiceLimits() (int, int) {
+	if question.Kind == KindOrder { // Every answer is put in order
+		return len(question.Answers), len(question.Answers)
+	}
+	if !question.IsMultiple() { // Only one answer can be chosen
+		return 1, 1
+	}
+	min, max := question.MinChoices, question.MaxChoices
+	if min < 1 {
+		min = 1
+	}
+	if max == 0 || max > len(question.Answers) {
+		max = len(question.Answers)
+	}
+	return min, max
+}
+
 // FreeMemory Used to free up memory from questions that have already been
-// served to the user. (This is done by setting the image to a blank string)
-// as most images will take up a few MB or so while in use
+// served to the user. (This is done by setting the image, audio and thumbnail
+// to a blank string) as most images will take up a few MB or so while in use
 func (question *QuestionData) FreeMemory() {
 	question.Image = ""
+	question.Audio = ""
+	question.Thumbnail = ""
 }
 
 // CreateRandomId Creates a random identifier of the specified length using
@@ -61,6 +479,22 @@ func CreateRandomId(length uint8) Identifier {
 	return Identifier(out)
 }
 
+// CreateSecret Creates a random hex encoded secret from the provided number of
+// bytes. Unlike CreateRandomId these are unpredictable so can be used as keys
+func CreateSecret(size int) string {
+	out := make([]byte, size)
+	if _, err := crand.Read(out); err != nil { // The system random source should never fail
+		panic(err)
+	}
+	return hex.EncodeToString(out)
+}
+
+// SecretEquals Checks whether the provided value matches the expected secret in
+// constant time. Empty secrets never match
+func SecretEquals(expected string, value string) bool {
+	return expected != "" && subtle.ConstantTimeCompare([]byte(expected), []byte(value)) == 1
+}
+
 // Time Retrieves the current time in milliseconds
 func Time() time.Duration {
 	return time.Duration(time.Now().UnixNano())
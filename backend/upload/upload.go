@@ -0,0 +1,243 @@
+package upload
+
+import (
+	"backend/tools"
+	"bytes"
+	"errors"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// Limits for upload sessions
+const (
+	MaxUploadSize  = 16 * 1024 * 1024 // The maximum size in bytes of a single upload
+	MaxSessions    = 64               // The maximum number of unfinished uploads at once
+	SessionTimeout = 30 * time.Minute // How long an upload is kept after it was last written to
+)
+
+// Dir The directory the contents of uploads are stored in until they are used
+var Dir = filepath.Join(os.TempDir(), "quizler-uploads")
+
+// Session An upload that is in progress or complete but not yet used. The
+// contents are written to a temporary file so that an interrupted upload can
+// continue from the last byte that was received
+type Session struct {
+	Lock     sync.Mutex // A lock for writing to the session
+	Id       string     // The unique id of the upload
+	Path     string     // The path of the temporary file holding the contents
+	Length   int64      // The total size of the upload in bytes
+	Offset   int64      // The number of bytes that have been received
+	Modified time.Time  // The last time the upload was written to
+}
+
+var (
+	sessionsLock = sync.RWMutex{}        // A lock for modifying the sessions
+	sessions     = map[string]*Session{} // The upload sessions mapped to their ids
+)
+
+// Complete checks whether every byte of the upload has been received
+func (session *Session) Complete() bool {
+	return session.Offset == session.Length
+}
+
+// Create starts a new upload session for an upload of the provided length
+func Create(length int64) (*Session, error) {
+	if length <= 0 || length > MaxUploadSize {
+		return nil, fmt.Errorf("uploads must be between 1 and %d bytes", MaxUploadSize)
+	}
+	sessionsLock.Lock()         // Establish lock on the sessions
+	defer sessionsLock.Unlock() // Defer the releasing of the lock
+	if len(sessions) >= MaxSessions {
+		return nil, errors.New("too many uploads in progress")
+	}
+	if err := os.MkdirAll(Dir, 0700); err != nil {
+		return nil, err
+	}
+	file, err := os.CreateTemp(Dir, "upload-*")
+	if err != nil {
+		return nil, err
+	}
+	_ = file.Close()
+	session := &Session{Id: tools.CreateSecret(16), Path: file.Name(), Length: length, Modified: time.Now()}
+	sessions[session.Id] = session
+	return session, nil
+}
+
+// Store creates a complete upload holding the provided contents so it can be
+// used to create a game the same as an upload sent in chunks
+func Store(data []byte) (*Session, error) {
+	session, err := Create(int64(len(data)))
+	if err != nil {
+		return nil, err
+	}
+	if _, err = session.Write(0, bytes.NewReader(data)); err != nil {
+		_ = session.Remove()
+		return nil, err
+	}
+	return session, nil
+}
+
+// Get retrieves the upload session with the provided id or nil if there isn't one
+func Get(id string) *Session {
+	sessionsLock.RLock()         // Establish a read lock on the sessions
+	defer sessionsLock.RUnlock() // Defer the releasing of the read lock
+	return sessions[id]
+}
+
+// Remove deletes the upload session and its contents
+func (session *Session) Remove() error {
+	sessionsLock.Lock() // Establish lock on the sessions
+	delete(sessions, session.Id)
+	sessionsLock.Unlock() // Release lock
+	return os.Remove(session.Path)
+}
+
+// Write appends the contents of the reader to the upload starting at the provided
+// offset which must be the number of bytes already received. Bytes that were
+// received are kept even if the reader fails part way through so the upload can
+// be continued. Returns the new offset
+func (session *Session) Write(offset int64, reader io.Reader) (int64, error) {
+	session.Lock.Lock()         // Establish lock on the session
+	defer session.Lock.Unlock() // Defer the releasing of the lock
+	if offset != session.Offset {
+		return session.Offset, fmt.Errorf("expected offset %d", session.Offset)
+	}
+	file, err := os.OpenFile(session.Path, os.O_WRONLY|os.O_APPEND, 0600)
+	if err != nil {
+		return session.Offset, err
+	}
+	defer file.Close()
+	// Read one byte more than the remaining length so oversized chunks can be detected
+	written, err := io.Copy(file, io.LimitReader(reader, session.Length-session.Offset+1))
+	if written > session.Length-session.Offset { // Discard the extra byte
+		written = session.Length - session.Offset
+		_ = file.Truncate(session.Length)
+		err = errors.New("upload is larger than its length")
+	}
+	session.Offset += written
+	session.Modified = time.Now()
+	return session.Offset, err
+}
+
+// Take removes the complete upload with the provided id and returns its contents
+func Take(id string) ([]byte, error) {
+	session := Get(id)
+	if session == nil {
+		return nil, errors.New("that upload doesn't exist")
+	}
+	session.Lock.Lock() // Establish lock on the session
+	complete := session.Complete()
+	session.Lock.Unlock() // Release lock
+	if !complete {
+		return nil, errors.New("that upload isn't complete")
+	}
+	data, err := os.ReadFile(session.Path)
+	_ = session.Remove()
+	return data, err
+}
+
+// Expire removes any upload sessions that haven't been written to within the
+// SessionTimeout. Returns the first error removing the contents of an upload
+func Expire() error {
+	sessionsLock.RLock()
+	var expired []*Session
+	for _, session := range sessions {
+		session.Lock.Lock()
+		if time.Since(session.Modified) > SessionTimeout {
+			expired = append(expired, session)
+		}
+		session.Lock.Unlock()
+	}
+	sessionsLock.RUnlock()
+	var failed error
+	for _, session := range expired {
+		if err := session.Remove(); err != nil && failed == nil {
+			failed = err
+		}
+	}
+	if len(expired) > 0 {
+		log.Printf("Removed %d expired uploads", len(expired))
+	}
+	return failed
+}
+
+// writeOffset sets the headers describing the progress of the upload
+func writeOffset(writer http.ResponseWriter, session *Session, offset int64) {
+	writer.Header().Set("Upload-Offset", strconv.FormatInt(offset, 10))
+	writer.Header().Set("Upload-Length", strconv.FormatInt(session.Length, 10))
+	writer.Header().Set("Cache-Control", "no-store")
+}
+
+// HandleUpload HTTP handler for /api/upload and /api/upload/{id}. Implements
+// resumable uploads using the headers from the tus protocol:
+//
+//	POST /api/upload with Upload-Length starts an upload and responds with its id
+//	HEAD /api/upload/{id} responds with the Upload-Offset received so far
+//	PATCH /api/upload/{id} with Upload-Offset appends the body to the upload
+//	DELETE /api/upload/{id} cancels the upload
+//
+// Once every byte is received the id can be used to create a game from the upload
+func HandleUpload(writer http.ResponseWriter, request *http.Request) {
+	id := strings.Trim(strings.TrimPrefix(request.URL.Path, "/api/upload"), "/")
+	if id == "" {
+		if request.Method != http.MethodPost { // Only uploads can be created without an id
+			tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+			return
+		}
+		length, err := strconv.ParseInt(request.Header.Get("Upload-Length"), 10, 64)
+		if err != nil {
+			tools.WriteError(writer, http.StatusBadRequest, "Missing or invalid Upload-Length")
+			return
+		}
+		session, err := Create(length)
+		if err != nil {
+			tools.WriteError(writer, http.StatusBadRequest, err.Error())
+			return
+		}
+		writer.Header().Set("Location", "/api/upload/"+session.Id)
+		writeOffset(writer, session, 0)
+		tools.WriteJson(writer, http.StatusCreated, struct {
+			Id string `json:"id"` // The id of the created upload
+		}{Id: session.Id})
+		return
+	}
+	session := Get(id)
+	if session == nil {
+		tools.WriteError(writer, http.StatusNotFound, "Upload not found")
+		return
+	}
+	switch request.Method {
+	case http.MethodHead:
+		session.Lock.Lock()
+		offset := session.Offset
+		session.Lock.Unlock()
+		writeOffset(writer, session, offset)
+		writer.WriteHeader(http.StatusOK)
+	case http.MethodPatch:
+		offset, err := strconv.ParseInt(request.Header.Get("Upload-Offset"), 10, 64)
+		if err != nil {
+			tools.WriteError(writer, http.StatusBadRequest, "Missing or invalid Upload-Offset")
+			return
+		}
+		offset, err = session.Write(offset, request.Body)
+		writeOffset(writer, session, offset)
+		if err != nil {
+			tools.WriteError(writer, http.StatusConflict, err.Error())
+			return
+		}
+		writer.WriteHeader(http.StatusNoContent)
+	case http.MethodDelete:
+		_ = session.Remove()
+		writer.WriteHeader(http.StatusNoContent)
+	default:
+		tools.WriteError(writer, http.StatusMethodNotAllowed, "Method not allowed")
+	}
+}
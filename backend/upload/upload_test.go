@@ -0,0 +1,79 @@
+package upload
+
+import (
+	"errors"
+	"io"
+	"strings"
+	"testing"
+)
+
+// TestResume tests that an upload interrupted part way through keeps the bytes
+// that were received and can be continued from that offset
+func TestResume(t *testing.T) {
+	Dir = t.TempDir()
+	contents := "the contents of the upload"
+	session, err := Create(int64(len(contents)))
+	if err != nil {
+		t.Fatal(err)
+	}
+	// A reader that fails after the first 10 bytes like a dropped connection
+	broken := io.MultiReader(strings.NewReader(contents[:10]), &failingReader{})
+	offset, err := session.Write(0, broken)
+	if err == nil || offset != 10 {
+		t.Fatalf("expected interrupted write at offset 10 got %d (%v)", offset, err)
+	}
+	if _, err = Take(session.Id); err == nil {
+		t.Fatal("expected incomplete upload to not be taken")
+	}
+	if _, err = session.Write(0, strings.NewReader(contents)); err == nil {
+		t.Fatal("expected write at the wrong offset to fail")
+	}
+	if offset, err = session.Write(10, strings.NewReader(contents[10:])); err != nil || offset != int64(len(contents)) {
+		t.Fatalf("expected resumed write to complete got %d (%v)", offset, err)
+	}
+	data, err := Take(session.Id)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if string(data) != contents {
+		t.Errorf("expected %q got %q", contents, data)
+	}
+	if Get(session.Id) != nil {
+		t.Error("expected upload to be removed once taken")
+	}
+}
+
+// TestStore tests that stored contents are a complete upload that can be taken
+func TestStore(t *testing.T) {
+	Dir = t.TempDir()
+	session, err := Store([]byte("contents"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if data, err := Take(session.Id); err != nil || string(data) != "contents" {
+		t.Errorf("expected the stored contents got %q (%v)", data, err)
+	}
+	if _, err = Store(nil); err == nil {
+		t.Error("expected empty contents to be rejected")
+	}
+}
+
+// TestTooLarge tests that bytes past the length of an upload are rejected
+func TestTooLarge(t *testing.T) {
+	Dir = t.TempDir()
+	session, err := Create(4)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer func() { _ = session.Remove() }()
+	if offset, err := session.Write(0, strings.NewReader("too long")); err == nil || offset != 4 {
+		t.Errorf("expected oversized write to fail at offset 4 got %d (%v)", offset, err)
+	}
+}
+
+// failingReader A reader which always fails
+type failingReader struct{}
+
+func (reader *failingReader) Read([]byte) (int, error) {
+	return 0, errors.New("connection lost")
+}
@@ -1,22 +1,38 @@
 import packets, {
+    BuzzerData,
+    CapacityData,
     debugLogPacket,
     Direction,
     DisconnectData,
+    DuplicateDeviceData,
     ErrorData,
+    FirstCorrectData,
     GameData,
     GameStateData,
+    InvalidQuizDetails,
+    LobbySettings,
+    NextQuestionData,
     Packet,
+    PlayerCountData,
     PlayerData,
     PlayerDataMode,
     PlayerDataWithMode,
+    PollResultsData,
     QuestionData,
+    ResultsData,
+    RoundData,
     ScoresData,
+    SequencedData,
+    SnapshotData,
     SPID,
+    StateSyncData,
     States,
-    TimeSyncData
+    SummaryData,
+    TimeSyncData,
+    WarmUpData
 } from "./packets";
 import { onUnmounted, reactive, ref, Ref, watch } from "vue";
-import { dialog, toast } from "@/tools/ui";
+import { dialog, toast, ToastMode } from "@/tools/ui";
 import { DEBUG, HOST } from "@/constants";
 import { router } from "@/router";
 import { useRouter } from "vue-router";
@@ -29,6 +45,7 @@ export enum GameState {
     STARTED,
     STOPPED,
     DOES_NOT_EXIST,
+    PAUSED,
 }
 
 // Defines a map of id -> player data
@@ -58,6 +75,16 @@ export class SocketApi {
     question = ref<QuestionData | null>(null) // The active question in the game (store here to persist)
     gameState = ref<GameState>(GameState.UNSET) // The current game state
     self = ref<PlayerData | null>(null) // The player we are playing as
+    playerCount = ref(0) // The number of players in the game
+    summary = ref<SummaryData | null>(null) // The results of the game sent to the host once it is over
+    buzzer = ref<BuzzerData | null>(null) // The player whose turn it is to answer the buzzer question
+    pollResults = ref<PollResultsData | null>(null) // The responses to the last poll or word cloud question
+    roundOver = ref<RoundData | null>(null) // The scores of the last quiz of the playlist that finished
+    warmUp = ref<WarmUpData | null>(null) // The warm-up poll asked while waiting for players
+    results = ref<ResultsData | null>(null) // The final rankings sent once the game is over
+    nextQuestion = ref<NextQuestionData | null>(null) // The preview of the next question (Host only)
+    private lastSeq = 0 // The number of the last event sent to everyone that was handled
+    private resyncing = false // Whether the events after the last number have been asked for again
 
     /**
      * A mapping to convert the packet ids into handler functions so that
@@ -74,6 +101,24 @@ export class SocketApi {
         [SPID.QUESTION]: this.onQuestion.bind(this),
         [SPID.ANSWER_RESULT]: EMPTY_HANDLER,
         [SPID.SCORES]: this.onScores.bind(this),
+        [SPID.DUPLICATE_DEVICE]: this.onDuplicateDevice.bind(this),
+        [SPID.STATE_SYNC]: this.onStateSync.bind(this),
+        [SPID.PLAYER_COUNT]: this.onPlayerCount.bind(this),
+        [SPID.KICKED]: this.onKicked.bind(this),
+        [SPID.CAPACITY_WARNING]: this.onCapacityWarning.bind(this),
+        [SPID.GAME_FULL]: this.onGameFull.bind(this),
+        [SPID.SETTINGS]: this.onSettings.bind(this),
+        [SPID.SUMMARY]: this.onSummary.bind(this),
+        [SPID.FIRST_CORRECT]: this.onFirstCorrect.bind(this),
+        [SPID.BUZZER]: this.onBuzzer.bind(this),
+        [SPID.POLL_RESULTS]: this.onPollResults.bind(this),
+        [SPID.ROUND_OVER]: this.onRoundOver.bind(this),
+        [SPID.WARM_UP]: this.onWarmUp.bind(this),
+        [SPID.RESULTS]: this.onResults.bind(this),
+        [SPID.NEXT_QUESTION]: this.onNextQuestion.bind(this),
+        [SPID.ANSWER_ACCEPTED]: EMPTY_HANDLER,
+        [SPID.SEQUENCED]: this.onSequenced.bind(this),
+        [SPID.SNAPSHOT]: this.onSnapshot.bind(this),
     }
 
     /**
@@ -97,21 +142,19 @@ export class SocketApi {
             if (DEBUG) console.debug('Connected to socket server') // Debug logging
             if (this.ws.readyState != WebSocket.OPEN) return // Ensure we are actually on the open ready state
             this.open.value = true // Update the open state
+            const data = this.gameData.value
+            if (data?.owner && data.key) { // If we were hosting a game try to take it back
+                this.send(packets.reconnectHost(data.id, data.key))
+            } else if (data?.display && data.key) { // If we were displaying a game show it again
+                this.send(packets.joinDisplay(data.id, data.key))
+            }
         }
         // Set the handler for the websocket message event
         ws.onmessage = (event: MessageEvent) => {
             try {
                 const packet = JSON.parse(event.data) as Packet // Parse the packet
                 debugLogPacket(Direction.IN, packet) // Debug print the packet info
-                const id: SPID = packet.id // Get the packet id
-                const data: any = packet.data
-                if (id in this.handlers) {  // Check to make sure we have a handler for this packet id
-                    const handler = this.handlers[id] // Retrieve the packet handler
-                    handler(data) // Invoke the packet handler
-                } else {
-                    // Send a warning to the console saying that there's no handler
-                    console.warn(`Don't know how to handle packet with id (${id.toString(16)})`)
-                }
+                this.handlePacket(packet)
             } catch (e) {
                 console.error(e)
             }
@@ -154,7 +197,7 @@ export class SocketApi {
             if (data.mode === PlayerDataMode.SELF) { // If the mode is SELF
                 this.self.value = elm // Set the self player to the player data
             }
-        } else if (data.mode === PlayerDataMode.REMOVE) { // if the mode is REMOVE
+        } else if (data.mode === PlayerDataMode.REMOVE || data.mode === PlayerDataMode.KICK) { // if the player left or was kicked
             delete this.players[data.id] // Remove the ID from the player map
         }
     }
@@ -175,6 +218,162 @@ export class SocketApi {
     }
 
 
+    /**
+     * Packet handler for DuplicateDevice packet (0x0A) warns the host
+     * that a player joined from the same device as another player
+     *
+     * @param data The ids of the two players
+     */
+    onDuplicateDevice(data: DuplicateDeviceData) {
+        const player = this.players[data.id]
+        const other = this.players[data.other]
+        if (player && other) {
+            toast(`"${player.name}" joined from the same device as "${other.name}"`, ToastMode.WARNING)
+        }
+    }
+
+    /**
+     * Packet handler for CapacityWarning packet (0x0E) warns the host
+     * that their game is nearly full
+     *
+     * @param data The number of players and the most the game allows
+     */
+    onCapacityWarning(data: CapacityData) {
+        toast(`The game is nearly full (${data.players} of ${data.max} players)`, ToastMode.WARNING)
+    }
+
+    /**
+     * Packet handler for GameFull packet (0x0F) tells the host that
+     * players are being turned away because their game is full
+     *
+     * @param data The number of players and the most the game allows
+     */
+    onGameFull(data: CapacityData) {
+        toast(`The game is full, players trying to join are being turned away (${data.max} players)`, ToastMode.WARNING)
+    }
+
+    /**
+     * Packet handler for FirstCorrect packet (0x12) announces the player
+     * who answered the question correctly first
+     *
+     * @param data The player and the bonus points they earned
+     */
+    onFirstCorrect(data: FirstCorrectData) {
+        const name = this.self.value?.id === data.id ? 'You' : data.name
+        toast(`${name} answered first and earned ${data.bonus} bonus points`)
+    }
+
+    /**
+     * Packet handler for Buzzer packet (0x13) stores the player whose
+     * turn it is to answer the buzzer question
+     *
+     * @param data The player answering or an empty id when nobody is
+     */
+    onBuzzer(data: BuzzerData) {
+        this.buzzer.value = data.id ? data : null
+    }
+
+    /**
+     * Packet handler for PollResults packet (0x14) stores the responses
+     * to a poll or word cloud question once it is over
+     *
+     * @param data The number of players that gave each response
+     */
+    onPollResults(data: PollResultsData) {
+        this.pollResults.value = data
+    }
+
+    /**
+     * Packet handler for RoundOver packet (0x15) stores the points each
+     * player earned in the quiz of the playlist that just finished
+     *
+     * @param data The scores of the quiz and the title of the next quiz
+     */
+    onRoundOver(data: RoundData) {
+        this.roundOver.value = data
+    }
+
+    /**
+     * Packet handler for WarmUp packet (0x16) stores the warm-up poll
+     * and the votes for each answer as they change
+     *
+     * @param data The question, answers and votes of the poll
+     */
+    onWarmUp(data: WarmUpData) {
+        this.warmUp.value = data
+    }
+
+    /**
+     * Packet handler for NextQuestion packet (0x18) stores the preview of
+     * the next question which the host is sent once a question is marked
+     *
+     * @param data The contents, kind, difficulty and notes of the next question
+     */
+    onNextQuestion(data: NextQuestionData) {
+        this.nextQuestion.value = data
+    }
+
+    /**
+     * Packet handler for Results packet (0x17) stores the final rankings
+     * of the players which the game over screen shows
+     *
+     * @param data The players ordered by their rank
+     */
+    onResults(data: ResultsData) {
+        this.results.value = data
+    }
+
+    /**
+     * Packet handler for Summary packet (0x11) stores the results of
+     * the game which the host is sent once the game is over
+     *
+     * @param data The results of the game
+     */
+    onSummary(data: SummaryData) {
+        this.summary.value = data
+    }
+
+    /**
+     * Packet handler for Settings packet (0x10) keeps the game data up
+     * to date when the host changes the settings of the game
+     *
+     * @param data The new settings of the game
+     */
+    onSettings(data: LobbySettings) {
+        const gameData = this.gameData.value
+        if (gameData) Object.assign(gameData, data)
+    }
+
+    /**
+     * Packet handler for StateSync packet (0x0B) restores the full state
+     * of a game that is being rejoined such as when the host reconnects
+     * or a display joins part way through the game
+     *
+     * @param data The current state of the game
+     */
+    onStateSync(data: StateSyncData) {
+        this.lastSeq = data.seq // Continue from the event the state was collected at
+        this.resyncing = false
+        this.gameState.value = data.state
+        this.onScores({scores: data.scores})
+        if (data.question) {
+            this.onQuestion(data.question)
+        }
+        if (data.total > 0) { // Continue the countdown from where the game is
+            this.handlers[SPID.TIME_SYNC]({total: data.total, remaining: data.remaining})
+        }
+    }
+
+    /**
+     * Packet handler for PlayerCount packet (0x0C) keeps track of the
+     * number of players in the game as players join and are removed
+     *
+     * @param data The number of players in the game
+     */
+    onPlayerCount(data: PlayerCountData) {
+        this.playerCount.value = data.count
+    }
+
     /**
      * Packet handler for GameState packet (0x05) handles keeping track
      * of the games state
@@ -192,6 +391,14 @@ export class SocketApi {
      * @param question The current question
      */
     onQuestion(question: QuestionData) {
+        if (question.presented) { // Presented questions are only shown on the display so only the answer count is sent
+            question.question = ''
+            question.answers = new Array(question.count ?? 0).fill('')
+        }
+        this.buzzer.value = null // Nobody has buzzed for the new question
+        this.pollResults.value = null // The new question hasn't been answered
+        this.warmUp.value = null // The warm-up poll is over once the questions start
+        this.nextQuestion.value = null // The previewed question is now being asked
         this.question.value = question // Set the question value
     }
 
@@ -209,6 +416,86 @@ export class SocketApi {
         router.push({name: 'Home'}).then().catch()
     }
 
+    /**
+     * Packet handler for the Kicked packet (0x0D) which is only sent to
+     * the player that the host kicked from the game
+     *
+     * @param data The kicked data contains the reason given by the host
+     */
+    onKicked(data: DisconnectData) {
+        dialog('Kicked', data.reason)
+        this.resetState()
+        router.push({name: 'Home'}).then().catch()
+    }
+
+    /**
+     * Invokes the handler for the provided packet
+     *
+     * @param packet The packet received from the server
+     */
+    handlePacket(packet: Packet) {
+        const id: SPID = packet.id // Get the packet id
+        const data: any = packet.data
+        if (id in this.handlers) {  // Check to make sure we have a handler for this packet id
+            const handler = this.handlers[id] // Retrieve the packet handler
+            handler(data) // Invoke the packet handler
+        } else {
+            // Send a warning to the console saying that there's no handler
+            console.warn(`Don't know how to handle packet with id (${id.toString(16)})`)
+        }
+    }
+
+    /**
+     * Packet handler for the Sequenced packet (0x1A) handles the events
+     * sent to everyone in the game in number order. When a number is
+     * skipped the server is asked for every event after the last one
+     * handled and events are ignored until those arrive
+     *
+     * @param data The number of the event and the event itself
+     */
+    onSequenced(data: SequencedData) {
+        if (data.seq <= this.lastSeq) return // The event was already handled
+        if (data.seq !== this.lastSeq + 1) { // An event was missed
+            if (!this.resyncing) {
+                this.resyncing = true
+                this.send(packets.resync(this.lastSeq))
+            }
+            return
+        }
+        this.lastSeq = data.seq
+        this.resyncing = false
+        this.handlePacket(data.packet)
+    }
+
+    /**
+     * Packet handler for the Snapshot packet (0x1B) recovers from an error
+     * without reconnecting using the compact snapshot of the game. The
+     * players are replaced keeping the scores that are already known
+     *
+     * @param data The compact snapshot of the game
+     */
+    onSnapshot(data: SnapshotData) {
+        this.lastSeq = data.seq // Continue from the event the snapshot was collected at
+        this.resyncing = false
+        this.gameState.value = data.state
+        const scores: Record<string, number> = {}
+        for (let key of Object.keys(this.players)) {
+            scores[key] = this.players[key].score
+            delete this.players[key]
+        }
+        for (let player of data.players) {
+            this.players[player.id] = {id: player.id, name: player.name, score: scores[player.id] ?? 0}
+        }
+        const self = this.self.value
+        if (self && data.score !== undefined) {
+            self.score = data.score
+            if (this.players[self.id]) this.players[self.id].score = data.score
+        }
+        if (data.total > 0) { // Continue the countdown from where the game is
+            this.handlers[SPID.TIME_SYNC]({total: data.total, remaining: data.remaining})
+        }
+    }
+
     /**
      * Clears the associated persisted state for this socket
      */
@@ -217,6 +504,16 @@ export class SocketApi {
         this.gameData.value = null
         this.question.value = null
         this.gameState.value = GameState.UNSET
+        this.playerCount.value = 0
+        this.summary.value = null
+        this.buzzer.value = null
+        this.pollResults.value = null
+        this.roundOver.value = null
+        this.warmUp.value = null
+        this.results.value = null
+        this.nextQuestion.value = null
+        this.lastSeq = 0
+        this.resyncing = false
         for (let key of Object.keys(this.players)) {
             delete this.players[key]
         }
@@ -230,7 +527,11 @@ export class SocketApi {
      */
     onError(data: ErrorData) {
         console.error(`An error occurred ${data.cause}`) // Print the error to the console
-        dialog('Error occurred', data.cause) // Display an error dialog
+        let content = data.cause
+        if (data.code === 'invalid_quiz') { // List every problem with the quiz
+            content += '\n\n' + (data.details as InvalidQuizDetails).problems.join('\n')
+        }
+        dialog('Error occurred', content) // Display an error dialog
     }
 
     /**
@@ -243,6 +544,8 @@ export class SocketApi {
         // Set the game data to the provided value
         this.gameData.value = data
         this.gameState.value = GameState.WAITING
+        this.lastSeq = data.seq ?? 0 // Only events after joining are sent
+        this.resyncing = false
     }
 
     /**
@@ -271,8 +574,9 @@ export class SocketApi {
      * Removes a player from the game (HOST ONLY)
      *
      * @param id The id of the player to kick
+     * @param reason Optional reason shown to the kicked player
      */
-    kick(id: string) {
+    kick(id: string, reason: string = '') {
         const player = this.players[id]
         if (player) { // If the player exists
             // Displayed a toast with the kicked message
@@ -280,7 +584,7 @@ export class SocketApi {
         }
         if (DEBUG) console.debug('Kicked player ' + id) // Print debug kicked message
         delete this.players[id] // Remove the player for the map
-        this.send(packets.kick(id)) // Send a kick player packet
+        this.send(packets.kick(id, reason)) // Send a kick player packet
     }
 }
 
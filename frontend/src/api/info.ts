@@ -0,0 +1,29 @@
+import { API_HOST } from "@/constants";
+import { TextStyle } from "@api/packets";
+
+// The public details of a game which are available before joining it
+export interface GameInfo extends TextStyle {
+    id: string;
+    title: string;
+    host?: string;
+    state: number;
+    players: number;
+    maxPlayers?: number;
+    questions: number;
+    joinable: boolean;
+    locales?: string[];
+}
+
+/**
+ * Retrieves the public details of the game with the provided code
+ * without opening a websocket
+ *
+ * @param code The game code
+ * @return The game details or null if the game doesn't exist
+ */
+export async function getGameInfo(code: string): Promise<GameInfo | null> {
+    const response = await fetch(`${API_HOST}/api/game/${code}/info`)
+    if (response.status === 404) return null
+    if (!response.ok) throw new Error(`Failed to load game info (${response.status})`)
+    return await response.json()
+}
@@ -3,16 +3,40 @@ import { GameState } from "@api/index";
 
 export interface ErrorData {
     cause: string;
+    code?: ErrorCode;
+    details?: any;
+}
+
+// Codes for errors which include details describing the error
+export type ErrorCode = 'invalid_name' | 'invalid_answer' | 'invalid_quiz' | 'game_full' | 'moderated' | 'already_answered' | 'stale_question'
+
+// The details of an invalid_quiz error listing every problem with the quiz
+export interface InvalidQuizDetails {
+    problems: string[];
 }
 
 export interface DisconnectData {
     reason: string;
 }
 
-export interface GameData {
+export interface GameData extends TextStyle {
     owner: boolean;
     id: string;
     title: string;
+    host?: string;
+    questions: number;
+    duration: number;
+    tooLong?: boolean;
+    warnings?: string[];
+    key?: string;
+    display?: boolean;
+    devices?: DeviceMode;
+    present?: boolean;
+    reveal?: RevealMode;
+    scoring?: ScoringMode;
+    maxPlayers?: number;
+    rounds?: string[];
+    seq?: number;
 }
 
 export interface PlayerData {
@@ -21,24 +45,153 @@ export interface PlayerData {
     score: number;
 }
 
-export type PlayerDataWithMode = PlayerData & { mode: PlayerDataMode }
+export type PlayerDataWithMode = PlayerData & { mode: PlayerDataMode, reason?: string }
 
 // The different possible values for player data packet modes
 export enum PlayerDataMode {
     ADD,
     REMOVE,
-    SELF
+    SELF,
+    KICK
 }
 
 export interface GameStateData {
     state: GameState
 }
 
-export interface QuestionData {
+export interface QuestionData extends TextStyle {
+    index?: number;
     image?: string;
     question: string;
     answers: string[];
     values?: number[];
+    marking?: MarkingMode;
+    minChoices?: number;
+    maxChoices?: number;
+    review?: number;
+    firstBonus?: number;
+    kind?: QuestionKind;
+    translations?: Record<string, Translation>;
+    difficulty?: Difficulty;
+    notes?: string;
+    numeric?: NumericData;
+    slider?: Slider;
+    blanks?: string[][];
+    patterns?: string[][];
+    blankCount?: number;
+    audio?: string;
+    presented?: boolean;
+    count?: number;
+}
+
+// The range of numbers players choose from when answering a NUMBER question.
+// A step of zero allows any number
+export interface Slider {
+    min: number;
+    max: number;
+    step?: number;
+}
+
+// The answer to a NUMBER question which players earn every point for when they
+// are within the tolerance. With partial marking the points fall to nothing over
+// the margin past the tolerance. Only the slider is sent to the players
+export interface NumericData extends Slider {
+    answer: number;
+    tolerance?: number;
+    margin?: number;
+}
+
+// How hard a question is which is only shown to the host
+export type Difficulty = 'easy' | 'medium' | 'hard'
+
+// The different ways the answers to a question can be marked. Every mode
+// other than SINGLE lets players choose more than one answer
+export enum MarkingMode {
+    SINGLE,
+    EXACT,
+    PARTIAL,
+    PENALTY
+}
+
+// The text of a question in another language with the answers in the same order
+// How the text of a quiz should be shown. The direction is chosen by the server
+// from the locale when the quiz doesn't set one
+export interface TextStyle {
+    locale?: string;
+    direction?: TextDirection;
+    font?: string;
+}
+
+// The directions the text of a quiz can be written in
+export type TextDirection = 'ltr' | 'rtl'
+
+export interface Translation {
+    question: string;
+    answers?: string[];
+}
+
+// How players respond to a question. BUZZER questions are answered by the
+// first player to buzz and judged by the host. POLL and WORDS questions are
+// never marked, everyone is shown the responses instead. ORDER questions are
+// answered by putting every answer in order, NUMBER questions by choosing a
+// number on a slider and BLANKS questions by writing a word for each blank
+export enum QuestionKind {
+    CHOICE,
+    BUZZER,
+    POLL,
+    WORDS,
+    ORDER,
+    NUMBER,
+    BLANKS
+}
+
+// The different ways a game can handle players joining from the same device
+export enum DeviceMode {
+    ALLOWED,
+    WARN,
+    BLOCK
+}
+
+// What players are shown after each question is marked
+export enum RevealMode {
+    LEADERBOARD,
+    ANSWERS,
+    RESULT
+}
+
+// The different ways the points for each question are decided. EXAM removes
+// the timer and awards the same points no matter how long players take
+export enum ScoringMode {
+    TIMED,
+    EXAM
+}
+
+// Settings chosen by the host when creating a game
+export interface GameSettings {
+    devices: DeviceMode;
+    host: string;
+    present: boolean;
+    reveal: RevealMode;
+    scoring: ScoringMode;
+    maxPlayers: number;
+}
+
+// The settings the host can change from the lobby before the game starts
+export type LobbySettings = Omit<GameSettings, 'host'>
+
+// The settings used when the host doesn't choose any
+export const DEFAULT_SETTINGS: GameSettings = {
+    devices: DeviceMode.ALLOWED,
+    host: '',
+    present: false,
+    reveal: RevealMode.LEADERBOARD,
+    scoring: ScoringMode.TIMED,
+    maxPlayers: 0,
+}
+
+export interface DuplicateDeviceData {
+    id: string;
+    other: string;
 }
 
 export interface NameTakenResultData {
@@ -56,14 +209,122 @@ export interface TimeSyncData {
     remaining: number;
 }
 
+// The time in milliseconds the server recorded for an answer after the
+// question was sent which is the time used for the score
+export interface AnswerAcceptedData {
+    question: number;
+    elapsed: number;
+}
+
 export interface AnswerResultData {
     result: boolean;
+    values?: number[];
+    number?: number;
+    blanks?: string[];
+    score?: number;
 }
 
 export interface ScoresData {
     scores: Record<string, number>
 }
 
+export interface StateSyncData {
+    state: number;
+    scores: Record<string, number>;
+    question?: QuestionData;
+    total: number;
+    remaining: number;
+    results?: Record<number, boolean>;
+    seq: number;
+}
+
+export interface PlayerCountData {
+    count: number;
+}
+
+export interface CapacityData {
+    players: number;
+    max: number;
+}
+
+// How many players took part in a question when it was marked
+export interface QuestionStats {
+    question: number;
+    players: number;
+    answered: number;
+    stopped: number; // Players who answered the previous question but not this one
+    median: number; // The median time in milliseconds players took to answer
+    p90: number; // The time in milliseconds 90% of the players answered within
+    fastest?: string; // The name of the player who answered first
+    quickest: number; // The time in milliseconds the fastest player took to answer
+}
+
+export interface FirstCorrectData {
+    id: string;
+    name: string;
+    bonus: number;
+}
+
+// The player whose turn it is to answer a buzzer question (an empty id when nobody is answering)
+export interface BuzzerData {
+    id: string;
+    name: string;
+}
+
+// The responses to a poll (counts of each answer) or word cloud (counts of each response)
+export interface PollResultsData {
+    counts?: number[];
+    words?: Record<string, number>;
+}
+
+export interface SummaryData {
+    questions: QuestionStats[];
+    rounds?: RoundData[];
+}
+
+// The points each player earned in one quiz of a playlist along with the
+// title of the next quiz (empty after the last quiz). Players are only sent
+// the scores when everyone is shown the leaderboard
+export interface RoundData {
+    round: number;
+    title: string;
+    scores?: Record<string, number>;
+    next?: string;
+}
+
+// An unscored poll the host asks while waiting for players. The counts are
+// only sent to the host and displays until the poll is closed
+export interface WarmUpData {
+    question: string;
+    answers: string[];
+    counts?: number[];
+    closed?: boolean;
+}
+
+// A preview of the next question only sent to the host while the results
+// of the current question are shown so they can introduce it
+export interface NextQuestionData {
+    index: number;
+    question: string;
+    kind: QuestionKind;
+    difficulty?: Difficulty;
+    notes?: string;
+}
+
+// The final score and rank of a player computed by the server once the game
+// is over. Players with the same score share the same rank
+export interface PlayerResult {
+    id: string;
+    name: string;
+    score: number;
+    rank: number;
+    points: number[];
+}
+
+export interface ResultsData {
+    players: PlayerResult[];
+}
+
 // Packet ids can be either client or server packet ids
 type PacketId = number | CPID | SPID
 
@@ -72,6 +333,30 @@ export interface Packet {
     data?: any;
 }
 
+// An event sent to everyone in the game with its number. The numbers increase by
+// one each event so a gap means an event was missed
+export interface SequencedData {
+    seq: number;
+    packet: Packet;
+}
+
+export interface PlayerSummary {
+    id: string;
+    name: string;
+}
+
+// A compact summary of the game sent when asked for. Players are also
+// sent their own score
+export interface SnapshotData {
+    state: GameState;
+    question?: number;
+    total: number;
+    remaining: number;
+    score?: number;
+    players: PlayerSummary[];
+    seq: number;
+}
+
 // Represents the direction a packet is travelling to IN = inbounds packets OUT = outbound packets
 export enum Direction {
     IN,
@@ -124,7 +409,25 @@ export enum SPID {
     TIME_SYNC,
     QUESTION,
     ANSWER_RESULT,
-    SCORES
+    SCORES,
+    DUPLICATE_DEVICE,
+    STATE_SYNC,
+    PLAYER_COUNT,
+    KICKED,
+    CAPACITY_WARNING,
+    GAME_FULL,
+    SETTINGS,
+    SUMMARY,
+    FIRST_CORRECT,
+    BUZZER,
+    POLL_RESULTS,
+    ROUND_OVER,
+    WARM_UP,
+    RESULTS,
+    NEXT_QUESTION,
+    ANSWER_ACCEPTED,
+    SEQUENCED,
+    SNAPSHOT
 }
 
 
@@ -137,6 +440,15 @@ export enum CPID {
     STATE_CHANGE,
     ANSWER,
     KICK,
+    RECONNECT_HOST,
+    JOIN_DISPLAY,
+    SET_MAX_PLAYERS,
+    UPDATE_SETTINGS,
+    BUZZ,
+    JUDGE_BUZZ,
+    WARM_UP,
+    RESYNC,
+    REQUEST_SNAPSHOT,
 }
 
 // An enum containing different states the client can request
@@ -158,8 +470,36 @@ const constructors = {
      *
      * @param title The new game title
      * @param questions The questions for the game
+     * @param settings The settings for the game
+     * @param style How the text of the quiz should be shown
+     * @param playlist The ids of stored quizzes to play after this quiz
+     */
+    createGame: (title: string, questions: QuestionData[], settings: GameSettings = DEFAULT_SETTINGS, style: TextStyle = {}, playlist: string[] = []) => ({
+        id: CPID.CREATE_GAME,
+        data: {title, questions, ...settings, ...style, playlist}
+    }),
+    /**
+     * Creates a new game from a quiz stored on the server
+     *
+     * @param quiz The id of the stored quiz
+     * @param settings The settings for the game
+     * @param playlist The ids of stored quizzes to play after this quiz
+     */
+    createStoredGame: (quiz: string, settings: GameSettings = DEFAULT_SETTINGS, playlist: string[] = []) => ({
+        id: CPID.CREATE_GAME,
+        data: {quiz, ...settings, playlist}
+    }),
+    /**
+     * Creates a new game from a quiz sent as a resumable upload
+     *
+     * @param upload The id of the complete upload
+     * @param settings The settings for the game
+     * @param playlist The ids of stored quizzes to play after this quiz
      */
-    createGame: (title: string, questions: QuestionData[]) => ({id: CPID.CREATE_GAME, data: {title, questions}}),
+    createUploadedGame: (upload: string, settings: GameSettings = DEFAULT_SETTINGS, playlist: string[] = []) => ({
+        id: CPID.CREATE_GAME,
+        data: {upload, ...settings, playlist}
+    }),
     /**
      * Checks if the provided name is already in use
      *
@@ -180,8 +520,14 @@ const constructors = {
      *
      * @param id The id of the game to request to join
      * @param name The name of the player to play as
+     * @param audio Whether the questions should be read aloud
+     * @param compact Whether to only receive the answer count for each question to save data
+     * @param locale The language to receive the questions in or empty for the original
      */
-    requestJoin: (id: string, name: string) => ({id: CPID.REQUEST_JOIN, data: {id, name}}),
+    requestJoin: (id: string, name: string, audio: boolean = false, compact: boolean = false, locale: string = '') => ({
+        id: CPID.REQUEST_JOIN,
+        data: {id, name, audio, compact, locale}
+    }),
     /**
      * Requests the server to change a specific game state
      *
@@ -193,17 +539,117 @@ const constructors = {
      * Tells the server which answer this player would like
      * to select
      *
+     * @param question The index of the question being answered
      * @param id The index of the answer to choose
      */
-    answer: (id: number) => ({id: CPID.ANSWER, data: {id}}),
+    answer: (question: number, id: number) => ({id: CPID.ANSWER, data: {question, id}}),
+    /**
+     * Tells the server which answers this player would like
+     * to select for questions that allow more than one answer
+     *
+     * @param question The index of the question being answered
+     * @param ids The indexes of the answers to choose
+     */
+    answerMany: (question: number, ids: number[]) => ({id: CPID.ANSWER, data: {question, id: 0, ids}}),
+    /**
+     * Tells the server the words this player wrote for a
+     * word cloud question
+     *
+     * @param question The index of the question being answered
+     * @param text The words the player wrote
+     */
+    answerWords: (question: number, text: string) => ({id: CPID.ANSWER, data: {question, id: 0, text}}),
+    /**
+     * Tells the server the number this player chose for a
+     * number question
+     *
+     * @param question The index of the question being answered
+     * @param number The number the player chose
+     */
+    answerNumber: (question: number, number: number) => ({id: CPID.ANSWER, data: {question, id: 0, number}}),
+    /**
+     * Tells the server the words this player wrote for each
+     * blank of a fill in the blanks question
+     *
+     * @param question The index of the question being answered
+     * @param blanks The words written for each blank in order
+     */
+    answerBlanks: (question: number, blanks: string[]) => ({id: CPID.ANSWER, data: {question, id: 0, blanks}}),
+    /**
+     * Votes for an answer of the warm-up poll while waiting
+     * for the game to start
+     *
+     * @param id The index of the answer to vote for
+     */
+    voteWarmUp: (id: number) => ({id: CPID.ANSWER, data: {id}}),
     /**
      * Kicks the player with the provided id from the game
      * this will only work if the player sending it is the
      * host
      *
      * @param id The id of the player to remove
+     * @param reason Optional reason shown to the kicked player
+     */
+    kick: (id: string, reason: string = '') => ({id: CPID.KICK, data: {id, reason}}),
+    /**
+     * Reconnects the host to their game after their connection
+     * was lost using the host key sent when the game was created
+     *
+     * @param id The id of the game to reconnect to
+     * @param key The host key of the game
+     */
+    reconnectHost: (id: string, key: string) => ({id: CPID.RECONNECT_HOST, data: {id, key}}),
+    /**
+     * Connects as a read-only display of the host view of a game
+     * such as the screen shown on a projector
+     *
+     * @param id The id of the game to display
+     * @param key The host key of the game
+     */
+    joinDisplay: (id: string, key: string) => ({id: CPID.JOIN_DISPLAY, data: {id, key}}),
+    /**
+     * Changes the most players that can join the game (Host only)
+     *
+     * @param value The most players that can join
+     */
+    setMaxPlayers: (value: number) => ({id: CPID.SET_MAX_PLAYERS, data: {value}}),
+    /**
+     * Replaces the settings of the game before it starts (Host only)
+     *
+     * @param settings The new settings for the game
+     */
+    updateSettings: (settings: LobbySettings) => ({id: CPID.UPDATE_SETTINGS, data: settings}),
+    /**
+     * Buzzes to answer the current buzzer question
+     */
+    buzz: {id: CPID.BUZZ, data: {}},
+    /**
+     * Judges the answer of the player whose turn it is to answer
+     * the buzzer question (Host only)
+     *
+     * @param correct Whether the player answered correctly
+     */
+    judgeBuzz: (correct: boolean) => ({id: CPID.JUDGE_BUZZ, data: {correct}}),
+    /**
+     * Asks the players an unscored poll while waiting for the game
+     * to start (Host only). Players vote using voteWarmUp
+     *
+     * @param question The question to ask or empty to end the poll
+     * @param answers The answers the players can vote for (e.g. emoji)
+     */
+    warmUp: (question: string, answers: string[] = []) => ({id: CPID.WARM_UP, data: {question, answers}}),
+    /**
+     * Asks the server for every event sent to everyone after
+     * the provided number when an event was missed
+     *
+     * @param seq The number of the last event that was handled
+     */
+    resync: (seq: number) => ({id: CPID.RESYNC, data: {seq}}),
+    /**
+     * Asks the server for a compact snapshot of the game to
+     * recover from an error without reconnecting
      */
-    kick: (id: string) => ({id: CPID.KICK, data: {id}}),
+    requestSnapshot: {id: CPID.REQUEST_SNAPSHOT, data: {}},
 }
 
 export default constructors
\ No newline at end of file
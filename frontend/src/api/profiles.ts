@@ -0,0 +1,21 @@
+import { API_HOST } from "@/constants";
+import { LobbySettings } from "@api/packets";
+
+// A named set of settings defined by the server that pre-fills the
+// settings of a new game
+export type Profile = LobbySettings & {
+    name: string;
+    description: string;
+}
+
+/**
+ * Retrieves the settings profiles the server offers to hosts
+ *
+ * @return The profiles in the order they should be listed
+ */
+export async function getProfiles(): Promise<Profile[]> {
+    const response = await fetch(`${API_HOST}/api/profiles`)
+    if (!response.ok) throw new Error(`Failed to load profiles (${response.status})`)
+    const data: { profiles: Profile[] } = await response.json()
+    return data.profiles
+}
@@ -0,0 +1,64 @@
+import { API_HOST } from "@/constants";
+
+// The size in bytes of each chunk sent to the server
+const CHUNK_SIZE = 256 * 1024
+// The number of times in a row a chunk can fail before the upload is abandoned
+const MAX_RETRIES = 5
+// The time in milliseconds to wait before retrying a failed chunk
+const RETRY_DELAY = 2000
+
+/**
+ * Reads the number of bytes the server has received from the
+ * Upload-Offset header of the provided response
+ *
+ * @param response The response from the upload endpoint
+ */
+function readOffset(response: Response): number {
+    const offset = parseInt(response.headers.get('Upload-Offset') ?? '')
+    if (isNaN(offset)) throw new Error('Upload response is missing its offset')
+    return offset
+}
+
+/**
+ * Uploads the provided data to the server in chunks using resumable
+ * uploads. Chunks that fail are retried from the last byte the server
+ * received so a dropped connection doesn't restart the whole upload
+ *
+ * @param data The data to upload
+ * @param progress Called with the number of bytes sent after each chunk
+ * @return The id of the complete upload
+ */
+export async function upload(data: Blob, progress?: (sent: number, total: number) => any): Promise<string> {
+    const created = await fetch(`${API_HOST}/api/upload`, {
+        method: 'POST',
+        headers: {'Upload-Length': data.size.toString()}
+    })
+    if (!created.ok) throw new Error(`Failed to start upload (${created.status})`)
+    const {id} = await created.json() as { id: string }
+    const url = `${API_HOST}/api/upload/${id}`
+    let offset = 0
+    let retries = 0
+    while (offset < data.size) {
+        try {
+            const response = await fetch(url, {
+                method: 'PATCH',
+                headers: {
+                    'Upload-Offset': offset.toString(),
+                    'Content-Type': 'application/offset+octet-stream'
+                },
+                body: data.slice(offset, offset + CHUNK_SIZE)
+            })
+            if (!response.ok) throw new Error(`Failed to upload chunk (${response.status})`)
+            offset = readOffset(response)
+            retries = 0
+            if (progress) progress(offset, data.size)
+        } catch (e) {
+            if (++retries > MAX_RETRIES) throw e
+            await new Promise(resolve => setTimeout(resolve, RETRY_DELAY))
+            // Ask the server how much it received before continuing
+            const response = await fetch(url, {method: 'HEAD'}).catch(() => null)
+            if (response && response.ok) offset = readOffset(response)
+        }
+    }
+    return id
+}
@@ -0,0 +1,30 @@
+import { API_HOST } from "@/constants";
+import { QuestionData, TextStyle } from "@api/packets";
+
+// The result of checking a quiz without creating a game
+export interface ValidationResult {
+    problems: string[]; // Problems that stop the quiz from being played
+    warnings: string[]; // Problems that don't stop the quiz from being played
+}
+
+/**
+ * Checks the quiz on the server without storing anything so problems
+ * can be shown while the quiz is being written
+ *
+ * @param title The title of the quiz
+ * @param questions The questions of the quiz
+ * @param style How the text of the quiz should be shown
+ * @return The problems and warnings with the quiz
+ */
+export async function validateQuiz(title: string, questions: QuestionData[], style: TextStyle = {}): Promise<ValidationResult> {
+    const response = await fetch(`${API_HOST}/api/quiz/validate`, {
+        method: 'POST',
+        headers: {'Content-Type': 'application/json'},
+        body: JSON.stringify({title, questions, ...style})
+    })
+    if (response.status !== 200 && response.status !== 400) {
+        throw new Error(`Failed to validate quiz (${response.status})`)
+    }
+    const data = await response.json()
+    return {problems: data.problems ?? [], warnings: data.warnings ?? []}
+}
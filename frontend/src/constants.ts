@@ -28,12 +28,47 @@ function getHost(value: string | undefined): string {
 
 // The websocket host url
 export const HOST: string = getHost(import.meta.env.VITE_HOST)
+// The base url of the HTTP API which is served alongside the websocket
+export const API_HOST: string = HOST.replace(/^ws/, 'http').replace(/\/ws$/, '')
 // Whether to do debug logging
 export const DEBUG: boolean = import.meta.env.VITE_DEBUG == 'true'
 // The maximum amount of questions a user can create
 export const MAX_QUESTIONS = 16
 // The maximum amount of answers each question can have
 export const MAX_ANSWERS = 9
+// The longest time in seconds a question can show its results for
+export const MAX_REVIEW = 60
+// The most bonus points a question can award the first player to answer correctly
+export const MAX_FIRST_BONUS = 1000
+// The most characters a player can write for a word cloud question
+export const MAX_WORD_LENGTH = 32
+// The most characters the name of the font of a quiz can have
+export const MAX_FONT_LENGTH = 64
+// The most characters the host notes of a question can have
+export const MAX_NOTES = 500
+// The most blanks a fill in the blanks question can have
+export const MAX_BLANKS = 5
+// The text written in a fill in the blanks question for each blank
+export const BLANK_MARKER = '___'
+// The size in bytes above which quizzes are sent as a resumable upload
+// instead of inside the create game packet
+export const UPLOAD_THRESHOLD = 1024 * 1024
+// The time in milliseconds to wait after the quiz stops changing before
+// checking it with the server
+export const VALIDATE_DELAY = 1000
+// The shape and color used to identify each answer in presentation mode where
+// players only see answer buttons and the answers are shown on the display
+export const ANSWER_SHAPES: { shape: string, color: string }[] = [
+    {shape: '▲', color: '#e21b3c'},
+    {shape: '◆', color: '#1368ce'},
+    {shape: '●', color: '#d89e00'},
+    {shape: '■', color: '#26890c'},
+    {shape: '★', color: '#864cbf'},
+    {shape: '♥', color: '#0aa3a3'},
+    {shape: '✚', color: '#c8501e'},
+    {shape: '⬟', color: '#46178f'},
+    {shape: '⬢', color: '#5a6b00'},
+]
 
 // Debug log the current host
 console.debug(`Web socket host is ${HOST}`)
\ No newline at end of file
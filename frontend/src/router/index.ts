@@ -7,6 +7,7 @@ import CreateQuestion from "@page/CreateQuestion.vue"
 import Overview from "@page/Overview.vue"
 import Game from "@page/Game.vue"
 import GameOver from "@page/GameOver.vue"
+import Display from "@page/Display.vue"
 
 
 export const router = createRouter({
@@ -51,6 +52,11 @@ export const router = createRouter({
             name: 'GameOver',
             path: '/game-over',
             component: GameOver
+        },
+        {
+            name: 'Display',
+            path: '/display',
+            component: Display
         }
     ]
 })
@@ -1,14 +1,18 @@
-import { QuestionData } from "@api/packets";
+import { DEFAULT_SETTINGS, GameSettings, QuestionData, TextStyle } from "@api/packets";
 import { reactive } from "vue";
 
 // The structure of this store
 interface State {
     questions: QuestionData[];
-    title: string
+    title: string;
+    settings: GameSettings;
+    style: TextStyle;
 }
 
 // A central store for storing the creating information
 export const store = reactive<State>({
     questions: [],
     title: '',
+    settings: {...DEFAULT_SETTINGS},
+    style: {},
 })
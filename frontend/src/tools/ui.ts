@@ -1,4 +1,5 @@
 import { reactive, Ref, ref, UnwrapNestedRefs } from "vue";
+import { TextStyle } from "@api/packets";
 
 // Enum for choosing what type of toast message should be displayed
 export enum ToastMode {
@@ -97,4 +98,18 @@ export async function confirmDialog(title: string, content: string): Promise<boo
 export function loading(value: boolean, message: string = 'Loading...') {
     loader.visible = value
     loader.message = message
-}
\ No newline at end of file
+}
+/**
+ * Creates the attributes for an element showing text of the quiz so that
+ * right to left quizzes and quizzes which need a particular font render
+ * correctly. Used with v-bind on the element
+ *
+ * @param style The style of the text or null to let the browser decide
+ */
+export function textAttrs(style?: TextStyle | null) {
+    return {
+        dir: style?.direction ?? 'auto',
+        lang: style?.locale,
+        style: style?.font ? {fontFamily: `"${style.font}", sans-serif`} : undefined
+    }
+}